@@ -87,6 +87,19 @@ pub fn command(attr: TokenStream, input: TokenStream) -> TokenStream {
 }
 
 fn struct_derive_from_args(ident: &Ident, struct_data: &DataStruct) -> TokenStream2 {
+    let target_index = match struct_data.fields {
+        Fields::Named(ref fields) => fields.named.iter().position(|field| {
+            field
+                .ident
+                .as_ref()
+                .is_some_and(|ident| ident == "safe_username" || ident == "username")
+        }),
+        _ => None,
+    };
+    let target_index = match target_index {
+        Some(index) => quote! {Some(#index)},
+        None => quote! {None},
+    };
     let (unfold, idents, syntax, type_signature, typed_syntax) = match struct_data.fields {
         Fields::Named(ref fields) => {
             let idents = fields.named.iter().map(|field| &field.ident);
@@ -177,6 +190,7 @@ fn struct_derive_from_args(ident: &Ident, struct_data: &DataStruct) -> TokenStre
         const TYPE_SIGNATURE: &'static str = concat!(stringify!(#ident), " { ", #type_signature, " }");
         const SYNTAX: &'static str = #syntax;
         const TYPED_SYNTAX: &'static str = #typed_syntax;
+        const TARGET_INDEX: Option<usize> = #target_index;
     }
 }
 
@@ -0,0 +1,11 @@
+use crate::models::location::IpLocation;
+use async_trait::async_trait;
+use std::net::IpAddr;
+
+#[async_trait]
+pub trait GeolocationProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Returns `None` if the provider doesn't know the address.
+    async fn lookup(&self, ip_address: IpAddr) -> anyhow::Result<Option<IpLocation>>;
+}
@@ -1,4 +1,7 @@
+use crate::adapters::geolocation::GeolocationProvider;
 use crate::common::error::ServiceResult;
+use crate::models::location::IpLocation;
+use async_trait::async_trait;
 use serde::Deserialize;
 use std::fmt::Display;
 use std::net::IpAddr;
@@ -25,3 +28,24 @@ pub async fn get_ip_info(ip: IpAddr) -> ServiceResult<IPLocation> {
     let location: IPLocation = reqwest::get(url).await?.json().await?;
     Ok(location)
 }
+
+/// Resolves locations through ip-api.com, used as a fallback for the local database.
+pub struct IpApiProvider;
+
+#[async_trait]
+impl GeolocationProvider for IpApiProvider {
+    fn name(&self) -> &'static str {
+        "ip_api"
+    }
+
+    async fn lookup(&self, ip_address: IpAddr) -> anyhow::Result<Option<IpLocation>> {
+        let location = get_ip_info(ip_address)
+            .await
+            .map_err(|e| anyhow::anyhow!("ip-api request failed: {e:?}"))?;
+        Ok(Some(IpLocation {
+            country_code: location.country_code,
+            latitude: location.latitude,
+            longitude: location.longitude,
+        }))
+    }
+}
@@ -0,0 +1,46 @@
+use crate::adapters::geolocation::GeolocationProvider;
+use crate::models::location::IpLocation;
+use async_trait::async_trait;
+use maxminddb::{MaxMindDBError, Reader, geoip2};
+use std::net::IpAddr;
+use std::path::Path;
+
+/// Resolves locations from a local GeoLite2-style City database.
+pub struct MmdbProvider {
+    reader: Reader<Vec<u8>>,
+}
+
+impl MmdbProvider {
+    pub fn open<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let reader = Reader::open_readfile(path)?;
+        Ok(Self { reader })
+    }
+}
+
+#[async_trait]
+impl GeolocationProvider for MmdbProvider {
+    fn name(&self) -> &'static str {
+        "mmdb"
+    }
+
+    async fn lookup(&self, ip_address: IpAddr) -> anyhow::Result<Option<IpLocation>> {
+        let city: geoip2::City = match self.reader.lookup(ip_address) {
+            Ok(city) => city,
+            Err(MaxMindDBError::AddressNotFoundError(_)) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let country_code = city
+            .country
+            .and_then(|country| country.iso_code)
+            .map(str::to_owned);
+        let (latitude, longitude) = match city.location {
+            Some(location) => (location.latitude, location.longitude),
+            None => (None, None),
+        };
+        Ok(Some(IpLocation {
+            country_code,
+            latitude: latitude.map(|latitude| latitude as f32),
+            longitude: longitude.map(|longitude| longitude as f32),
+        }))
+    }
+}
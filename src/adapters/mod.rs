@@ -1,4 +1,7 @@
 pub mod beatmaps_service;
 pub mod discord;
+pub mod geolocation;
 pub mod ip_api;
+pub mod mmdb;
 pub mod performance_service;
+pub mod push_gateway;
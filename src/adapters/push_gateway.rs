@@ -0,0 +1,21 @@
+use crate::settings::AppSettings;
+use std::sync::LazyLock;
+
+static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| reqwest::Client::new());
+
+/// Replaces the metrics of this component on the prometheus push gateway.
+pub async fn push(metrics: String) -> anyhow::Result<()> {
+    let settings = AppSettings::get();
+    let Some(base_url) = &settings.metrics.push_gateway_url else {
+        return Ok(());
+    };
+
+    let url = format!("{base_url}/metrics/job/{}", settings.app_component);
+    CLIENT
+        .put(url)
+        .body(metrics)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
@@ -0,0 +1,13 @@
+use crate::common::metrics;
+use crate::common::state::AppState;
+use crate::usecases;
+use axum::extract::State;
+use tracing::error;
+
+pub async fn get_metrics(State(state): State<AppState>) -> String {
+    if let Err(e) = usecases::metrics::record_online_state(&state).await {
+        error!("Failed to record online state metrics: {e:?}");
+    }
+    metrics::record_pool_utilization(&state);
+    metrics::render()
+}
@@ -1,10 +1,13 @@
 mod health;
+mod metrics;
 pub mod osu;
 pub mod v1;
+pub mod v2;
 
 use crate::common::axum_ip::IpAddrInfo;
 use crate::common::context::Context;
 use crate::common::redis_pool::RedisPool;
+use crate::common::shutdown;
 use crate::common::state::AppState;
 use crate::lifecycle;
 use crate::models::bancho::BanchoResponse;
@@ -15,8 +18,13 @@ use axum::http::request::Parts;
 use axum::routing::{get, post};
 use sqlx::{MySql, Pool};
 use std::net::SocketAddr;
+use std::time::Duration;
 use tokio::net::TcpListener;
-use tracing::info;
+use tracing::{info, warn};
+
+/// How long long-lived connections get to hand their clients off after in-flight
+/// requests were drained, kept well below the pod's termination grace period.
+const CONNECTION_DRAIN_TIMEOUT: Duration = Duration::from_secs(10);
 
 pub async fn serve(settings: &AppSettings) -> anyhow::Result<()> {
     let state = lifecycle::initialize_state(&settings).await?;
@@ -26,12 +34,20 @@ pub async fn serve(settings: &AppSettings) -> anyhow::Result<()> {
     let app = Router::new()
         .merge(router())
         .route("/_health", get(health::health_check))
+        .route("/metrics", get(metrics::get_metrics))
         .with_state(state);
     axum::serve(
         listener,
         app.into_make_service_with_connect_info::<SocketAddr>(),
     )
+    .with_graceful_shutdown(lifecycle::shutdown_signal())
     .await?;
+
+    // upgraded websockets are not tracked by axum
+    if !shutdown::wait_for_connections(CONNECTION_DRAIN_TIMEOUT).await {
+        warn!("Timed out waiting for connections to close");
+    }
+    info!("Shutdown complete");
     Ok(())
 }
 
@@ -45,7 +61,9 @@ pub struct RequestContext {
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/", post(osu::bancho::controller).get(osu::bancho::index))
+        .route("/ws", get(osu::websocket::controller))
         .nest("/api/v1", v1::router())
+        .nest("/api/v2", v2::router())
 }
 
 impl FromRequestParts<AppState> for RequestContext {
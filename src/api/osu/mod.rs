@@ -1 +1,2 @@
 pub mod bancho;
+pub mod websocket;
@@ -0,0 +1,178 @@
+use crate::api::RequestContext;
+use crate::common::error::AppError;
+use crate::common::shutdown;
+use crate::common::state::AppState;
+use crate::events;
+use crate::lifecycle;
+use crate::models::sessions::Session;
+use crate::settings::AppSettings;
+use crate::usecases::{bancho_settings, sessions, streams};
+use axum::extract::Query;
+use axum::extract::ws::{Message as WsMessage, WebSocket, WebSocketUpgrade};
+use axum::http::HeaderMap;
+use axum::response::{IntoResponse, Response};
+use bancho_protocol::messages::Message;
+use bancho_protocol::messages::server::{Alert, Restart};
+use serde::Deserialize;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{error, info};
+use uuid::Uuid;
+
+/// How long a single blocking read on the session's streams may take.
+/// Joining a stream wakes up the read of websocket sessions,
+/// so that the new stream is read from right away.
+const PUSH_TIMEOUT: Duration = Duration::from_secs(5);
+const PUSH_BUFFER_SIZE: usize = 64;
+
+#[derive(Deserialize)]
+pub struct WebSocketArgs {
+    /// Browsers cannot set headers on websocket requests,
+    /// so the cho-token may also be passed as a query parameter.
+    pub token: Option<Uuid>,
+}
+
+/// WebSocket transport for the osu! bancho protocol.
+/// Accepts the same framed events as the polling endpoint
+/// and pushes stream data as soon as it arrives.
+pub async fn controller(
+    ctx: RequestContext,
+    headers: HeaderMap,
+    Query(args): Query<WebSocketArgs>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    let session_id = headers
+        .get("osu-token")
+        .and_then(|token| token.to_str().ok())
+        .and_then(|token| Uuid::parse_str(token).ok())
+        .or(args.token);
+    let Some(session_id) = session_id else {
+        return AppError::Unauthorized.into_response();
+    };
+
+    let session = match sessions::fetch_one(&ctx, session_id).await {
+        Ok(session) => session,
+        Err(e) => return e.into_response(),
+    };
+    ws.on_upgrade(move |socket| handle_socket(ctx, session, socket))
+}
+
+async fn handle_socket(ctx: RequestContext, mut session: Session, mut socket: WebSocket) {
+    info!(user_id = session.user_id, "User connected via websocket.");
+    let _connection = shutdown::track_connection();
+    let (push_tx, mut push_rx) = mpsc::channel(PUSH_BUFFER_SIZE);
+    let pusher = tokio::spawn(push_pending_data(
+        AppState::from_ctx(&ctx),
+        session.session_id,
+        push_tx,
+    ));
+
+    loop {
+        tokio::select! {
+            message = socket.recv() => match message {
+                Some(Ok(WsMessage::Binary(request_data))) => {
+                    session = match sessions::extend(&ctx, session.session_id).await {
+                        Ok(session) => session,
+                        Err(_) => {
+                            let _ = socket.send(WsMessage::Binary(restart_data().into())).await;
+                            break;
+                        }
+                    };
+
+                    let (response_data, logged_out) =
+                        match events::handle_event_data(&ctx, &mut session, &request_data).await {
+                            Ok(res) => res,
+                            Err(e) => (Message::serialize(Alert { message: e.message() }), false),
+                        };
+                    if !response_data.is_empty()
+                        && socket.send(WsMessage::Binary(response_data.into())).await.is_err()
+                    {
+                        break;
+                    }
+                    if logged_out {
+                        break;
+                    }
+                }
+                Some(Ok(WsMessage::Close(_))) | Some(Err(_)) | None => break,
+                // pings are answered automatically, text frames are not part of the protocol
+                Some(Ok(_)) => {}
+            },
+            // the client reconnects to one of the remaining replicas
+            _ = shutdown::wait() => {
+                let restart_data = events::shutdown_restart_data();
+                let _ = socket.send(WsMessage::Binary(restart_data.into())).await;
+                let _ = socket.send(WsMessage::Close(None)).await;
+                break;
+            }
+            pending_data = push_rx.recv() => match pending_data {
+                Some(pending_data) => {
+                    if socket.send(WsMessage::Binary(pending_data.into())).await.is_err() {
+                        break;
+                    }
+                }
+                // the pusher has stopped, the session is gone
+                None => break,
+            },
+        }
+    }
+
+    pusher.abort();
+    if let Err(e) = streams::remove_push_active(&ctx, session.session_id).await {
+        error!("Failed to unmark websocket session: {e:?}");
+    }
+    info!(
+        user_id = session.user_id,
+        "User disconnected from websocket."
+    );
+}
+
+/// Forwards stream data of the session as soon as it arrives.
+/// Keeps the session alive for as long as the socket is connected.
+async fn push_pending_data(ctx: AppState, session_id: Uuid, push_tx: mpsc::Sender<Vec<u8>>) {
+    let settings = AppSettings::get();
+    let mut redis = match lifecycle::connect_redis_blocking(settings, PUSH_TIMEOUT).await {
+        Ok(redis) => redis,
+        Err(e) => {
+            error!("Failed to open blocking redis connection: {e:?}");
+            return;
+        }
+    };
+
+    loop {
+        let session = match sessions::extend(&ctx, session_id).await {
+            Ok(session) => session,
+            Err(AppError::SessionsNotFound) => {
+                let _ = push_tx.send(restart_data()).await;
+                return;
+            }
+            Err(e) => {
+                error!("Failed to extend websocket session: {e:?}");
+                return;
+            }
+        };
+
+        // outlives a single read, so joins between two reads still wake up the next one
+        if let Err(e) = streams::set_push_active(&ctx, session_id, PUSH_TIMEOUT * 2).await {
+            error!("Failed to mark websocket session: {e:?}");
+            return;
+        }
+        match streams::wait_for_pending_data(&mut redis, &session, PUSH_TIMEOUT).await {
+            Ok(pending_data) if pending_data.is_empty() => {}
+            Ok(pending_data) => {
+                if push_tx.send(pending_data).await.is_err() {
+                    return;
+                }
+            }
+            Err(e) => {
+                error!("Failed to read pending data for websocket: {e:?}");
+                return;
+            }
+        }
+    }
+}
+
+fn restart_data() -> Vec<u8> {
+    Message::serialize(Restart {
+        milliseconds: bancho_settings::cached().reconnect_delay,
+    })
+}
@@ -2,6 +2,7 @@ use crate::api::RequestContext;
 use crate::common::error::{AppError, ServiceResponse};
 use crate::entities::bot;
 use crate::entities::channels::ChannelName;
+use crate::models::api_keys::ApiKeyScope;
 use crate::models::ripple::{
     BaseSuccessData, FetchPlayerMatchDetailsArgs, IsOnlineArgs, IsOnlineResponse, IsVerifiedArgs,
     OnlineUsersResponse, PlayerMatchDetailsResponse, SendChatbotDirectMessageArgs,
@@ -9,8 +10,7 @@ use crate::models::ripple::{
     VerifiedStatusResponse,
 };
 use crate::repositories::streams::StreamName;
-use crate::settings::AppSettings;
-use crate::usecases::{channels, ripple, sessions, streams, users};
+use crate::usecases::{api_keys, channels, ripple, sessions, streams, users};
 use axum::Json;
 use axum::extract::Query;
 use bancho_protocol::messages::server::ChatMessage;
@@ -78,10 +78,9 @@ pub async fn send_chatbot_message(
     ctx: RequestContext,
     Query(args): Query<SendChatbotMessageArgs>,
 ) -> ServiceResponse<BaseSuccessData> {
-    let settings = AppSettings::get();
-    if args.key != settings.app_ci_key {
-        return Err(AppError::Unauthorized);
-    }
+    api_keys::authenticate(&ctx, &args.key)
+        .await?
+        .require_scope(ApiKeyScope::Chatbot)?;
 
     if !args.channel.starts_with('#') {
         return Err(AppError::ChannelsInvalidName);
@@ -104,10 +103,9 @@ pub async fn send_chatbot_direct_message(
     ctx: RequestContext,
     Query(args): Query<SendChatbotDirectMessageArgs>,
 ) -> ServiceResponse<SendChatbotDirectMessageResponse> {
-    let settings = AppSettings::get();
-    if args.key != settings.app_ci_key {
-        return Err(AppError::Unauthorized);
-    }
+    api_keys::authenticate(&ctx, &args.key)
+        .await?
+        .require_scope(ApiKeyScope::Chatbot)?;
 
     let recipient_sessions: Vec<_> = sessions::fetch_by_user_id(&ctx, args.user_id)
         .await?
@@ -0,0 +1,26 @@
+use crate::api::RequestContext;
+use crate::api::v2::admin::ApiAuth;
+use crate::common::error::ServiceResponse;
+use crate::models::admin::AuditLogQuery;
+use crate::models::api_keys::ApiKeyScope;
+use crate::models::audit_logs::AuditLog;
+use crate::usecases::audit_logs;
+use axum::Json;
+use axum::extract::Query;
+
+const DEFAULT_LIMIT: u32 = 50;
+const MAX_LIMIT: u32 = 500;
+
+pub async fn fetch(
+    ctx: RequestContext,
+    ApiAuth(api_key): ApiAuth,
+    Query(args): Query<AuditLogQuery>,
+) -> ServiceResponse<Vec<AuditLog>> {
+    api_key.require_scope(ApiKeyScope::Audit)?;
+    let limit = args.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+    let logs = match args.user_id {
+        Some(user_id) => audit_logs::fetch_by_user_id(&ctx, user_id, limit).await?,
+        None => audit_logs::fetch_recent(&ctx, limit).await?,
+    };
+    Ok(Json(logs))
+}
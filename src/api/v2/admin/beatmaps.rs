@@ -0,0 +1,84 @@
+use crate::adapters::discord;
+use crate::api::RequestContext;
+use crate::api::v2::admin::{ApiAuth, audited};
+use crate::common::error::ServiceResponse;
+use crate::models::admin::{MapStatusArgs, MapStatusResponse};
+use crate::models::api_keys::ApiKeyScope;
+use crate::usecases::beatmaps;
+use axum::Json;
+use axum::extract::Path;
+
+pub async fn change_map_status(
+    ctx: RequestContext,
+    ApiAuth(api_key): ApiAuth,
+    Path(beatmap_id): Path<i32>,
+    Json(args): Json<MapStatusArgs>,
+) -> ServiceResponse<MapStatusResponse> {
+    let arguments = format!("{beatmap_id} {:?}", args.status);
+    audited(
+        &ctx,
+        &api_key,
+        "map_status",
+        None,
+        Some(&arguments),
+        async {
+            api_key.require_scope(ApiKeyScope::Beatmaps)?;
+            let (beatmap, previous_status) =
+                beatmaps::change_map_status(&ctx, beatmap_id, args.status).await?;
+            let _ = discord::send_ranked_maps_embed(
+                &beatmap,
+                previous_status,
+                &api_key.name,
+                api_key.created_by,
+            )
+            .await;
+            Ok(MapStatusResponse {
+                beatmap_id: beatmap.beatmap_id,
+                beatmapset_id: beatmap.beatmapset_id,
+                previous_status,
+                status: beatmap.ranked_status,
+            })
+        },
+    )
+    .await
+}
+
+/// Only returns the beatmaps whose status actually changed.
+pub async fn change_set_status(
+    ctx: RequestContext,
+    ApiAuth(api_key): ApiAuth,
+    Path(beatmapset_id): Path<i32>,
+    Json(args): Json<MapStatusArgs>,
+) -> ServiceResponse<Vec<MapStatusResponse>> {
+    let arguments = format!("{beatmapset_id} {:?}", args.status);
+    audited(
+        &ctx,
+        &api_key,
+        "set_status",
+        None,
+        Some(&arguments),
+        async {
+            api_key.require_scope(ApiKeyScope::Beatmaps)?;
+            let mut response = vec![];
+            for (beatmap, previous_status) in
+                beatmaps::change_set_status(&ctx, beatmapset_id, args.status).await?
+            {
+                let _ = discord::send_ranked_maps_embed(
+                    &beatmap,
+                    previous_status,
+                    &api_key.name,
+                    api_key.created_by,
+                )
+                .await;
+                response.push(MapStatusResponse {
+                    beatmap_id: beatmap.beatmap_id,
+                    beatmapset_id: beatmap.beatmapset_id,
+                    previous_status,
+                    status: beatmap.ranked_status,
+                });
+            }
+            Ok(response)
+        },
+    )
+    .await
+}
@@ -0,0 +1,46 @@
+use crate::api::RequestContext;
+use crate::api::v2::admin::{ApiAuth, audited};
+use crate::common::error::ServiceResponse;
+use crate::models::admin::MaintenanceStatus;
+use crate::models::api_keys::ApiKeyScope;
+use crate::usecases::bancho_settings;
+use axum::Json;
+use tracing::info;
+
+pub async fn fetch(
+    ctx: RequestContext,
+    ApiAuth(api_key): ApiAuth,
+) -> ServiceResponse<MaintenanceStatus> {
+    api_key.require_scope(ApiKeyScope::Maintenance)?;
+    let enabled = bancho_settings::in_maintenance_mode(&ctx).await?;
+    Ok(Json(MaintenanceStatus { enabled }))
+}
+
+pub async fn update(
+    ctx: RequestContext,
+    ApiAuth(api_key): ApiAuth,
+    Json(args): Json<MaintenanceStatus>,
+) -> ServiceResponse<MaintenanceStatus> {
+    let arguments = args.enabled.to_string();
+    audited(
+        &ctx,
+        &api_key,
+        "maintenance",
+        None,
+        Some(&arguments),
+        async {
+            api_key.require_scope(ApiKeyScope::Maintenance)?;
+            bancho_settings::set_maintenance(&ctx, args.enabled).await?;
+            if !args.enabled {
+                bancho_settings::clear_maintenance_window(&ctx).await?;
+            }
+            info!(
+                enabled = args.enabled,
+                api_key = api_key.name,
+                "Maintenance mode updated."
+            );
+            Ok(args)
+        },
+    )
+    .await
+}
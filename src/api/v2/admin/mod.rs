@@ -0,0 +1,86 @@
+pub mod audit_logs;
+pub mod beatmaps;
+pub mod maintenance;
+pub mod users;
+
+use crate::api::RequestContext;
+use crate::common::error::{AppError, ServiceResponse, ServiceResult};
+use crate::common::state::AppState;
+use crate::models::api_keys::ApiKey;
+use crate::usecases::{api_keys, audit_logs};
+use axum::extract::FromRequestParts;
+use axum::http::header::AUTHORIZATION;
+use axum::http::request::Parts;
+use axum::routing::{get, post, put};
+use axum::{Json, Router};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/users/{user_id}/kick", post(users::kick))
+        .route("/users/{user_id}/logout", post(users::logout))
+        .route("/users/{user_id}/alert", post(users::alert))
+        .route(
+            "/users/{user_id}/silence",
+            post(users::silence).delete(users::unsilence),
+        )
+        .route(
+            "/users/{user_id}/restrict",
+            post(users::restrict).delete(users::unrestrict),
+        )
+        .route(
+            "/users/{user_id}/ban",
+            post(users::ban).delete(users::unban),
+        )
+        .route(
+            "/users/{user_id}/freeze",
+            post(users::freeze).delete(users::unfreeze),
+        )
+        .route(
+            "/beatmaps/{beatmap_id}/status",
+            put(beatmaps::change_map_status),
+        )
+        .route(
+            "/beatmapsets/{beatmapset_id}/status",
+            put(beatmaps::change_set_status),
+        )
+        .route(
+            "/maintenance",
+            get(maintenance::fetch).put(maintenance::update),
+        )
+        .route("/audit-logs", get(audit_logs::fetch))
+}
+
+/// An API key authenticated through the `Authorization: Bearer <key>` header.
+pub struct ApiAuth(pub ApiKey);
+
+impl FromRequestParts<AppState> for ApiAuth {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let key = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or(AppError::Unauthorized)?;
+        let api_key = api_keys::authenticate(state, key.trim()).await?;
+        Ok(Self(api_key))
+    }
+}
+
+/// Performs an action of the API key and records its outcome in the audit log.
+async fn audited<T>(
+    ctx: &RequestContext,
+    api_key: &ApiKey,
+    action: &str,
+    target_user_id: Option<i64>,
+    arguments: Option<&str>,
+    perform: impl Future<Output = ServiceResult<T>>,
+) -> ServiceResponse<T> {
+    let result = perform.await;
+    audit_logs::record_api_action(ctx, api_key, action, target_user_id, arguments, &result).await;
+    result.map(Json)
+}
@@ -0,0 +1,299 @@
+use crate::adapters::discord;
+use crate::api::RequestContext;
+use crate::api::v2::admin::{ApiAuth, audited};
+use crate::common::context::Context;
+use crate::common::error::{ServiceResponse, ServiceResult};
+use crate::common::website;
+use crate::models::admin::{
+    AlertArgs, PunishArgs, ReasonArgs, SessionsActionResponse, SilenceArgs, UserActionResponse,
+};
+use crate::models::api_keys::{ApiKey, ApiKeyScope};
+use crate::models::bancho::LoginError;
+use crate::models::users::User;
+use crate::repositories::streams::StreamName;
+use crate::usecases::{punishments, sessions, streams, users};
+use axum::Json;
+use axum::extract::Path;
+use bancho_protocol::messages::MessageArgs;
+use bancho_protocol::messages::server::{Alert, LoginResult};
+use bancho_protocol::serde::BinarySerialize;
+use std::time::Duration;
+
+/// Disconnects all sessions of the user, clients will reconnect on their own.
+pub async fn kick(
+    ctx: RequestContext,
+    ApiAuth(api_key): ApiAuth,
+    Path(user_id): Path<i64>,
+    Json(args): Json<ReasonArgs>,
+) -> ServiceResponse<SessionsActionResponse> {
+    audited(
+        &ctx,
+        &api_key,
+        "kick",
+        Some(user_id),
+        Some(&args.reason),
+        async {
+            api_key.require_scope(ApiKeyScope::Kick)?;
+            let user = users::fetch_one(&ctx, user_id).await?;
+            let mut kicked_sessions = 0;
+            for session in sessions::fetch_by_user_id(&ctx, user_id).await? {
+                sessions::delete(&ctx, &session).await?;
+                kicked_sessions += 1;
+            }
+
+            let log_message = describe_action(&api_key, "kicked", &user, Some(&args.reason));
+            let _ = discord::send_logs_purple_embed("User Kicked", &log_message, None).await;
+            Ok(SessionsActionResponse {
+                user_id,
+                sessions: kicked_sessions,
+            })
+        },
+    )
+    .await
+}
+
+/// Sends all sessions of the user back to the login screen,
+/// the sessions are cleaned up once the clients stop polling.
+pub async fn logout(
+    ctx: RequestContext,
+    ApiAuth(api_key): ApiAuth,
+    Path(user_id): Path<i64>,
+) -> ServiceResponse<SessionsActionResponse> {
+    audited(&ctx, &api_key, "logout", Some(user_id), None, async {
+        api_key.require_scope(ApiKeyScope::Kick)?;
+        let user = users::fetch_one(&ctx, user_id).await?;
+        let logout_packet = LoginResult {
+            user_id: LoginError::InvalidCredentials as i32,
+        };
+        let sessions = broadcast_to_sessions(&ctx, user_id, logout_packet).await?;
+
+        let log_message = describe_action(&api_key, "logged out", &user, None);
+        let _ = discord::send_logs_purple_embed("User Logged Out", &log_message, None).await;
+        Ok(SessionsActionResponse { user_id, sessions })
+    })
+    .await
+}
+
+pub async fn alert(
+    ctx: RequestContext,
+    ApiAuth(api_key): ApiAuth,
+    Path(user_id): Path<i64>,
+    Json(args): Json<AlertArgs>,
+) -> ServiceResponse<SessionsActionResponse> {
+    audited(
+        &ctx,
+        &api_key,
+        "alert",
+        Some(user_id),
+        Some(&args.message),
+        async {
+            api_key.require_scope(ApiKeyScope::Alert)?;
+            let alert = Alert {
+                message: &args.message,
+            };
+            let sessions = broadcast_to_sessions(&ctx, user_id, alert).await?;
+            Ok(SessionsActionResponse { user_id, sessions })
+        },
+    )
+    .await
+}
+
+pub async fn silence(
+    ctx: RequestContext,
+    ApiAuth(api_key): ApiAuth,
+    Path(user_id): Path<i64>,
+    Json(args): Json<SilenceArgs>,
+) -> ServiceResponse<UserActionResponse> {
+    audited(
+        &ctx,
+        &api_key,
+        "silence",
+        Some(user_id),
+        Some(&args.reason),
+        async {
+            api_key.require_scope(ApiKeyScope::Silence)?;
+            let user = users::fetch_one(&ctx, user_id).await?;
+            users::silence_user(&ctx, user_id, &args.reason, args.seconds.max(0)).await?;
+
+            let log_message = describe_action(&api_key, "silenced", &user, Some(&args.reason));
+            let _ = discord::send_logs_red_embed("User Silenced", &log_message, None).await;
+            Ok(UserActionResponse::from(user))
+        },
+    )
+    .await
+}
+
+pub async fn unsilence(
+    ctx: RequestContext,
+    ApiAuth(api_key): ApiAuth,
+    Path(user_id): Path<i64>,
+) -> ServiceResponse<UserActionResponse> {
+    audited(&ctx, &api_key, "unsilence", Some(user_id), None, async {
+        api_key.require_scope(ApiKeyScope::Silence)?;
+        let user = users::fetch_one(&ctx, user_id).await?;
+        users::silence_user(&ctx, user_id, "", 0).await?;
+
+        let log_message = describe_action(&api_key, "unsilenced", &user, None);
+        let _ = discord::send_logs_blue_embed("User Unsilenced", &log_message, None).await;
+        Ok(UserActionResponse::from(user))
+    })
+    .await
+}
+
+pub async fn restrict(
+    ctx: RequestContext,
+    ApiAuth(api_key): ApiAuth,
+    Path(user_id): Path<i64>,
+    Json(args): Json<PunishArgs>,
+) -> ServiceResponse<UserActionResponse> {
+    audited(
+        &ctx,
+        &api_key,
+        "restrict",
+        Some(user_id),
+        Some(&args.reason),
+        async {
+            api_key.require_scope(ApiKeyScope::Restrict)?;
+            let user = users::fetch_one(&ctx, user_id).await?;
+            let duration = args.duration_seconds.map(Duration::from_secs);
+            punishments::restrict(&ctx, user_id, &args.reason, api_key.created_by, duration)
+                .await?;
+
+            let log_message = describe_action(&api_key, "restricted", &user, Some(&args.reason));
+            let _ = discord::send_logs_red_embed("User Restricted", &log_message, None).await;
+            Ok(UserActionResponse::from(user))
+        },
+    )
+    .await
+}
+
+pub async fn unrestrict(
+    ctx: RequestContext,
+    ApiAuth(api_key): ApiAuth,
+    Path(user_id): Path<i64>,
+) -> ServiceResponse<UserActionResponse> {
+    audited(&ctx, &api_key, "unrestrict", Some(user_id), None, async {
+        api_key.require_scope(ApiKeyScope::Restrict)?;
+        let user = users::fetch_one(&ctx, user_id).await?;
+        users::unrestrict_user(&ctx, user_id).await?;
+
+        let log_message = describe_action(&api_key, "unrestricted", &user, None);
+        let _ = discord::send_logs_blue_embed("User Unrestricted", &log_message, None).await;
+        Ok(UserActionResponse::from(user))
+    })
+    .await
+}
+
+pub async fn ban(
+    ctx: RequestContext,
+    ApiAuth(api_key): ApiAuth,
+    Path(user_id): Path<i64>,
+    Json(args): Json<PunishArgs>,
+) -> ServiceResponse<UserActionResponse> {
+    audited(
+        &ctx,
+        &api_key,
+        "ban",
+        Some(user_id),
+        Some(&args.reason),
+        async {
+            api_key.require_scope(ApiKeyScope::Ban)?;
+            let user = users::fetch_one(&ctx, user_id).await?;
+            let duration = args.duration_seconds.map(Duration::from_secs);
+            punishments::ban(&ctx, user_id, &args.reason, api_key.created_by, duration).await?;
+
+            let log_message = describe_action(&api_key, "banned", &user, Some(&args.reason));
+            let _ = discord::send_logs_red_embed("User Banned", &log_message, None).await;
+            Ok(UserActionResponse::from(user))
+        },
+    )
+    .await
+}
+
+pub async fn unban(
+    ctx: RequestContext,
+    ApiAuth(api_key): ApiAuth,
+    Path(user_id): Path<i64>,
+) -> ServiceResponse<UserActionResponse> {
+    audited(&ctx, &api_key, "unban", Some(user_id), None, async {
+        api_key.require_scope(ApiKeyScope::Ban)?;
+        let user = users::fetch_one(&ctx, user_id).await?;
+        users::unban_user(&ctx, user_id).await?;
+
+        let log_message = describe_action(&api_key, "unbanned", &user, None);
+        let _ = discord::send_logs_blue_embed("User Unbanned", &log_message, None).await;
+        Ok(UserActionResponse::from(user))
+    })
+    .await
+}
+
+pub async fn freeze(
+    ctx: RequestContext,
+    ApiAuth(api_key): ApiAuth,
+    Path(user_id): Path<i64>,
+    Json(args): Json<ReasonArgs>,
+) -> ServiceResponse<UserActionResponse> {
+    audited(
+        &ctx,
+        &api_key,
+        "freeze",
+        Some(user_id),
+        Some(&args.reason),
+        async {
+            api_key.require_scope(ApiKeyScope::Freeze)?;
+            let user = users::fetch_one(&ctx, user_id).await?;
+            users::freeze_user(&ctx, user_id, &args.reason).await?;
+
+            let log_message = describe_action(&api_key, "frozen", &user, Some(&args.reason));
+            let _ = discord::send_logs_red_embed("User Frozen", &log_message, None).await;
+            Ok(UserActionResponse::from(user))
+        },
+    )
+    .await
+}
+
+pub async fn unfreeze(
+    ctx: RequestContext,
+    ApiAuth(api_key): ApiAuth,
+    Path(user_id): Path<i64>,
+) -> ServiceResponse<UserActionResponse> {
+    audited(&ctx, &api_key, "unfreeze", Some(user_id), None, async {
+        api_key.require_scope(ApiKeyScope::Freeze)?;
+        let user = users::fetch_one(&ctx, user_id).await?;
+        users::unfreeze_user(&ctx, user_id).await?;
+
+        let log_message = describe_action(&api_key, "unfrozen", &user, None);
+        let _ = discord::send_logs_blue_embed("User Unfrozen", &log_message, None).await;
+        Ok(UserActionResponse::from(user))
+    })
+    .await
+}
+
+// utility
+
+async fn broadcast_to_sessions<C: Context, M: MessageArgs>(
+    ctx: &C,
+    user_id: i64,
+    message: M,
+) -> ServiceResult<usize> {
+    let data = message.as_message().serialize();
+    let mut sessions = 0;
+    for session in sessions::fetch_by_user_id(ctx, user_id).await? {
+        streams::broadcast_data(ctx, StreamName::User(session.session_id), &data, None, None)
+            .await?;
+        sessions += 1;
+    }
+    Ok(sessions)
+}
+
+fn describe_action(api_key: &ApiKey, action: &str, user: &User, reason: Option<&str>) -> String {
+    let target_profile = website::get_profile_link(user.user_id);
+    let mut description = format!(
+        "API key `{}` has {action} [{}]({target_profile})",
+        api_key.name, user.username
+    );
+    if let Some(reason) = reason {
+        description.push_str(&format!(" for: {reason}"));
+    }
+    description
+}
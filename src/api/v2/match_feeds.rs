@@ -0,0 +1,167 @@
+use crate::api::RequestContext;
+use crate::common::error::{AppError, ServiceResult};
+use crate::common::shutdown;
+use crate::common::state::AppState;
+use crate::lifecycle;
+use crate::settings::AppSettings;
+use crate::usecases::{match_feeds, multiplayer};
+use axum::extract::Path;
+use axum::http::HeaderMap;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::IpAddr;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::{error, warn};
+
+/// How long a single blocking read on the feed may take.
+/// Between reads we check whether the viewer is still connected.
+const FEED_TIMEOUT: Duration = Duration::from_secs(5);
+const FEED_BUFFER_SIZE: usize = 64;
+/// Every viewer holds a dedicated redis connection, so viewers are capped per replica.
+const MAX_VIEWERS: usize = 512;
+const MAX_VIEWERS_PER_IP: usize = 4;
+
+type FeedStream = ReceiverStream<Result<Event, Infallible>>;
+
+#[derive(Default)]
+struct Viewers {
+    total: usize,
+    per_ip: HashMap<IpAddr, usize>,
+}
+
+static VIEWERS: LazyLock<Mutex<Viewers>> = LazyLock::new(Default::default);
+
+/// A connected viewer, released once the feed ends.
+struct ViewerSlot {
+    ip_address: IpAddr,
+}
+
+impl ViewerSlot {
+    fn acquire(ip_address: IpAddr) -> Option<Self> {
+        let mut viewers = VIEWERS.lock().expect("match feed viewers poisoned");
+        let ip_viewers = viewers.per_ip.get(&ip_address).copied().unwrap_or(0);
+        if viewers.total >= MAX_VIEWERS || ip_viewers >= MAX_VIEWERS_PER_IP {
+            return None;
+        }
+        viewers.total += 1;
+        viewers.per_ip.insert(ip_address, ip_viewers + 1);
+        Some(Self { ip_address })
+    }
+}
+
+impl Drop for ViewerSlot {
+    fn drop(&mut self) {
+        let mut viewers = VIEWERS.lock().expect("match feed viewers poisoned");
+        viewers.total -= 1;
+        if let Some(ip_viewers) = viewers.per_ip.get_mut(&self.ip_address) {
+            *ip_viewers -= 1;
+            if *ip_viewers == 0 {
+                viewers.per_ip.remove(&self.ip_address);
+            }
+        }
+    }
+}
+
+/// Server-Sent Events feed of a multiplayer match.
+/// Viewers are read-only and never join any bancho stream.
+pub async fn controller(
+    ctx: RequestContext,
+    Path(match_id): Path<i64>,
+    headers: HeaderMap,
+) -> ServiceResult<Sse<FeedStream>> {
+    let mp_match = multiplayer::fetch_one(&ctx, match_id).await?;
+
+    // reconnecting viewers resume where they left off
+    let last_event_id = match headers.get("last-event-id") {
+        Some(event_id) => {
+            let event_id = event_id
+                .to_str()
+                .map_err(|_| AppError::DecodingRequestFailed)?;
+            if !is_valid_event_id(event_id) {
+                return Err(AppError::DecodingRequestFailed);
+            }
+            event_id.to_owned()
+        }
+        None => match_feeds::fetch_latest_event_id(&ctx, mp_match.match_id).await?,
+    };
+
+    let ip_address = ctx.request_ip.ip_addr;
+    let Some(viewer) = ViewerSlot::acquire(ip_address) else {
+        warn!(
+            ip_address = ip_address.to_string(),
+            match_id, "Rejected match feed viewer, too many viewers connected"
+        );
+        return Err(AppError::RateLimitsExceeded);
+    };
+
+    let (feed_tx, feed_rx) = mpsc::channel(FEED_BUFFER_SIZE);
+    tokio::spawn(push_feed_events(
+        AppState::from_ctx(&ctx),
+        mp_match.match_id,
+        last_event_id,
+        feed_tx,
+        viewer,
+    ));
+    Ok(Sse::new(ReceiverStream::new(feed_rx)).keep_alive(KeepAlive::default()))
+}
+
+async fn push_feed_events(
+    ctx: AppState,
+    match_id: i64,
+    mut last_event_id: String,
+    feed_tx: mpsc::Sender<Result<Event, Infallible>>,
+    _viewer: ViewerSlot,
+) {
+    let _connection = shutdown::track_connection();
+    let settings = AppSettings::get();
+    let mut redis = match lifecycle::connect_redis_blocking(settings, FEED_TIMEOUT).await {
+        Ok(redis) => redis,
+        Err(e) => {
+            error!("Failed to open blocking redis connection: {e:?}");
+            return;
+        }
+    };
+
+    // viewers reconnect on their own once the feed ends, resuming from their last event
+    while !feed_tx.is_closed() && !shutdown::is_shutting_down() {
+        let entries =
+            match match_feeds::wait_for_events(&mut redis, match_id, &last_event_id, FEED_TIMEOUT)
+                .await
+            {
+                Ok(entries) => entries,
+                Err(e) => {
+                    error!(match_id, "Failed to read match feed events: {e:?}");
+                    return;
+                }
+            };
+
+        if entries.is_empty() {
+            // the feed expires once the match is gone
+            if multiplayer::fetch_one(&ctx, match_id).await.is_err() {
+                return;
+            }
+            continue;
+        }
+
+        for entry in entries {
+            let event = Event::default().id(&entry.event_id).data(entry.event);
+            if feed_tx.send(Ok(event)).await.is_err() {
+                return;
+            }
+            last_event_id = entry.event_id;
+        }
+    }
+}
+
+/// Stream entry ids are formatted as `<milliseconds>-<sequence>`.
+fn is_valid_event_id(event_id: &str) -> bool {
+    event_id
+        .split_once('-')
+        .is_some_and(|(milliseconds, sequence)| {
+            milliseconds.parse::<u64>().is_ok() && sequence.parse::<u64>().is_ok()
+        })
+}
@@ -0,0 +1,47 @@
+use crate::api::RequestContext;
+use crate::common::context::Context;
+use crate::common::error::{ServiceResponse, ServiceResult};
+use crate::models::live_matches::{LiveMatch, LiveMatchTimer};
+use crate::models::multiplayer::{MultiplayerMatch, MultiplayerMatchSlots};
+use crate::repositories::multiplayer::TimerType;
+use crate::usecases::multiplayer;
+use axum::Json;
+use axum::extract::Path;
+
+pub async fn fetch_all(ctx: RequestContext) -> ServiceResponse<Vec<LiveMatch>> {
+    let matches = multiplayer::fetch_all_with_slots(&ctx).await?;
+    let mut live_matches = Vec::with_capacity(matches.len());
+    for (mp_match, slots) in matches {
+        live_matches.push(live_match(&ctx, mp_match, slots).await?);
+    }
+    Ok(Json(live_matches))
+}
+
+pub async fn fetch_one(
+    ctx: RequestContext,
+    Path(match_id): Path<i64>,
+) -> ServiceResponse<LiveMatch> {
+    let mp_match = multiplayer::fetch_one(&ctx, match_id).await?;
+    let slots = multiplayer::fetch_all_slots(&ctx, mp_match.match_id).await?;
+    Ok(Json(live_match(&ctx, mp_match, slots).await?))
+}
+
+async fn live_match<C: Context>(
+    ctx: &C,
+    mp_match: MultiplayerMatch,
+    slots: MultiplayerMatchSlots,
+) -> ServiceResult<LiveMatch> {
+    let referees = multiplayer::get_referees(ctx, mp_match.match_id).await?;
+    let mut timers = vec![];
+    for timer_type in [TimerType::Regular, TimerType::MatchStart] {
+        let remaining_seconds =
+            multiplayer::get_timer_remaining_seconds(ctx, mp_match.match_id, timer_type).await?;
+        if let Some(remaining_seconds) = remaining_seconds {
+            timers.push(LiveMatchTimer {
+                timer_type: timer_type.as_str(),
+                remaining_seconds,
+            });
+        }
+    }
+    Ok(LiveMatch::from(mp_match, slots, referees, timers))
+}
@@ -0,0 +1,15 @@
+pub mod admin;
+pub mod match_feeds;
+pub mod matches;
+
+use crate::common::state::AppState;
+use axum::Router;
+use axum::routing::get;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/matches", get(matches::fetch_all))
+        .route("/matches/{match_id}", get(matches::fetch_one))
+        .route("/matches/{match_id}/feed", get(match_feeds::controller))
+        .nest("/admin", admin::router())
+}
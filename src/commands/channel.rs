@@ -0,0 +1,551 @@
+use crate::commands;
+use crate::commands::{COMMAND_PREFIX, CommandResult, CommandRouterInstance};
+use crate::common::chat::format_duration;
+use crate::common::context::Context;
+use crate::common::error::{AppError, ServiceResult};
+use crate::entities::channels::ChannelName;
+use crate::models::channels::{Channel, ChannelRole};
+use crate::models::privileges::Privileges;
+use crate::models::sessions::Session;
+use crate::usecases::{channels, users};
+use bancho_service_macros::{FromCommandArgs, command};
+use std::ops::Deref;
+use std::time::Duration;
+
+const MAX_HISTORY_SIZE: u32 = 50;
+const MAX_SLOW_MODE_SECONDS: u32 = 60 * 60;
+
+pub static COMMANDS: CommandRouterInstance = commands![
+    create,
+    delete,
+    info,
+    set_topic,
+    set_invite_only,
+    set_privileges,
+    set_history_size,
+    transfer_owner,
+    add_moderator,
+    remove_moderator,
+    invite_user,
+    uninvite_user,
+    kick_user,
+    ban_user,
+    unban_user,
+    help,
+];
+
+#[derive(Debug, FromCommandArgs)]
+pub struct CreateArgs {
+    pub channel_name: String,
+    pub topic: String,
+}
+
+#[command(
+    "create",
+    required_privileges = Privileges::Donator,
+)]
+pub async fn create<C: Context>(ctx: &C, sender: &Session, args: CreateArgs) -> CommandResult {
+    let channel = channels::create(ctx, sender, &args.channel_name, &args.topic).await?;
+    Ok(Some(format!("Channel {} has been created.", channel.name)))
+}
+
+#[derive(Debug, FromCommandArgs)]
+pub struct ChannelArgs {
+    pub channel_name: String,
+}
+
+#[command("delete")]
+pub async fn delete<C: Context>(ctx: &C, sender: &Session, args: ChannelArgs) -> CommandResult {
+    let channel = fetch_managed_channel(ctx, sender, &args.channel_name).await?;
+    channels::delete(ctx, &channel).await?;
+    Ok(Some(format!("Channel {} has been deleted.", channel.name)))
+}
+
+#[command("info")]
+pub async fn info<C: Context>(ctx: &C, sender: &Session, args: ChannelArgs) -> CommandResult {
+    let channel = fetch_chat_channel(ctx, &args.channel_name).await?;
+    if !channel.can_read(sender.privileges) {
+        return Err(AppError::ChannelsUnauthorized);
+    }
+
+    let owner = match channel.owner_user_id {
+        Some(owner_user_id) => users::fetch_one(ctx, owner_user_id).await?.username,
+        None => "none".to_string(),
+    };
+    let mut moderators = vec![];
+    for user_id in channels::fetch_moderator_ids(ctx, &channel).await? {
+        moderators.push(users::fetch_one(ctx, user_id).await?.username);
+    }
+    let moderators = match moderators.is_empty() {
+        true => "none".to_string(),
+        false => moderators.join(", "),
+    };
+
+    let message = format!(
+        "{}: {}\nOwner: {owner}\nModerators: {moderators}\nInvite only: {}\nHistory size: {}\nSlow mode: {}s\nRead privileges: {} | Write privileges: {}",
+        channel.name,
+        channel.description,
+        channel.invite_only,
+        channel.history_size,
+        channel.slow_mode_seconds,
+        channel.read_privileges.bits(),
+        channel.write_privileges.bits(),
+    );
+    Ok(Some(message))
+}
+
+#[derive(Debug, FromCommandArgs)]
+pub struct TopicArgs {
+    pub channel_name: String,
+    pub topic: String,
+}
+
+#[command("topic")]
+pub async fn set_topic<C: Context>(ctx: &C, sender: &Session, args: TopicArgs) -> CommandResult {
+    let mut channel = fetch_moderated_channel(ctx, sender, &args.channel_name).await?;
+    channel.description = args.topic;
+    channels::update(ctx, &channel).await?;
+    Ok(Some(format!("Topic of {} has been updated.", channel.name)))
+}
+
+#[command("private")]
+pub async fn set_invite_only<C: Context>(
+    ctx: &C,
+    sender: &Session,
+    args: ChannelArgs,
+) -> CommandResult {
+    let mut channel = fetch_managed_channel(ctx, sender, &args.channel_name).await?;
+    channel.invite_only = !channel.invite_only;
+    channels::update(ctx, &channel).await?;
+    match channel.invite_only {
+        true => Ok(Some(format!("{} is now invite only.", channel.name))),
+        false => Ok(Some(format!("{} is now public.", channel.name))),
+    }
+}
+
+#[derive(Debug, FromCommandArgs)]
+pub struct PrivilegesArgs {
+    pub channel_name: String,
+    pub read_privileges: i32,
+    pub write_privileges: i32,
+}
+
+#[command("privileges")]
+pub async fn set_privileges<C: Context>(
+    ctx: &C,
+    sender: &Session,
+    args: PrivilegesArgs,
+) -> CommandResult {
+    let mut channel = fetch_managed_channel(ctx, sender, &args.channel_name).await?;
+    let read_privileges = Privileges::from_bits_truncate(args.read_privileges);
+    let write_privileges = Privileges::from_bits_truncate(args.write_privileges);
+    // owners must not be able to lock themselves out of their channel
+    if !sender.has_any_privilege(read_privileges) || !sender.has_any_privilege(write_privileges) {
+        return Err(AppError::CommandsInvalidArgument(
+            "You must have the privileges you require",
+        ));
+    }
+
+    channel.read_privileges = read_privileges;
+    channel.write_privileges = write_privileges;
+    channels::update(ctx, &channel).await?;
+    Ok(Some(format!(
+        "Privileges of {} have been updated.",
+        channel.name
+    )))
+}
+
+#[derive(Debug, FromCommandArgs)]
+pub struct HistorySizeArgs {
+    pub channel_name: String,
+    pub history_size: u32,
+}
+
+#[command("history")]
+pub async fn set_history_size<C: Context>(
+    ctx: &C,
+    sender: &Session,
+    args: HistorySizeArgs,
+) -> CommandResult {
+    if args.history_size > MAX_HISTORY_SIZE {
+        return Ok(Some(format!(
+            "History size must be at most {MAX_HISTORY_SIZE} messages."
+        )));
+    }
+
+    let mut channel = fetch_managed_channel(ctx, sender, &args.channel_name).await?;
+    channel.history_size = args.history_size as _;
+    channels::update(ctx, &channel).await?;
+    Ok(Some(format!(
+        "{} now replays the last {} messages to joining users.",
+        channel.name, channel.history_size
+    )))
+}
+
+#[derive(Debug, FromCommandArgs)]
+pub struct ChannelUserArgs {
+    pub channel_name: String,
+    pub safe_username: String,
+}
+
+#[command("owner")]
+pub async fn transfer_owner<C: Context>(
+    ctx: &C,
+    sender: &Session,
+    args: ChannelUserArgs,
+) -> CommandResult {
+    let mut channel = fetch_managed_channel(ctx, sender, &args.channel_name).await?;
+    if channel.is_system() {
+        return Err(AppError::ChannelsSystemChannel);
+    }
+    let target_user = users::fetch_one_by_username_safe(ctx, &args.safe_username).await?;
+    channels::set_role(ctx, &channel, target_user.user_id, None).await?;
+    channel.owner_user_id = Some(target_user.user_id);
+    channels::update(ctx, &channel).await?;
+    Ok(Some(format!(
+        "{} is now the owner of {}.",
+        target_user.username, channel.name
+    )))
+}
+
+#[command("mod")]
+pub async fn add_moderator<C: Context>(
+    ctx: &C,
+    sender: &Session,
+    args: ChannelUserArgs,
+) -> CommandResult {
+    let channel = fetch_managed_channel(ctx, sender, &args.channel_name).await?;
+    let target_user = users::fetch_one_by_username_safe(ctx, &args.safe_username).await?;
+    channels::set_role(
+        ctx,
+        &channel,
+        target_user.user_id,
+        Some(ChannelRole::Moderator),
+    )
+    .await?;
+    Ok(Some(format!(
+        "{} is now a moderator of {}.",
+        target_user.username, channel.name
+    )))
+}
+
+#[command("unmod")]
+pub async fn remove_moderator<C: Context>(
+    ctx: &C,
+    sender: &Session,
+    args: ChannelUserArgs,
+) -> CommandResult {
+    let channel = fetch_managed_channel(ctx, sender, &args.channel_name).await?;
+    let target_user = users::fetch_one_by_username_safe(ctx, &args.safe_username).await?;
+    if channels::fetch_role(ctx, &channel, target_user.user_id).await?
+        != Some(ChannelRole::Moderator)
+    {
+        return Ok(Some(format!(
+            "{} is not a moderator of {}.",
+            target_user.username, channel.name
+        )));
+    }
+
+    // moderators stay invited to the channel
+    channels::set_role(
+        ctx,
+        &channel,
+        target_user.user_id,
+        Some(ChannelRole::Member),
+    )
+    .await?;
+    Ok(Some(format!(
+        "{} is no longer a moderator of {}.",
+        target_user.username, channel.name
+    )))
+}
+
+#[command("invite")]
+pub async fn invite_user<C: Context>(
+    ctx: &C,
+    sender: &Session,
+    args: ChannelUserArgs,
+) -> CommandResult {
+    let channel = fetch_moderated_channel(ctx, sender, &args.channel_name).await?;
+    let target_user = users::fetch_one_by_username_safe(ctx, &args.safe_username).await?;
+    match channels::fetch_role(ctx, &channel, target_user.user_id).await? {
+        Some(ChannelRole::Banned) => Ok(Some(format!(
+            "{} is banned from {}.",
+            target_user.username, channel.name
+        ))),
+        Some(_) => Ok(Some(format!(
+            "{} is already invited to {}.",
+            target_user.username, channel.name
+        ))),
+        None => {
+            channels::set_role(
+                ctx,
+                &channel,
+                target_user.user_id,
+                Some(ChannelRole::Member),
+            )
+            .await?;
+            Ok(Some(format!(
+                "{} has been invited to {}.",
+                target_user.username, channel.name
+            )))
+        }
+    }
+}
+
+#[command("uninvite")]
+pub async fn uninvite_user<C: Context>(
+    ctx: &C,
+    sender: &Session,
+    args: ChannelUserArgs,
+) -> CommandResult {
+    let channel = fetch_moderated_channel(ctx, sender, &args.channel_name).await?;
+    let target_user = users::fetch_one_by_username_safe(ctx, &args.safe_username).await?;
+    ensure_can_moderate_user(ctx, sender, &channel, target_user.user_id).await?;
+    if channels::fetch_role(ctx, &channel, target_user.user_id).await? != Some(ChannelRole::Member)
+    {
+        return Ok(Some(format!(
+            "{} is not invited to {}.",
+            target_user.username, channel.name
+        )));
+    }
+
+    channels::set_role(ctx, &channel, target_user.user_id, None).await?;
+    if channel.invite_only {
+        channels::kick(ctx, &channel, target_user.user_id).await?;
+    }
+    Ok(Some(format!(
+        "{} is no longer invited to {}.",
+        target_user.username, channel.name
+    )))
+}
+
+#[command("kick")]
+pub async fn kick_user<C: Context>(
+    ctx: &C,
+    sender: &Session,
+    args: ChannelUserArgs,
+) -> CommandResult {
+    let channel = fetch_moderated_channel(ctx, sender, &args.channel_name).await?;
+    let target_user = users::fetch_one_by_username_safe(ctx, &args.safe_username).await?;
+    ensure_can_moderate_user(ctx, sender, &channel, target_user.user_id).await?;
+    match channels::kick(ctx, &channel, target_user.user_id).await? {
+        true => Ok(Some(format!(
+            "{} has been kicked from {}.",
+            target_user.username, channel.name
+        ))),
+        false => Ok(Some(format!(
+            "{} is not in {}.",
+            target_user.username, channel.name
+        ))),
+    }
+}
+
+#[command("ban")]
+pub async fn ban_user<C: Context>(
+    ctx: &C,
+    sender: &Session,
+    args: ChannelUserArgs,
+) -> CommandResult {
+    let channel = fetch_moderated_channel(ctx, sender, &args.channel_name).await?;
+    let target_user = users::fetch_one_by_username_safe(ctx, &args.safe_username).await?;
+    ensure_can_moderate_user(ctx, sender, &channel, target_user.user_id).await?;
+    channels::set_role(
+        ctx,
+        &channel,
+        target_user.user_id,
+        Some(ChannelRole::Banned),
+    )
+    .await?;
+    channels::kick(ctx, &channel, target_user.user_id).await?;
+    Ok(Some(format!(
+        "{} has been banned from {}.",
+        target_user.username, channel.name
+    )))
+}
+
+#[command("unban")]
+pub async fn unban_user<C: Context>(
+    ctx: &C,
+    sender: &Session,
+    args: ChannelUserArgs,
+) -> CommandResult {
+    let channel = fetch_moderated_channel(ctx, sender, &args.channel_name).await?;
+    let target_user = users::fetch_one_by_username_safe(ctx, &args.safe_username).await?;
+    if !channels::is_banned(ctx, &channel, target_user.user_id).await? {
+        return Ok(Some(format!(
+            "{} is not banned from {}.",
+            target_user.username, channel.name
+        )));
+    }
+
+    channels::set_role(ctx, &channel, target_user.user_id, None).await?;
+    Ok(Some(format!(
+        "{} has been unbanned from {}.",
+        target_user.username, channel.name
+    )))
+}
+
+// the following commands are registered at the top level
+
+#[derive(Debug, FromCommandArgs)]
+pub struct SlowModeArgs {
+    pub channel_name: String,
+    pub slow_mode_seconds: u32,
+}
+
+#[command("slowmode")]
+pub async fn set_slow_mode<C: Context>(
+    ctx: &C,
+    sender: &Session,
+    args: SlowModeArgs,
+) -> CommandResult {
+    if args.slow_mode_seconds > MAX_SLOW_MODE_SECONDS {
+        return Ok(Some(format!(
+            "Slow mode must be at most {MAX_SLOW_MODE_SECONDS} seconds."
+        )));
+    }
+
+    let mut channel = fetch_moderated_channel(ctx, sender, &args.channel_name).await?;
+    channel.slow_mode_seconds = args.slow_mode_seconds;
+    channels::update(ctx, &channel).await?;
+    match channel.slow_mode_seconds {
+        0 => Ok(Some(format!(
+            "Slow mode has been disabled in {}.",
+            channel.name
+        ))),
+        slow_mode_seconds => Ok(Some(format!(
+            "Users can now send one message every {slow_mode_seconds} seconds in {}.",
+            channel.name
+        ))),
+    }
+}
+
+#[derive(Debug, FromCommandArgs)]
+pub struct MuteArgs {
+    pub safe_username: String,
+    pub channel_name: String,
+    pub duration: Duration,
+}
+
+#[command("chanmute")]
+pub async fn mute_user<C: Context>(ctx: &C, sender: &Session, args: MuteArgs) -> CommandResult {
+    let channel = fetch_moderated_channel(ctx, sender, &args.channel_name).await?;
+    let target_user = users::fetch_one_by_username_safe(ctx, &args.safe_username).await?;
+    ensure_can_moderate_user(ctx, sender, &channel, target_user.user_id).await?;
+    let mute_seconds = args.duration.as_secs();
+    if mute_seconds == 0 {
+        return Ok(Some(
+            "The mute duration must be at least one second.".to_owned(),
+        ));
+    }
+
+    channels::mute(ctx, &channel, target_user.user_id, mute_seconds).await?;
+    Ok(Some(format!(
+        "{} has been muted in {} for {}.",
+        target_user.username,
+        channel.name,
+        format_duration(mute_seconds)
+    )))
+}
+
+#[derive(Debug, FromCommandArgs)]
+pub struct UnmuteArgs {
+    pub safe_username: String,
+    pub channel_name: String,
+}
+
+#[command("chanunmute")]
+pub async fn unmute_user<C: Context>(ctx: &C, sender: &Session, args: UnmuteArgs) -> CommandResult {
+    let channel = fetch_moderated_channel(ctx, sender, &args.channel_name).await?;
+    let target_user = users::fetch_one_by_username_safe(ctx, &args.safe_username).await?;
+    match channels::unmute(ctx, &channel, target_user.user_id).await? {
+        true => Ok(Some(format!(
+            "{} has been unmuted in {}.",
+            target_user.username, channel.name
+        ))),
+        false => Ok(Some(format!(
+            "{} is not muted in {}.",
+            target_user.username, channel.name
+        ))),
+    }
+}
+
+#[command("help", forward_message = false)]
+pub async fn help<C: Context>(_ctx: &C, sender: &Session) -> CommandResult {
+    let mut response = "Supported channel subcommands:\n".to_owned();
+    for (name, cmd) in COMMANDS.deref().commands.iter() {
+        match cmd.properties.required_privileges {
+            Some(required_privileges) if sender.has_all_privileges(required_privileges) => {
+                response.push_str(COMMAND_PREFIX);
+                response.push_str("channel ");
+                response.push_str(name);
+                response.push('\n');
+            }
+            None => {
+                response.push_str(COMMAND_PREFIX);
+                response.push_str("channel ");
+                response.push_str(name);
+                response.push('\n');
+            }
+            _ => {}
+        }
+    }
+    Ok(Some(response))
+}
+
+// utility
+
+async fn fetch_chat_channel<C: Context>(ctx: &C, channel_name: &str) -> ServiceResult<Channel> {
+    let channel_name = channel_name.to_lowercase();
+    channels::fetch_one(ctx, ChannelName::Chat(&channel_name)).await
+}
+
+async fn fetch_moderated_channel<C: Context>(
+    ctx: &C,
+    sender: &Session,
+    channel_name: &str,
+) -> ServiceResult<Channel> {
+    let channel = fetch_chat_channel(ctx, channel_name).await?;
+    match channels::can_moderate(ctx, sender, &channel).await? {
+        true => Ok(channel),
+        false => Err(AppError::ChannelsUnauthorized),
+    }
+}
+
+async fn fetch_managed_channel<C: Context>(
+    ctx: &C,
+    sender: &Session,
+    channel_name: &str,
+) -> ServiceResult<Channel> {
+    let channel = fetch_chat_channel(ctx, channel_name).await?;
+    match channels::can_manage(sender, &channel) {
+        true => Ok(channel),
+        false => Err(AppError::ChannelsUnauthorized),
+    }
+}
+
+/// Moderators cannot act against the owner, staff or other moderators.
+async fn ensure_can_moderate_user<C: Context>(
+    ctx: &C,
+    sender: &Session,
+    channel: &Channel,
+    target_user_id: i64,
+) -> ServiceResult<()> {
+    if sender.privileges.is_staff() {
+        return Ok(());
+    }
+    if channel.is_owner(target_user_id) {
+        return Err(AppError::ChannelsUnauthorized);
+    }
+    if channels::can_manage(sender, channel) {
+        return Ok(());
+    }
+
+    let target_user = users::fetch_one(ctx, target_user_id).await?;
+    let target_role = channels::fetch_role(ctx, channel, target_user_id).await?;
+    if target_user.privileges.is_staff() || target_role == Some(ChannelRole::Moderator) {
+        return Err(AppError::ChannelsUnauthorized);
+    }
+    Ok(())
+}
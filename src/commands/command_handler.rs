@@ -2,9 +2,11 @@ use crate::commands::from_args::FromCommandArgs;
 use crate::commands::{CommandResponse, CommandResult};
 use crate::common::context::Context;
 use crate::common::error::{AppError, ServiceResult};
+use crate::common::metrics;
 use crate::common::redis_pool::RedisPool;
 use crate::models::privileges::Privileges;
 use crate::models::sessions::Session;
+use crate::usecases::audit_logs;
 use async_trait::async_trait;
 use hashbrown::HashMap;
 use sqlx::{MySql, Pool};
@@ -42,6 +44,11 @@ pub trait CommandHandlerProxy: Send + Sync {
         session: &Session,
         args: Option<&str>,
     ) -> ServiceResult<Option<CommandResponse>>;
+
+    /// The user the command acts on, taken from the raw arguments.
+    fn target<'a>(&self, _args: Option<&'a str>) -> Option<&'a str> {
+        None
+    }
 }
 
 struct CommandContext {
@@ -87,6 +94,7 @@ impl<CMD: Command> CommandHandlerProxy for CMD {
         session: &Session,
         args: Option<&str>,
     ) -> ServiceResult<Option<CommandResponse>> {
+        metrics::record_command(CMD::PROPERTIES.name);
         let ctx = CommandContext::from_ctx(ctx);
         let args = CMD::Args::from_args(args)?;
         let answer = CMD::handle(&ctx, session, args).await?;
@@ -95,6 +103,11 @@ impl<CMD: Command> CommandHandlerProxy for CMD {
             properties: CMD::PROPERTIES,
         }))
     }
+
+    fn target<'a>(&self, args: Option<&'a str>) -> Option<&'a str> {
+        let index = CMD::Args::TARGET_INDEX?;
+        args?.split(' ').nth(index)
+    }
 }
 
 #[macro_export]
@@ -151,18 +164,20 @@ impl CommandRouter {
     }
 
     pub fn nest(&mut self, name: &'static str, router: &'static CommandRouter) {
-        self.commands
-            .insert(name, RegisteredCommand::group(name, router));
+        self.commands.insert(
+            name,
+            RegisteredCommand::group(name, NestedRouter { name, router }),
+        );
     }
-}
 
-#[async_trait]
-impl CommandHandlerProxy for &CommandRouter {
-    async fn handle(
+    /// Routes the command to its handler, `parent` is the name of the group
+    /// the router is nested in.
+    async fn dispatch(
         &self,
         ctx: &dyn Context,
         session: &Session,
         args: Option<&str>,
+        parent: Option<&str>,
     ) -> ServiceResult<Option<CommandResponse>> {
         match args {
             Some(args) => {
@@ -175,12 +190,31 @@ impl CommandHandlerProxy for &CommandRouter {
                 let args = parts.next();
                 match self.get(cmd_name) {
                     Some(command) => {
-                        if let Some(required_privileges) = command.properties.required_privileges
-                            && !session.has_all_privileges(required_privileges)
-                        {
-                            return Err(AppError::CommandsUnauthorized);
-                        }
-                        command.handler.handle(ctx, &session, args).await
+                        let Some(required_privileges) = command.properties.required_privileges
+                        else {
+                            return command.handler.handle(ctx, &session, args).await;
+                        };
+
+                        // privileged commands are audited, including unauthorized attempts
+                        let result = match session.has_all_privileges(required_privileges) {
+                            true => command.handler.handle(ctx, &session, args).await,
+                            false => Err(AppError::CommandsUnauthorized),
+                        };
+                        // nested commands are recorded with their full path, e.g. "mp password"
+                        let command_name = match parent {
+                            Some(parent) => format!("{parent} {}", command.properties.name),
+                            None => command.properties.name.to_owned(),
+                        };
+                        audit_logs::record_command(
+                            &CommandContext::from_ctx(ctx),
+                            session,
+                            &command_name,
+                            command.handler.target(args),
+                            args,
+                            &result,
+                        )
+                        .await;
+                        result
                     }
                     None => Err(AppError::CommandsUnknownCommand),
                 }
@@ -190,6 +224,38 @@ impl CommandHandlerProxy for &CommandRouter {
     }
 }
 
+#[async_trait]
+impl CommandHandlerProxy for &CommandRouter {
+    async fn handle(
+        &self,
+        ctx: &dyn Context,
+        session: &Session,
+        args: Option<&str>,
+    ) -> ServiceResult<Option<CommandResponse>> {
+        self.dispatch(ctx, session, args, None).await
+    }
+}
+
+/// A command group, routing to the commands of the nested router.
+struct NestedRouter {
+    name: &'static str,
+    router: &'static CommandRouter,
+}
+
+#[async_trait]
+impl CommandHandlerProxy for NestedRouter {
+    async fn handle(
+        &self,
+        ctx: &dyn Context,
+        session: &Session,
+        args: Option<&str>,
+    ) -> ServiceResult<Option<CommandResponse>> {
+        self.router
+            .dispatch(ctx, session, args, Some(self.name))
+            .await
+    }
+}
+
 impl RegisteredCommand {
     pub fn new<C: 'static + Command>(cmd: C) -> Self {
         Self {
@@ -9,6 +9,8 @@ pub trait FromCommandArgs: Sized {
     const TYPE_SIGNATURE: &'static str;
     const SYNTAX: &'static str = "args";
     const TYPED_SYNTAX: &'static str = "args";
+    /// Position of the argument naming the user the command acts on, used for audit logs.
+    const TARGET_INDEX: Option<usize> = None;
 }
 
 impl FromCommandArgs for NoArg {
@@ -4,12 +4,13 @@ use crate::common::context::Context;
 use crate::entities::bot;
 use crate::models::performance::PerformanceRequestArgs;
 use crate::models::privileges::Privileges;
+use crate::models::rate_limits::{RateLimitAction, RateLimitScope};
 use crate::models::sessions::Session;
 use crate::repositories::streams::StreamName;
 use crate::settings::AppSettings;
 use crate::usecases::{
-    beatmaps, multiplayer, performance, presences, scores, sessions, spectators, streams,
-    tillerino, user_reports, users,
+    bancho_settings, beatmaps, multiplayer, performance, presences, rate_limits, scores, sessions,
+    spectators, streams, tillerino, user_reports, users,
 };
 use bancho_protocol::messages::server::{Alert, ChatMessage};
 use bancho_protocol::structures::IrcMessage;
@@ -91,11 +92,37 @@ pub async fn announce<C: Context>(ctx: &C, _sender: &Session, message: String) -
     Ok(Some("Announcement sent successfully.".to_owned()))
 }
 
-const MAX_ROLL: i32 = 1_000_000;
+#[command("queue")]
+pub async fn host_queue<C: Context>(ctx: &C, sender: &Session) -> CommandResult {
+    let Some(match_id) = multiplayer::fetch_session_match_id(ctx, sender.session_id).await? else {
+        return Ok(Some("You are not in a multiplayer match.".to_string()));
+    };
+    if !multiplayer::is_auto_host(ctx, match_id).await? {
+        return Ok(Some("Auto host is not enabled for this match.".to_string()));
+    }
+
+    let host_queue = multiplayer::fetch_host_queue(ctx, match_id).await?;
+    let mut usernames = Vec::with_capacity(host_queue.len());
+    for user_id in host_queue {
+        let user = users::fetch_one(ctx, user_id).await?;
+        usernames.push(user.username);
+    }
+    Ok(Some(format!("Host queue: {}", usernames.join(", "))))
+}
 
 #[command("roll")]
-pub async fn roll<C: Context>(_ctx: &C, sender: &Session, max_roll: Option<i32>) -> CommandResult {
-    let max_roll = max_roll.unwrap_or(MAX_ROLL).min(MAX_ROLL).max(1);
+pub async fn roll<C: Context>(ctx: &C, sender: &Session, max_roll: Option<i32>) -> CommandResult {
+    rate_limits::check(
+        ctx,
+        RateLimitAction::Roll,
+        RateLimitScope::User(sender.user_id),
+    )
+    .await?;
+    let settings = bancho_settings::fetch_current(ctx).await?;
+    let max_roll = max_roll
+        .unwrap_or(settings.max_roll)
+        .min(settings.max_roll)
+        .max(1);
     let result = rand::random_range(1..=max_roll);
     let response = format!("{} rolls {result} points!", sender.username);
     Ok(Some(response))
@@ -179,6 +206,12 @@ pub async fn report_user<C: Context>(
     sender: &Session,
     args: ReportUserArgs,
 ) -> CommandResult {
+    rate_limits::check(
+        ctx,
+        RateLimitAction::Report,
+        RateLimitScope::User(sender.user_id),
+    )
+    .await?;
     let user = users::fetch_one_by_username_safe(ctx, &args.username).await?;
     user_reports::create(ctx, sender.user_id, user.user_id, args.reason).await?;
     Ok(Some("Report successful!".to_owned()))
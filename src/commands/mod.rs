@@ -1,3 +1,4 @@
+pub mod channel;
 mod command_handler;
 mod from_args;
 pub mod misc;
@@ -26,13 +27,18 @@ pub const COMMAND_PREFIX: &str = "!";
 
 static COMMAND_ROUTER: CommandRouterInstance = commands![
     include = [
+        "channel" => channel::COMMANDS,
         "mp" => mp::COMMANDS,
         "system" => system::COMMANDS,
     ],
+    channel::mute_user,
+    channel::set_slow_mode,
+    channel::unmute_user,
     misc::alert_all,
     misc::alert_user,
     misc::announce,
     misc::help,
+    misc::host_queue,
     misc::last_user_score,
     misc::map_mirror,
     misc::report_user,
@@ -41,15 +47,22 @@ static COMMAND_ROUTER: CommandRouterInstance = commands![
     misc::overwrite_best_score_with_last,
 
     staff::add_bn,
+    staff::api_key,
+    staff::audit,
     staff::ban_user,
+    staff::chat_filter,
+    staff::client_build,
+    staff::client_versions,
     staff::edit_map,
     staff::freeze_user,
     staff::kick,
+    staff::login_notice,
     staff::remove_bn,
     staff::restrict_user,
     staff::silence_user,
     staff::unban_user,
     staff::unfreeze_user,
+    staff::unlock_login,
     staff::unrestrict_user,
     staff::unsilence_user,
     staff::whitelist_user,
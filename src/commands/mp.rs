@@ -1,14 +1,13 @@
 use crate::commands;
 use crate::commands::{COMMAND_PREFIX, CommandResult, CommandRouterInstance};
 use crate::common::context::Context;
-use crate::common::error::AppError;
+use crate::common::error::{AppError, ServiceResult};
 use crate::common::website;
-use crate::entities::gamemodes::Gamemode;
 use crate::models::privileges::Privileges;
 use crate::models::sessions::Session;
 use crate::repositories::multiplayer::TimerType;
 use crate::repositories::streams::StreamName;
-use crate::usecases::{beatmaps, multiplayer, sessions, streams, users};
+use crate::usecases::{multiplayer, sessions, streams, tournaments, users};
 use bancho_protocol::messages::server::{ChatMessage, MatchJoinFailed};
 use bancho_protocol::structures::{IrcMessage, MatchTeam, MatchTeamType, Mode, Mods, WinCondition};
 use bancho_service_macros::{FromCommandArgs, command};
@@ -44,6 +43,18 @@ pub static COMMANDS: CommandRouterInstance = commands![
     match_history_link,
     timer,
     aborttimer,
+    best_of,
+    add_pool_map,
+    clear_pool,
+    ban_map,
+    pick_map,
+    tournament_status,
+    auto_host,
+    set_star_range,
+    set_max_length,
+    set_map_mode,
+    ranked_only,
+    clear_constraints,
 ];
 
 #[derive(Debug, FromCommandArgs)]
@@ -349,7 +360,8 @@ pub async fn start<C: Context>(
                 match_id,
                 TimerType::MatchStart,
                 args.timer_duration.as_secs(),
-            );
+            )
+            .await?;
             Ok(Some(format!(
                 "Countdown started. Match starts in {:?} second(s).",
                 args.timer_duration.as_secs(),
@@ -440,37 +452,28 @@ pub async fn set_map<C: Context>(ctx: &C, sender: &Session, args: MapArgs) -> Co
         .await?
         .ok_or(AppError::MultiplayerUserNotInMatch)?;
 
-    let mut mp_match = multiplayer::fetch_one(ctx, match_id).await?;
-    if mp_match.host_user_id != sender.user_id
-        && !multiplayer::is_referee(ctx, match_id, sender.user_id).await?
-    {
+    let mp_match = multiplayer::fetch_one(ctx, match_id).await?;
+    let is_referee = multiplayer::is_referee(ctx, match_id, sender.user_id).await?;
+    if mp_match.host_user_id != sender.user_id && !is_referee {
         return Err(AppError::MultiplayerUnauthorized);
     }
 
-    // Fetch the beatmap to get its details
-    let beatmap = beatmaps::fetch_by_id(ctx, args.beatmap_id).await?;
-    mp_match.beatmap_id = beatmap.beatmap_id;
-    mp_match.beatmap_name = beatmap.song_name;
-    mp_match.beatmap_md5 = beatmap.beatmap_md5;
-
-    let new_mode = match args.gamemode {
-        Some(gamemode) if beatmap.mode == Mode::Standard => Mode::try_from(gamemode)
-            .map_err(|_| AppError::CommandsInvalidArgument("Invalid gamemode"))?,
-        _ => beatmap.mode,
-    };
-
-    // osu! mode changed, reset mods.
-    if mp_match.mode.as_bancho() != new_mode {
-        mp_match.mods = Mods::None;
-        let mut slots = multiplayer::fetch_all_slots(ctx, match_id).await?;
-        for slot in &mut slots {
-            slot.mods = Mods::None;
-        }
-        multiplayer::update_all_slots(ctx, match_id, slots).await?;
+    // referees are not bound to the map constraints of their match
+    if !is_referee
+        && let Some(rejection) =
+            multiplayer::check_map_constraints(ctx, match_id, args.beatmap_id).await?
+    {
+        return Ok(Some(format!("This map can't be picked, {rejection}.")));
     }
 
-    mp_match.mode = Gamemode::from_mode_and_mods(new_mode, mp_match.mods);
-    multiplayer::update(ctx, mp_match).await?;
+    let gamemode = match args.gamemode {
+        Some(gamemode) => Some(
+            Mode::try_from(gamemode)
+                .map_err(|_| AppError::CommandsInvalidArgument("Invalid gamemode"))?,
+        ),
+        None => None,
+    };
+    multiplayer::change_beatmap(ctx, match_id, args.beatmap_id, gamemode).await?;
 
     Ok(Some("Match map has been updated.".to_string()))
 }
@@ -712,6 +715,12 @@ pub async fn view_settings<C: Context>(ctx: &C, sender: &Session) -> CommandResu
         msg.push(format!("{} ({})", user.username, user.user_id));
     }
 
+    let constraints = multiplayer::fetch_constraints(ctx, match_id).await?;
+    msg.push(format!(
+        "Map constraints: {}",
+        multiplayer::format_constraints(&constraints)
+    ));
+
     Ok(Some(msg.join("\n")))
 }
 
@@ -817,7 +826,7 @@ pub async fn timer<C: Context>(ctx: &C, sender: &Session, args: TimerArgs) -> Co
         return Err(AppError::MultiplayerUnauthorized);
     }
 
-    multiplayer::start_timer(ctx, match_id, TimerType::Regular, timer_seconds);
+    multiplayer::start_timer(ctx, match_id, TimerType::Regular, timer_seconds).await?;
     Ok(Some(format!(
         "Countdown started. Ends in {timer_seconds} second(s)."
     )))
@@ -840,3 +849,274 @@ pub async fn aborttimer<C: Context>(ctx: &C, sender: &Session) -> CommandResult
 
     Ok(Some("Countdown stopped.".to_string()))
 }
+
+#[derive(Debug, FromCommandArgs)]
+pub struct BestOfArgs {
+    pub best_of: u8,
+    pub bans_per_team: Option<u8>,
+}
+
+#[command("bestof")]
+pub async fn best_of<C: Context>(ctx: &C, sender: &Session, args: BestOfArgs) -> CommandResult {
+    if args.best_of < 1 || args.best_of > 13 || args.best_of % 2 == 0 {
+        return Ok(Some(
+            "Best of must be an odd number between 1 and 13.".to_string(),
+        ));
+    }
+
+    let match_id = fetch_refereed_match_id(ctx, sender).await?;
+    let bans_per_team = args
+        .bans_per_team
+        .unwrap_or(tournaments::DEFAULT_BANS_PER_TEAM);
+    tournaments::enable(ctx, match_id, args.best_of, bans_per_team).await?;
+    Ok(Some(format!(
+        "Tournament mode enabled, the match is played as best of {} with {bans_per_team} ban(s) per team.",
+        args.best_of
+    )))
+}
+
+#[derive(Debug, FromCommandArgs)]
+pub struct PoolArgs {
+    pub code: String,
+    pub beatmap_id: i32,
+}
+
+#[command("pool")]
+pub async fn add_pool_map<C: Context>(ctx: &C, sender: &Session, args: PoolArgs) -> CommandResult {
+    let match_id = fetch_refereed_match_id(ctx, sender).await?;
+    let pool_map = tournaments::add_pool_map(ctx, match_id, &args.code, args.beatmap_id).await?;
+    Ok(Some(format!(
+        "Added beatmap {} to the mappool as {}.",
+        pool_map.beatmap_id, pool_map.code
+    )))
+}
+
+#[command("clearpool")]
+pub async fn clear_pool<C: Context>(ctx: &C, sender: &Session) -> CommandResult {
+    let match_id = fetch_refereed_match_id(ctx, sender).await?;
+    tournaments::clear_pool(ctx, match_id).await?;
+    Ok(Some("Mappool cleared.".to_string()))
+}
+
+#[derive(Debug, FromCommandArgs)]
+pub struct PickArgs {
+    pub code: String,
+    /// Referees pick on behalf of a team, regardless of whose turn it is
+    pub colour: Option<String>,
+}
+
+#[command("ban")]
+pub async fn ban_map<C: Context>(ctx: &C, sender: &Session, args: PickArgs) -> CommandResult {
+    let match_id = multiplayer::fetch_session_match_id(ctx, sender.session_id)
+        .await?
+        .ok_or(AppError::MultiplayerUserNotInMatch)?;
+
+    let (team, referee) =
+        resolve_picking_team(ctx, match_id, sender, args.colour.as_deref()).await?;
+    let pool_map =
+        tournaments::ban(ctx, match_id, sender.user_id, &args.code, team, referee).await?;
+    Ok(Some(format!("{} has been banned.", pool_map.code)))
+}
+
+#[command("pick")]
+pub async fn pick_map<C: Context>(ctx: &C, sender: &Session, args: PickArgs) -> CommandResult {
+    let match_id = multiplayer::fetch_session_match_id(ctx, sender.session_id)
+        .await?
+        .ok_or(AppError::MultiplayerUserNotInMatch)?;
+
+    let (team, referee) =
+        resolve_picking_team(ctx, match_id, sender, args.colour.as_deref()).await?;
+    let pool_map =
+        tournaments::pick(ctx, match_id, sender.user_id, &args.code, team, referee).await?;
+    Ok(Some(format!(
+        "{} has been picked. The match starts once the countdown ends.",
+        pool_map.code
+    )))
+}
+
+#[command("tourney")]
+pub async fn tournament_status<C: Context>(ctx: &C, sender: &Session) -> CommandResult {
+    let match_id = multiplayer::fetch_session_match_id(ctx, sender.session_id)
+        .await?
+        .ok_or(AppError::MultiplayerUserNotInMatch)?;
+
+    let tournament = tournaments::fetch_one(ctx, match_id).await?;
+    Ok(Some(tournaments::format_status(&tournament)))
+}
+
+#[command("autohost")]
+pub async fn auto_host<C: Context>(ctx: &C, sender: &Session) -> CommandResult {
+    let match_id = multiplayer::fetch_session_match_id(ctx, sender.session_id)
+        .await?
+        .ok_or(AppError::MultiplayerUserNotInMatch)?;
+
+    let mp_match = multiplayer::fetch_one(ctx, match_id).await?;
+    if mp_match.host_user_id != sender.user_id
+        && !multiplayer::is_referee(ctx, match_id, sender.user_id).await?
+    {
+        return Err(AppError::MultiplayerUnauthorized);
+    }
+
+    let enabled = !multiplayer::is_auto_host(ctx, match_id).await?;
+    multiplayer::set_auto_host(ctx, match_id, enabled).await?;
+    match enabled {
+        true => Ok(Some(
+            "Auto host enabled. Host rotates after every game, see !queue for the order."
+                .to_string(),
+        )),
+        false => Ok(Some("Auto host disabled.".to_string())),
+    }
+}
+
+#[derive(Debug, FromCommandArgs)]
+pub struct StarRangeArgs {
+    pub min_stars: Option<f32>,
+    pub max_stars: Option<f32>,
+}
+
+#[command("stars")]
+pub async fn set_star_range<C: Context>(
+    ctx: &C,
+    sender: &Session,
+    args: StarRangeArgs,
+) -> CommandResult {
+    if let (Some(min_stars), Some(max_stars)) = (args.min_stars, args.max_stars)
+        && min_stars > max_stars
+    {
+        return Ok(Some(
+            "Minimum star rating must not exceed the maximum.".to_string(),
+        ));
+    }
+
+    let match_id = fetch_refereed_match_id(ctx, sender).await?;
+    let mut constraints = multiplayer::fetch_constraints(ctx, match_id).await?;
+    constraints.min_stars = args.min_stars.filter(|stars| *stars > 0.0);
+    constraints.max_stars = args.max_stars;
+    multiplayer::update_constraints(ctx, match_id, &constraints).await?;
+
+    let message = format!(
+        "Map constraints updated: {}",
+        multiplayer::format_constraints(&constraints)
+    );
+    Ok(Some(message))
+}
+
+#[derive(Debug, FromCommandArgs)]
+pub struct MaxLengthArgs {
+    pub max_length: Option<Duration>,
+}
+
+#[command("maxlength")]
+pub async fn set_max_length<C: Context>(
+    ctx: &C,
+    sender: &Session,
+    args: MaxLengthArgs,
+) -> CommandResult {
+    let match_id = fetch_refereed_match_id(ctx, sender).await?;
+    let mut constraints = multiplayer::fetch_constraints(ctx, match_id).await?;
+    constraints.max_length = args
+        .max_length
+        .map(|max_length| max_length.as_secs() as i32)
+        .filter(|max_length| *max_length > 0);
+    multiplayer::update_constraints(ctx, match_id, &constraints).await?;
+
+    let message = format!(
+        "Map constraints updated: {}",
+        multiplayer::format_constraints(&constraints)
+    );
+    Ok(Some(message))
+}
+
+#[derive(Debug, FromCommandArgs)]
+pub struct MapModeArgs {
+    pub gamemode: Option<u8>,
+}
+
+#[command("mapmode")]
+pub async fn set_map_mode<C: Context>(
+    ctx: &C,
+    sender: &Session,
+    args: MapModeArgs,
+) -> CommandResult {
+    if let Some(gamemode) = args.gamemode
+        && gamemode > 3
+    {
+        return Ok(Some("Gamemode must be 0, 1, 2 or 3.".to_string()));
+    }
+
+    let match_id = fetch_refereed_match_id(ctx, sender).await?;
+    let mut constraints = multiplayer::fetch_constraints(ctx, match_id).await?;
+    constraints.mode = args.gamemode;
+    multiplayer::update_constraints(ctx, match_id, &constraints).await?;
+
+    let message = format!(
+        "Map constraints updated: {}",
+        multiplayer::format_constraints(&constraints)
+    );
+    Ok(Some(message))
+}
+
+#[command("rankedonly")]
+pub async fn ranked_only<C: Context>(ctx: &C, sender: &Session) -> CommandResult {
+    let match_id = fetch_refereed_match_id(ctx, sender).await?;
+    let mut constraints = multiplayer::fetch_constraints(ctx, match_id).await?;
+    constraints.ranked_only = !constraints.ranked_only;
+    multiplayer::update_constraints(ctx, match_id, &constraints).await?;
+
+    let message = format!(
+        "Map constraints updated: {}",
+        multiplayer::format_constraints(&constraints)
+    );
+    Ok(Some(message))
+}
+
+#[command("clearconstraints")]
+pub async fn clear_constraints<C: Context>(ctx: &C, sender: &Session) -> CommandResult {
+    let match_id = fetch_refereed_match_id(ctx, sender).await?;
+    multiplayer::update_constraints(ctx, match_id, &Default::default()).await?;
+    Ok(Some("Map constraints cleared.".to_string()))
+}
+
+/// Map constraints, the mappool and the tournament state are managed by referees only,
+/// as they bind the host and the players.
+async fn fetch_refereed_match_id<C: Context>(ctx: &C, sender: &Session) -> ServiceResult<i64> {
+    let match_id = multiplayer::fetch_session_match_id(ctx, sender.session_id)
+        .await?
+        .ok_or(AppError::MultiplayerUserNotInMatch)?;
+    match multiplayer::is_referee(ctx, match_id, sender.user_id).await? {
+        true => Ok(match_id),
+        false => Err(AppError::MultiplayerUnauthorized),
+    }
+}
+
+/// Players pick for their own team, referees may name the team they pick for.
+/// Returns the team along with whether the sender is a referee.
+async fn resolve_picking_team<C: Context>(
+    ctx: &C,
+    match_id: i64,
+    sender: &Session,
+    colour: Option<&str>,
+) -> ServiceResult<(MatchTeam, bool)> {
+    let referee = multiplayer::is_referee(ctx, match_id, sender.user_id).await?;
+    if let Some(colour) = colour {
+        if !referee {
+            return Err(AppError::MultiplayerUnauthorized);
+        }
+        return match colour.to_lowercase().as_str() {
+            "red" => Ok((MatchTeam::Red, referee)),
+            "blue" => Ok((MatchTeam::Blue, referee)),
+            _ => Err(AppError::CommandsInvalidArgument(
+                "Team colour must be red or blue.",
+            )),
+        };
+    }
+
+    let (_, slot) = multiplayer::fetch_session_slot(ctx, match_id, sender.session_id).await?;
+    match slot.team {
+        MatchTeam::Red => Ok((MatchTeam::Red, referee)),
+        MatchTeam::Blue => Ok((MatchTeam::Blue, referee)),
+        _ => Err(AppError::CommandsInvalidArgument(
+            "You are not part of a team.",
+        )),
+    }
+}
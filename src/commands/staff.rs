@@ -1,19 +1,29 @@
 use crate::adapters::discord;
-use crate::commands::CommandResult;
+use crate::commands::{CommandResult, FromCommandArgs};
+use crate::common::chat::format_duration;
 use crate::common::context::Context;
+use crate::common::error::{AppError, ServiceResult};
 use crate::common::website;
-use crate::entities::bot;
-use crate::models::bancho::LoginError;
+use crate::models::api_keys::ApiKeyScope;
 use crate::models::beatmaps::RankedStatus;
+use crate::models::chat_filters::{FilterAction, FilterRuleType};
+use crate::models::client_builds::{BuildPolicy, BuildVersion};
+use crate::models::login_attempts::LoginAttemptScope;
+use crate::models::login_notices::{NoticeDelivery, NoticeTarget};
 use crate::models::privileges::Privileges;
 use crate::models::sessions::Session;
-use crate::repositories::streams::StreamName;
-use crate::usecases::{badges, beatmaps, sessions, streams, tillerino, users};
-use bancho_protocol::messages::server::{ChatMessage, LoginResult};
-use bancho_protocol::structures::IrcMessage;
+use crate::usecases::{
+    api_keys, audit_logs, badges, beatmaps, chat_filters, client_builds, login_attempts,
+    login_notices, punishments, sessions, tillerino, users,
+};
 use bancho_service_macros::{FromCommandArgs, command};
+use chrono::{DateTime, TimeDelta, Utc};
+use std::net::IpAddr;
+use std::str::FromStr;
 use std::time::Duration;
 
+const DEFAULT_CENSOR_REPLACEMENT: &str = "***";
+
 #[derive(Debug, FromCommandArgs)]
 pub struct EditMapArgs {
     pub action: String,
@@ -232,36 +242,22 @@ pub struct BanArgs {
 )]
 pub async fn ban_user<C: Context>(ctx: &C, sender: &Session, args: BanArgs) -> CommandResult {
     let target_user = users::fetch_one_by_username_safe(ctx, &args.safe_username).await?;
+    let (duration, reason) = parse_punishment_reason(&args.reason);
 
-    // Ban the user (remove login privileges)
-    users::ban_user(ctx, target_user.user_id).await?;
-
-    // Send ban packet to online sessions
-    let target_sessions = sessions::fetch_by_username(ctx, &target_user.username).await?;
-    for session in target_sessions {
-        let ban_packet = LoginResult {
-            user_id: LoginError::Banned as i32,
-        };
-        streams::broadcast_message(
-            ctx,
-            StreamName::User(session.session_id),
-            ban_packet,
-            None,
-            None,
-        )
-        .await?;
-    }
+    // Ban the user (remove login privileges) and send the ban packet to online sessions
+    punishments::ban(ctx, target_user.user_id, reason, sender.user_id, duration).await?;
 
     let sender_profile = website::get_profile_link(sender.user_id);
     let target_profile = website::get_profile_link(target_user.user_id);
+    let length = describe_punishment_length(duration);
     let log_message = format!(
-        "[{}]({}) has banned [{}]({}) for: {}",
-        sender.username, sender_profile, target_user.username, target_profile, args.reason
+        "[{}]({}) has banned [{}]({}){length} for: {reason}",
+        sender.username, sender_profile, target_user.username, target_profile
     );
     let _ = discord::send_logs_red_embed("User Banned", &log_message, None).await;
 
     let osu_format_reply = format!(
-        "[{} {}] has been banned",
+        "[{} {}] has been banned{length}",
         target_profile, target_user.username
     );
     Ok(Some(osu_format_reply))
@@ -312,40 +308,22 @@ pub async fn restrict_user<C: Context>(
     args: RestrictArgs,
 ) -> CommandResult {
     let target_user = users::fetch_one_by_username_safe(ctx, &args.safe_username).await?;
+    let (duration, reason) = parse_punishment_reason(&args.reason);
 
-    // Restrict the user (remove publicly visible privileges)
-    users::restrict_user(ctx, target_user.user_id).await?;
-
-    // Notify online sessions
-    let target_sessions = sessions::fetch_by_username(ctx, &target_user.username).await?;
-    for session in target_sessions {
-        // Send restriction notification packet
-        let restriction_message = IrcMessage {
-            recipient: &target_user.username,
-            sender: bot::BOT_NAME,
-            sender_id: bot::BOT_ID as _,
-            text: "Your account is now in restricted mode. Visit the website for more information.",
-        };
-        streams::broadcast_message(
-            ctx,
-            StreamName::User(session.session_id),
-            ChatMessage(&restriction_message),
-            None,
-            None,
-        )
-        .await?;
-    }
+    // Restrict the user (remove publicly visible privileges) and notify online sessions
+    punishments::restrict(ctx, target_user.user_id, reason, sender.user_id, duration).await?;
 
     let sender_profile = website::get_profile_link(sender.user_id);
     let target_profile = website::get_profile_link(target_user.user_id);
+    let length = describe_punishment_length(duration);
     let log_message = format!(
-        "[{}]({}) has restricted [{}]({}) for: {}",
-        sender.username, sender_profile, target_user.username, target_profile, args.reason
+        "[{}]({}) has restricted [{}]({}){length} for: {reason}",
+        sender.username, sender_profile, target_user.username, target_profile
     );
     let _ = discord::send_logs_red_embed("User Restricted", &log_message, None).await;
     let osu_format_reply = format!(
-        "[{} {}] has been restricted for: {}",
-        target_profile, target_user.username, args.reason
+        "[{} {}] has been restricted{length} for: {reason}",
+        target_profile, target_user.username
     );
     Ok(Some(osu_format_reply))
 }
@@ -461,6 +439,48 @@ pub async fn unfreeze_user<C: Context>(
     Ok(Some(osu_format_reply))
 }
 
+#[derive(Debug, FromCommandArgs)]
+pub struct UnlockArgs {
+    /// Either a username or an IP address
+    pub target: String,
+}
+
+/// Lifts the login lockout of a username or a network after too many failed attempts.
+#[command(
+    "unlock",
+    required_privileges = Privileges::AdminManageUsers,
+    forward_message = false,
+)]
+pub async fn unlock_login<C: Context>(
+    ctx: &C,
+    sender: &Session,
+    args: UnlockArgs,
+) -> CommandResult {
+    let target = args.target.trim();
+    let scope = match IpAddr::from_str(target) {
+        Ok(ip_address) => LoginAttemptScope::Ip(ip_address),
+        Err(_) => LoginAttemptScope::Username(target),
+    };
+    if !login_attempts::unlock(ctx, scope).await? {
+        return Ok(Some(format!(
+            "{target} has no failed login attempts or lockouts."
+        )));
+    }
+
+    let sender_profile = website::get_profile_link(sender.user_id);
+    let log_message = format!(
+        "[{}]({}) has lifted the login lockout of {} `{}`",
+        sender.username,
+        sender_profile,
+        scope.as_str(),
+        target
+    );
+    let _ = discord::send_logs_blue_embed("Login Lockout Lifted", &log_message, None).await;
+    Ok(Some(format!(
+        "The login lockout of {target} has been lifted."
+    )))
+}
+
 #[derive(Debug, FromCommandArgs)]
 pub struct WhitelistArgs {
     pub safe_username: String,
@@ -582,3 +602,408 @@ pub async fn unsilence_user<C: Context>(
     );
     Ok(Some(osu_format_reply))
 }
+
+#[derive(Debug, FromCommandArgs)]
+pub struct ChatFilterArgs {
+    pub action: String,
+    pub args: Option<String>,
+}
+
+#[derive(Debug, FromCommandArgs)]
+pub struct AddChatFilterArgs {
+    pub rule_type: String,
+    pub action: String,
+    pub pattern: Option<String>,
+}
+
+#[command(
+    "filter",
+    required_privileges = Privileges::AdminChatMod,
+)]
+pub async fn chat_filter<C: Context>(
+    ctx: &C,
+    sender: &Session,
+    args: ChatFilterArgs,
+) -> CommandResult {
+    match args.action.as_str() {
+        "add" => {
+            let args = AddChatFilterArgs::from_args(args.args.as_deref())?;
+            let rule_type = FilterRuleType::from_str(&args.rule_type)?;
+            let action = parse_filter_action(&args.action)?;
+            let pattern = args.pattern.unwrap_or_default();
+            if pattern.is_empty() && rule_type != FilterRuleType::InviteLinks {
+                return Ok(Some("Please provide a pattern for this rule.".to_owned()));
+            }
+
+            let filter_id =
+                chat_filters::create(ctx, rule_type, &pattern, action, sender.user_id).await?;
+            Ok(Some(format!("Chat filter #{filter_id} has been added.")))
+        }
+        "list" => {
+            let filters = chat_filters::fetch_all(ctx).await?;
+            if filters.is_empty() {
+                return Ok(Some("There are no chat filters.".to_owned()));
+            }
+
+            let mut response = "Chat filters:\n".to_owned();
+            for filter in filters.iter() {
+                let action = match &filter.action {
+                    FilterAction::Censor(replacement) => format!("censor ({replacement})"),
+                    FilterAction::Silence(seconds) => format!("silence ({seconds}s)"),
+                    action => action.as_str().to_owned(),
+                };
+                response.push_str(&format!(
+                    "#{} {} -> {}: {}\n",
+                    filter.id,
+                    filter.rule_type.as_str(),
+                    action,
+                    filter.pattern
+                ));
+            }
+            Ok(Some(response))
+        }
+        "remove" => {
+            let filter_id = i64::from_args(args.args.as_deref())?;
+            chat_filters::delete(ctx, filter_id).await?;
+            Ok(Some(format!("Chat filter #{filter_id} has been removed.")))
+        }
+        _ => Ok(Some(
+            "Invalid action! Valid actions are: add, list, remove".to_owned(),
+        )),
+    }
+}
+
+#[derive(Debug, FromCommandArgs)]
+pub struct ApiKeyArgs {
+    pub action: String,
+    pub args: Option<String>,
+}
+
+#[derive(Debug, FromCommandArgs)]
+pub struct CreateApiKeyArgs {
+    pub scopes: String,
+    pub name: String,
+}
+
+#[command(
+    "apikey",
+    required_privileges = Privileges::AdminCaker,
+    forward_message = false,
+)]
+pub async fn api_key<C: Context>(ctx: &C, sender: &Session, args: ApiKeyArgs) -> CommandResult {
+    match args.action.as_str() {
+        "create" => {
+            let args = CreateApiKeyArgs::from_args(args.args.as_deref())?;
+            let scopes = ApiKeyScope::parse_list(&args.scopes)?;
+            let (key_id, key) = api_keys::create(ctx, &args.name, &scopes, sender.user_id).await?;
+            Ok(Some(format!(
+                "API key #{key_id} has been created, it will not be shown again: {key}"
+            )))
+        }
+        "list" => {
+            let keys = api_keys::fetch_active(ctx).await?;
+            if keys.is_empty() {
+                return Ok(Some("There are no API keys.".to_owned()));
+            }
+
+            let mut response = "API keys:\n".to_owned();
+            for key in keys.iter() {
+                response.push_str(&format!(
+                    "#{} {}: {}\n",
+                    key.id,
+                    key.name,
+                    ApiKeyScope::format_list(&key.scopes)
+                ));
+            }
+            Ok(Some(response))
+        }
+        "revoke" => {
+            let key_id = i64::from_args(args.args.as_deref())?;
+            api_keys::revoke(ctx, key_id).await?;
+            Ok(Some(format!("API key #{key_id} has been revoked.")))
+        }
+        _ => Ok(Some(
+            "Invalid action! Valid actions are: create, list, revoke".to_owned(),
+        )),
+    }
+}
+
+#[derive(Debug, FromCommandArgs)]
+pub struct LoginNoticeArgs {
+    pub action: String,
+    pub args: Option<String>,
+}
+
+#[derive(Debug, FromCommandArgs)]
+pub struct AddLoginNoticeArgs {
+    pub target: String,
+    pub delivery: String,
+    pub message: String,
+}
+
+/// Manages the notices shown to users when they log in.
+#[command(
+    "notice",
+    required_privileges = Privileges::AdminSendAlerts,
+    forward_message = false,
+)]
+pub async fn login_notice<C: Context>(
+    ctx: &C,
+    sender: &Session,
+    args: LoginNoticeArgs,
+) -> CommandResult {
+    match args.action.as_str() {
+        "add" => {
+            let args = AddLoginNoticeArgs::from_args(args.args.as_deref())?;
+            let target = NoticeTarget::from_str(&args.target)?;
+            let delivery = NoticeDelivery::from_str(&args.delivery)?;
+            let (starts_at, duration, message) = parse_notice_schedule(&args.message);
+            let ends_at = duration.map(|duration| {
+                starts_at.unwrap_or_else(Utc::now) + TimeDelta::seconds(duration.as_secs() as _)
+            });
+            let notice_id = login_notices::create(
+                ctx,
+                message,
+                target,
+                delivery,
+                starts_at,
+                ends_at,
+                sender.user_id,
+            )
+            .await?;
+
+            let mut response = format!("Login notice #{notice_id} has been added");
+            if let Some(starts_at) = starts_at {
+                response.push_str(&format!(
+                    ", starting at {} UTC",
+                    starts_at.format("%Y-%m-%d %H:%M")
+                ));
+            }
+            if let Some(duration) = duration {
+                response.push_str(&format!(" for {}", format_duration(duration.as_secs())));
+            }
+            response.push('.');
+            Ok(Some(response))
+        }
+        "list" => {
+            let notices = login_notices::fetch_all(ctx).await?;
+            if notices.is_empty() {
+                return Ok(Some("There are no login notices.".to_owned()));
+            }
+
+            let mut response = "Login notices:\n".to_owned();
+            for notice in notices.iter() {
+                let starts_at = match notice.starts_at {
+                    Some(starts_at) => format!(" from {}", starts_at.format("%Y-%m-%d %H:%M")),
+                    None => String::new(),
+                };
+                let ends_at = match notice.ends_at {
+                    Some(ends_at) => format!(" until {}", ends_at.format("%Y-%m-%d %H:%M")),
+                    None => String::new(),
+                };
+                response.push_str(&format!(
+                    "#{} {} ({}){starts_at}{ends_at}: {}\n",
+                    notice.id,
+                    notice.target.as_str(),
+                    notice.delivery.as_str(),
+                    notice.message
+                ));
+            }
+            Ok(Some(response))
+        }
+        "remove" => {
+            let notice_id = i64::from_args(args.args.as_deref())?;
+            login_notices::delete(ctx, notice_id).await?;
+            Ok(Some(format!("Login notice #{notice_id} has been removed.")))
+        }
+        _ => Ok(Some(
+            "Invalid action! Valid actions are: add, list, remove".to_owned(),
+        )),
+    }
+}
+
+#[derive(Debug, FromCommandArgs)]
+pub struct ClientBuildArgs {
+    pub action: String,
+    pub args: Option<String>,
+}
+
+#[derive(Debug, FromCommandArgs)]
+pub struct AddClientBuildArgs {
+    pub build: String,
+    pub reason: String,
+}
+
+/// Manages the client builds which are always allowed or denied on login.
+#[command(
+    "clientbuild",
+    required_privileges = Privileges::AdminManageServers,
+    forward_message = false,
+)]
+pub async fn client_build<C: Context>(
+    ctx: &C,
+    sender: &Session,
+    args: ClientBuildArgs,
+) -> CommandResult {
+    match args.action.as_str() {
+        action @ ("allow" | "deny") => {
+            let policy = BuildPolicy::from_str(action)?;
+            let args = AddClientBuildArgs::from_args(args.args.as_deref())?;
+            let version = BuildVersion::from_str(&args.build)?;
+            let build_id =
+                client_builds::create(ctx, version, policy, &args.reason, sender.user_id).await?;
+            let outcome = match policy {
+                BuildPolicy::Allow => "allowed",
+                BuildPolicy::Deny => "denied",
+            };
+            Ok(Some(format!(
+                "Client build #{build_id} ({version}) is now {outcome}."
+            )))
+        }
+        "list" => {
+            let builds = client_builds::fetch_all(ctx).await?;
+            if builds.is_empty() {
+                return Ok(Some("There are no client build rules.".to_owned()));
+            }
+
+            let mut response = "Client builds:\n".to_owned();
+            for build in builds.iter() {
+                response.push_str(&format!(
+                    "#{} {} ({}): {}\n",
+                    build.id,
+                    build.version,
+                    build.policy.as_str(),
+                    build.reason
+                ));
+            }
+            Ok(Some(response))
+        }
+        "remove" => {
+            let build_id = i64::from_args(args.args.as_deref())?;
+            client_builds::delete(ctx, build_id).await?;
+            Ok(Some(format!("Client build #{build_id} has been removed.")))
+        }
+        _ => Ok(Some(
+            "Invalid action! Valid actions are: allow, deny, list, remove".to_owned(),
+        )),
+    }
+}
+
+/// Shows how many online sessions use each osu! version.
+#[command(
+    "clients",
+    required_privileges = Privileges::AdminManageServers,
+    forward_message = false,
+)]
+pub async fn client_versions<C: Context>(ctx: &C, _sender: &Session) -> CommandResult {
+    let versions = client_builds::fetch_version_distribution(ctx).await?;
+    let total_sessions: usize = versions.iter().map(|(_, count)| count).sum();
+    let mut response = format!("Client versions of {total_sessions} online sessions:\n");
+    for (version, count) in versions.iter() {
+        response.push_str(&format!("{version}: {count}\n"));
+    }
+    Ok(Some(response))
+}
+
+const AUDIT_LOG_LIMIT: u32 = 10;
+
+#[derive(Debug, FromCommandArgs)]
+pub struct AuditArgs {
+    pub safe_username: String,
+}
+
+/// Shows the most recent audit log entries performed by or against a user.
+#[command(
+    "audit",
+    required_privileges = Privileges::AdminViewAuditLogs,
+    forward_message = false,
+)]
+pub async fn audit<C: Context>(ctx: &C, _sender: &Session, args: AuditArgs) -> CommandResult {
+    let user = users::fetch_one_by_username_safe(ctx, &args.safe_username).await?;
+    let logs = audit_logs::fetch_by_user_id(ctx, user.user_id, AUDIT_LOG_LIMIT).await?;
+    if logs.is_empty() {
+        return Ok(Some(format!(
+            "There are no audit logs for {}.",
+            user.username
+        )));
+    }
+
+    let mut response = format!("Recent audit logs for {}:\n", user.username);
+    for log in logs.iter() {
+        let outcome = match &log.error {
+            None => "ok",
+            Some(error) => error,
+        };
+        response.push_str(&format!(
+            "#{} {} {}: {} {} ({outcome})\n",
+            log.id,
+            log.created_at.format("%Y-%m-%d %H:%M"),
+            log.username,
+            log.action,
+            log.arguments.as_deref().unwrap_or_default(),
+        ));
+    }
+    Ok(Some(response))
+}
+
+/// Punishment reasons may start with a duration, e.g. `7d cheating`.
+/// Punishments without one are permanent.
+fn parse_punishment_reason(reason: &str) -> (Option<Duration>, &str) {
+    let Some((duration, rest)) = reason.split_once(' ') else {
+        return (None, reason);
+    };
+    match Duration::from_args(Some(duration)) {
+        Ok(duration) if !duration.is_zero() => (Some(duration), rest),
+        _ => (None, reason),
+    }
+}
+
+fn describe_punishment_length(duration: Option<Duration>) -> String {
+    match duration {
+        Some(duration) => format!(" for {}", format_duration(duration.as_secs())),
+        None => String::new(),
+    }
+}
+
+/// Notices may start with the date they are shown from and how long they are shown,
+/// e.g. `2026-11-01T18:00:00Z 7d Scheduled maintenance on Sunday`. Both are optional.
+fn parse_notice_schedule(message: &str) -> (Option<DateTime<Utc>>, Option<Duration>, &str) {
+    let (starts_at, message) = match message.split_once(' ') {
+        Some((starts_at, rest)) => match DateTime::<Utc>::from_args(Some(starts_at)) {
+            Ok(starts_at) => (Some(starts_at), rest),
+            Err(_) => (None, message),
+        },
+        None => (None, message),
+    };
+    let (duration, message) = match message.split_once(' ') {
+        Some((duration, rest)) => match Duration::from_args(Some(duration)) {
+            Ok(duration) if !duration.is_zero() => (Some(duration), rest),
+            _ => (None, message),
+        },
+        None => (None, message),
+    };
+    (starts_at, duration, message)
+}
+
+/// Actions are written as `censor[:replacement]`, `drop`, `silence:<duration>` or `flag`.
+fn parse_filter_action(action: &str) -> ServiceResult<FilterAction> {
+    let (action, value) = match action.split_once(':') {
+        Some((action, value)) => (action, Some(value)),
+        None => (action, None),
+    };
+    match (action, value) {
+        ("censor", replacement) => Ok(FilterAction::Censor(
+            replacement.unwrap_or(DEFAULT_CENSOR_REPLACEMENT).to_owned(),
+        )),
+        ("drop", None) => Ok(FilterAction::Drop),
+        ("silence", Some(duration)) => {
+            let duration = Duration::from_args(Some(duration))?;
+            match duration.as_secs() {
+                0 => Err(AppError::ChatFiltersInvalidAction),
+                seconds => Ok(FilterAction::Silence(seconds as i64)),
+            }
+        }
+        ("flag", None) => Ok(FilterAction::Flag),
+        _ => Err(AppError::ChatFiltersInvalidAction),
+    }
+}
@@ -1,23 +1,125 @@
 use crate::commands;
-use crate::commands::{CommandResult, CommandRouterInstance};
+use crate::commands::{CommandResult, CommandRouterInstance, FromCommandArgs};
+use crate::common::chat::format_duration;
 use crate::common::context::Context;
+use crate::models::bancho_settings::BanchoSettingKey;
 use crate::models::privileges::Privileges;
 use crate::models::sessions::Session;
-use crate::usecases::bancho_settings;
-use bancho_service_macros::command;
+use crate::usecases::{bancho_settings, maintenance};
+use bancho_service_macros::{FromCommandArgs, command};
+use std::str::FromStr;
+use std::time::Duration;
 
-pub static COMMANDS: CommandRouterInstance = commands![maintenance];
+pub static COMMANDS: CommandRouterInstance =
+    commands![maintenance_mode, list_settings, set_setting];
+
+/// Scheduled windows last this long unless a length is given.
+const DEFAULT_MAINTENANCE_LENGTH: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, FromCommandArgs)]
+pub struct MaintenanceArgs {
+    pub action: String,
+    pub args: Option<String>,
+}
+
+#[derive(Debug, FromCommandArgs)]
+pub struct ScheduleMaintenanceArgs {
+    pub starts_in: Duration,
+    pub reason: String,
+}
 
 #[command(
     "maintenance",
     required_privileges = Privileges::AdminCaker,
 )]
-pub async fn maintenance<C: Context>(ctx: &C, _sender: &Session) -> CommandResult {
-    let is_active = bancho_settings::toggle_maintenance(ctx).await?;
-    // TODO: kick all non-admin users unless they're in a multiplayer match
-    let on_off = match is_active {
-        true => "on",
-        false => "off",
+pub async fn maintenance_mode<C: Context>(
+    ctx: &C,
+    _sender: &Session,
+    args: Option<MaintenanceArgs>,
+) -> CommandResult {
+    let Some(args) = args else {
+        let is_active = bancho_settings::toggle_maintenance(ctx).await?;
+        if !is_active {
+            bancho_settings::clear_maintenance_window(ctx).await?;
+        }
+        let on_off = match is_active {
+            true => "on",
+            false => "off",
+        };
+        return Ok(Some(format!("Turned {on_off} maintenance mode.")));
     };
-    Ok(Some(format!("Turned {on_off} maintenance mode.")))
+
+    match args.action.as_str() {
+        "schedule" => {
+            let args = ScheduleMaintenanceArgs::from_args(args.args.as_deref())?;
+            let (length, reason) = parse_maintenance_reason(&args.reason);
+            let length = length.unwrap_or(DEFAULT_MAINTENANCE_LENGTH);
+            let window = maintenance::schedule(ctx, args.starts_in, length, reason).await?;
+            Ok(Some(format!(
+                "Scheduled maintenance in {} for {}, starting at {} UTC.",
+                format_duration(args.starts_in.as_secs()),
+                format_duration(length.as_secs()),
+                window.starts_at.format("%Y-%m-%d %H:%M:%S"),
+            )))
+        }
+        "cancel" => match maintenance::cancel(ctx).await? {
+            true => Ok(Some("Cancelled the scheduled maintenance.".to_owned())),
+            false => Ok(Some("Turned off maintenance mode.".to_owned())),
+        },
+        _ => Ok(Some(
+            "Invalid action! Valid actions are: schedule, cancel".to_owned(),
+        )),
+    }
+}
+
+/// Reasons may start with the length of the window, e.g. `2h "database migration"`.
+fn parse_maintenance_reason(reason: &str) -> (Option<Duration>, &str) {
+    let (length, reason) = match reason.split_once(' ') {
+        Some((length, rest)) => match Duration::from_args(Some(length)) {
+            Ok(length) if !length.is_zero() => (Some(length), rest),
+            _ => (None, reason),
+        },
+        None => (None, reason),
+    };
+    (length, reason.trim().trim_matches('"'))
+}
+
+#[command(
+    "settings",
+    required_privileges = Privileges::AdminManageSettings,
+    forward_message = false,
+)]
+pub async fn list_settings<C: Context>(ctx: &C, _sender: &Session) -> CommandResult {
+    let settings = bancho_settings::fetch_current(ctx).await?;
+    let mut response = "Settings:\n".to_owned();
+    for key in BanchoSettingKey::ALL {
+        let value = settings.get(key);
+        response.push_str(&format!("{}: {}\n", key.as_str(), value.trim()));
+    }
+    Ok(Some(response))
+}
+
+#[derive(Debug, FromCommandArgs)]
+pub struct SetSettingArgs {
+    pub key: String,
+    pub value: String,
+}
+
+#[command(
+    "set",
+    required_privileges = Privileges::AdminManageSettings,
+    forward_message = false,
+)]
+pub async fn set_setting<C: Context>(
+    ctx: &C,
+    _sender: &Session,
+    args: SetSettingArgs,
+) -> CommandResult {
+    let key = BanchoSettingKey::from_str(&args.key)?;
+    let settings = bancho_settings::set(ctx, key, &args.value).await?;
+    Ok(Some(format!(
+        "Set {} to {}.",
+        key.as_str(),
+        settings.get(key).trim()
+    )))
 }
@@ -1,3 +1,21 @@
 pub fn safe_username(username: &str) -> String {
     username.to_lowercase().trim().replace(' ', "_")
 }
+
+/// Formats a duration like `1d 2h 3m 4s`, omitting empty units.
+pub fn format_duration(total_seconds: u64) -> String {
+    const UNITS: [(u64, &str); 4] = [(24 * 60 * 60, "d"), (60 * 60, "h"), (60, "m"), (1, "s")];
+    let mut remaining = total_seconds;
+    let mut parts = vec![];
+    for (unit_seconds, suffix) in UNITS {
+        let amount = remaining / unit_seconds;
+        if amount > 0 {
+            parts.push(format!("{amount}{suffix}"));
+            remaining %= unit_seconds;
+        }
+    }
+    match parts.is_empty() {
+        true => "0s".to_owned(),
+        false => parts.join(" "),
+    }
+}
@@ -25,6 +25,10 @@ pub enum AppError {
     InteractionBlocked,
     MaintenanceModeEnabled,
 
+    ApiKeysInvalidScope,
+    ApiKeysMissingScope,
+    ApiKeysNotFound,
+
     BeatmapsNotFound,
 
     BadgesNotFound,
@@ -32,6 +36,12 @@ pub enum AppError {
     ChannelsNotFound,
     ChannelsUnauthorized,
     ChannelsInvalidName,
+    ChannelsAlreadyExists,
+    ChannelsBanned,
+    ChannelsLimitReached,
+    ChannelsMuted,
+    ChannelsSlowMode,
+    ChannelsSystemChannel,
 
     /// 0: Syntax, 1: Type Signature, 2: Typed Syntax
     CommandsInvalidSyntax(&'static str, &'static str, &'static str),
@@ -39,9 +49,23 @@ pub enum AppError {
     CommandsUnknownCommand,
     CommandsUnauthorized,
 
+    ChatFiltersInvalidRule,
+    ChatFiltersInvalidAction,
+    ChatFiltersInvalidPattern,
+    ChatFiltersNotFound,
+
+    ClientBuildsInvalidVersion,
+    ClientBuildsInvalidPolicy,
+    ClientBuildsNotFound,
+
+    LoginNoticesInvalidTarget,
+    LoginNoticesInvalidDelivery,
+    LoginNoticesNotFound,
+
     MessagesInvalidLength,
     MessagesUserAutoSilenced,
     MessagesUserSilenced,
+    MessagesFiltered,
 
     MultiplayerNotFound,
     MultiplayerUnauthorized,
@@ -53,6 +77,8 @@ pub enum AppError {
 
     PresencesNotFound,
 
+    RateLimitsExceeded,
+
     RelationshipsNotFound,
 
     UsersNotFound,
@@ -63,8 +89,22 @@ pub enum AppError {
     SessionsInvalidCredentials,
     SessionsNotFound,
     SessionsLimitReached,
+    SessionsLockedOut,
+
+    SettingsInvalidKey,
+    SettingsInvalidValue,
 
     StreamsInvalidKey,
+
+    TournamentsNotEnabled,
+    TournamentsInvalidMapCode,
+    TournamentsMapNotInPool,
+    TournamentsMapUnavailable,
+    TournamentsTiebreakerRequired,
+    TournamentsMatchDecided,
+    TournamentsNotYourTurn,
+    TournamentsBansPending,
+    TournamentsBanLimitReached,
 }
 
 impl<E: Into<anyhow::Error>> From<E> for AppError {
@@ -90,6 +130,10 @@ impl AppError {
             AppError::InteractionBlocked => "interaction_blocked",
             AppError::MaintenanceModeEnabled => "maintenance_mode_enabled",
 
+            AppError::ApiKeysInvalidScope => "api_keys.invalid_scope",
+            AppError::ApiKeysMissingScope => "api_keys.missing_scope",
+            AppError::ApiKeysNotFound => "api_keys.not_found",
+
             AppError::BeatmapsNotFound => "beatmaps.not_found",
 
             AppError::BadgesNotFound => "badges.not_found",
@@ -97,15 +141,35 @@ impl AppError {
             AppError::ChannelsNotFound => "channels.not_found",
             AppError::ChannelsUnauthorized => "channels.unauthorized",
             AppError::ChannelsInvalidName => "channels.invalid_name",
+            AppError::ChannelsAlreadyExists => "channels.already_exists",
+            AppError::ChannelsBanned => "channels.banned",
+            AppError::ChannelsLimitReached => "channels.limit_reached",
+            AppError::ChannelsMuted => "channels.muted",
+            AppError::ChannelsSlowMode => "channels.slow_mode",
+            AppError::ChannelsSystemChannel => "channels.system_channel",
 
             AppError::CommandsInvalidSyntax(_, _, _) => "commands.invalid_syntax",
             AppError::CommandsInvalidArgument(_) => "commands.invalid_argument",
             AppError::CommandsUnknownCommand => "commands.unknown_command",
             AppError::CommandsUnauthorized => "commands.unauthorized",
 
+            AppError::ChatFiltersInvalidRule => "chat_filters.invalid_rule",
+            AppError::ChatFiltersInvalidAction => "chat_filters.invalid_action",
+            AppError::ChatFiltersInvalidPattern => "chat_filters.invalid_pattern",
+            AppError::ChatFiltersNotFound => "chat_filters.not_found",
+
+            AppError::ClientBuildsInvalidVersion => "client_builds.invalid_version",
+            AppError::ClientBuildsInvalidPolicy => "client_builds.invalid_policy",
+            AppError::ClientBuildsNotFound => "client_builds.not_found",
+
+            AppError::LoginNoticesInvalidTarget => "login_notices.invalid_target",
+            AppError::LoginNoticesInvalidDelivery => "login_notices.invalid_delivery",
+            AppError::LoginNoticesNotFound => "login_notices.not_found",
+
             AppError::MessagesInvalidLength => "messages.invalid_length",
             AppError::MessagesUserAutoSilenced => "messages.user_auto_silenced",
             AppError::MessagesUserSilenced => "messages.user_silenced",
+            AppError::MessagesFiltered => "messages.filtered",
 
             AppError::MultiplayerNotFound => "multiplayer.not_found",
             AppError::MultiplayerUnauthorized => "multiplayer.unauthorized",
@@ -117,6 +181,8 @@ impl AppError {
 
             AppError::PresencesNotFound => "presences.not_found",
 
+            AppError::RateLimitsExceeded => "rate_limits.exceeded",
+
             AppError::RelationshipsNotFound => "relationships.not_found",
 
             AppError::UsersNotFound => "users.not_found",
@@ -127,8 +193,22 @@ impl AppError {
             AppError::SessionsInvalidCredentials => "sessions.invalid_credentials",
             AppError::SessionsNotFound => "sessions.not_found",
             AppError::SessionsLimitReached => "sessions.limit_reached",
+            AppError::SessionsLockedOut => "sessions.locked_out",
+
+            AppError::SettingsInvalidKey => "settings.invalid_key",
+            AppError::SettingsInvalidValue => "settings.invalid_value",
 
             AppError::StreamsInvalidKey => "streams.invalid_key",
+
+            AppError::TournamentsNotEnabled => "tournaments.not_enabled",
+            AppError::TournamentsInvalidMapCode => "tournaments.invalid_map_code",
+            AppError::TournamentsMapNotInPool => "tournaments.map_not_in_pool",
+            AppError::TournamentsMapUnavailable => "tournaments.map_unavailable",
+            AppError::TournamentsTiebreakerRequired => "tournaments.tiebreaker_required",
+            AppError::TournamentsMatchDecided => "tournaments.match_decided",
+            AppError::TournamentsNotYourTurn => "tournaments.not_your_turn",
+            AppError::TournamentsBansPending => "tournaments.bans_pending",
+            AppError::TournamentsBanLimitReached => "tournaments.ban_limit_reached",
         }
     }
 
@@ -138,12 +218,24 @@ impl AppError {
             AppError::Unauthorized => "You are not authorized to perform this action.",
             AppError::DecodingRequestFailed => "Failed to decode request",
             AppError::InternalServerError(_) => "An internal server error has occurred.",
-            AppError::UnsupportedClientVersion => "Client is unsupported",
-            AppError::ClientTooOld => "Client is too old",
+            AppError::UnsupportedClientVersion => {
+                "This osu! version is not supported, please update your client."
+            }
+            AppError::ClientTooOld => {
+                "Your osu! client is too old to play on Akatsuki, please update your client."
+            }
             AppError::InteractionBlocked => {
                 "You do not have permission to interact with this user."
             }
-            AppError::MaintenanceModeEnabled => "Maintenance mode is enabled.",
+            AppError::MaintenanceModeEnabled => {
+                "Akatsuki is currently down for maintenance, please try again later."
+            }
+
+            AppError::ApiKeysInvalidScope => {
+                "Invalid scope. Valid scopes are: *, kick, silence, restrict, ban, freeze, alert, beatmaps, maintenance, audit"
+            }
+            AppError::ApiKeysMissingScope => "This API key is not allowed to perform this action.",
+            AppError::ApiKeysNotFound => "API key not found.",
 
             AppError::BeatmapsNotFound => "Beatmap could not be found.",
 
@@ -154,6 +246,14 @@ impl AppError {
                 "You do not have permission to send messages to this channel."
             }
             AppError::ChannelsInvalidName => "Invalid Channel Name (must start with `#`)",
+            AppError::ChannelsAlreadyExists => "A channel with this name already exists.",
+            AppError::ChannelsBanned => "You are banned from this channel.",
+            AppError::ChannelsLimitReached => "You cannot own any more channels.",
+            AppError::ChannelsMuted => "You are muted in this channel.",
+            AppError::ChannelsSlowMode => {
+                "This channel is in slow mode. Please wait before sending another message."
+            }
+            AppError::ChannelsSystemChannel => "System channels cannot be deleted or transferred.",
 
             AppError::CommandsInvalidSyntax(_, _, _) => "Invalid Command Syntax",
             AppError::CommandsInvalidArgument(_) => "Invalid Command Argument",
@@ -162,6 +262,31 @@ impl AppError {
                 "You do not have sufficient privileges to use this command."
             }
 
+            AppError::ChatFiltersInvalidRule => {
+                "Invalid rule type. Valid rule types are: literal, regex, allow_domains, deny_domains, invite_links"
+            }
+            AppError::ChatFiltersInvalidAction => {
+                "Invalid action. Valid actions are: censor[:replacement], drop, silence:<duration>, flag"
+            }
+            AppError::ChatFiltersInvalidPattern => "The filter pattern is invalid.",
+            AppError::ChatFiltersNotFound => "Chat filter not found.",
+
+            AppError::ClientBuildsInvalidVersion => {
+                "Invalid build. Builds are written as <yyyymmdd>[.<minor>], e.g. 20240123.2"
+            }
+            AppError::ClientBuildsInvalidPolicy => {
+                "Invalid policy. Valid policies are: allow, deny"
+            }
+            AppError::ClientBuildsNotFound => "Client build not found.",
+
+            AppError::LoginNoticesInvalidTarget => {
+                "Invalid target. Valid targets are: everyone, donors, staff, restricted, frozen, pending_verification"
+            }
+            AppError::LoginNoticesInvalidDelivery => {
+                "Invalid delivery. Valid deliveries are: alert, notification, bot_message"
+            }
+            AppError::LoginNoticesNotFound => "Login notice not found.",
+
             AppError::MessagesInvalidLength => {
                 "Your message was too short/long. It has not been sent."
             }
@@ -169,6 +294,9 @@ impl AppError {
                 "You have sent too many messages in a short period of time."
             }
             AppError::MessagesUserSilenced => "You have been silenced.",
+            AppError::MessagesFiltered => {
+                "Your message was blocked by the chat filter. It has not been sent."
+            }
 
             AppError::MultiplayerNotFound => "The multiplayer match could not be found.",
             AppError::MultiplayerUnauthorized => {
@@ -184,6 +312,10 @@ impl AppError {
 
             AppError::PresencesNotFound => "Presence not found",
 
+            AppError::RateLimitsExceeded => {
+                "You are doing this too often. Please wait a moment and try again."
+            }
+
             AppError::RelationshipsNotFound => "Relationship not found",
 
             AppError::UsersNotFound => "This user does not exist.",
@@ -198,20 +330,59 @@ impl AppError {
             AppError::SessionsLimitReached => {
                 "You have reached the max amount of logins. Please wait a few minutes or log out in other clients."
             }
+            AppError::SessionsLockedOut => {
+                "Too many failed login attempts, please try again later."
+            }
+
+            AppError::SettingsInvalidKey => "Unknown setting! Use !system settings to list them.",
+            AppError::SettingsInvalidValue => "Invalid value for this setting.",
 
             AppError::StreamsInvalidKey => "Invalid Streams Key",
+
+            AppError::TournamentsNotEnabled => {
+                "Tournament mode is not enabled for this match. Use !mp bestof to enable it."
+            }
+            AppError::TournamentsInvalidMapCode => {
+                "Invalid map code. Map codes start with their mods, e.g. NM1, HDHR2, FM1 or TB."
+            }
+            AppError::TournamentsMapNotInPool => "This map is not part of the mappool.",
+            AppError::TournamentsMapUnavailable => "This map has already been picked or banned.",
+            AppError::TournamentsTiebreakerRequired => "The next pick must be the tiebreaker.",
+            AppError::TournamentsMatchDecided => "This match has already been decided.",
+            AppError::TournamentsNotYourTurn => "It is not your team's turn.",
+            AppError::TournamentsBansPending => "All bans have to be made before picking.",
+            AppError::TournamentsBanLimitReached => "All bans have already been made.",
         }
     }
 
     pub const fn http_status_code(&self) -> StatusCode {
         match self {
             AppError::DecodingRequestFailed
+            | AppError::ApiKeysInvalidScope
             | AppError::ChannelsInvalidName
+            | AppError::ChannelsAlreadyExists
+            | AppError::ChannelsSystemChannel
             | AppError::CommandsInvalidSyntax(_, _, _)
             | AppError::CommandsInvalidArgument(_)
+            | AppError::ChatFiltersInvalidRule
+            | AppError::ChatFiltersInvalidAction
+            | AppError::ChatFiltersInvalidPattern
+            | AppError::ClientBuildsInvalidVersion
+            | AppError::ClientBuildsInvalidPolicy
+            | AppError::LoginNoticesInvalidTarget
+            | AppError::LoginNoticesInvalidDelivery
             | AppError::MessagesInvalidLength
             | AppError::MultiplayerInvalidSlotID
-            | AppError::StreamsInvalidKey => StatusCode::BAD_REQUEST,
+            | AppError::SettingsInvalidKey
+            | AppError::SettingsInvalidValue
+            | AppError::StreamsInvalidKey
+            | AppError::TournamentsInvalidMapCode
+            | AppError::TournamentsMapUnavailable
+            | AppError::TournamentsTiebreakerRequired
+            | AppError::TournamentsMatchDecided
+            | AppError::TournamentsNotYourTurn
+            | AppError::TournamentsBansPending
+            | AppError::TournamentsBanLimitReached => StatusCode::BAD_REQUEST,
 
             AppError::Unauthorized
             | AppError::ChannelsUnauthorized
@@ -223,16 +394,25 @@ impl AppError {
             AppError::UnsupportedClientVersion
             | AppError::ClientTooOld
             | AppError::InteractionBlocked
+            | AppError::ApiKeysMissingScope
+            | AppError::ChannelsBanned
+            | AppError::ChannelsLimitReached
+            | AppError::ChannelsMuted
             | AppError::MultiplayerMatchFull
             | AppError::SessionsLoginForbidden
             | AppError::SessionsLimitReached
             | AppError::MessagesUserSilenced
+            | AppError::MessagesFiltered
             | AppError::MaintenanceModeEnabled => StatusCode::FORBIDDEN,
 
-            AppError::BadgesNotFound
+            AppError::ApiKeysNotFound
+            | AppError::BadgesNotFound
             | AppError::BeatmapsNotFound
             | AppError::ChannelsNotFound
+            | AppError::ChatFiltersNotFound
+            | AppError::ClientBuildsNotFound
             | AppError::CommandsUnknownCommand
+            | AppError::LoginNoticesNotFound
             | AppError::MultiplayerNotFound
             | AppError::MultiplayerSlotNotFound
             | AppError::MultiplayerUserNotInMatch
@@ -240,8 +420,13 @@ impl AppError {
             | AppError::RelationshipsNotFound
             | AppError::UsersNotFound
             | AppError::ScoresNotFound
-            | AppError::SessionsNotFound => StatusCode::NOT_FOUND,
-            AppError::MessagesUserAutoSilenced => StatusCode::TOO_MANY_REQUESTS,
+            | AppError::SessionsNotFound
+            | AppError::TournamentsNotEnabled
+            | AppError::TournamentsMapNotInPool => StatusCode::NOT_FOUND,
+            AppError::ChannelsSlowMode
+            | AppError::MessagesUserAutoSilenced
+            | AppError::RateLimitsExceeded
+            | AppError::SessionsLockedOut => StatusCode::TOO_MANY_REQUESTS,
 
             AppError::Unexpected | AppError::InternalServerError(_) => {
                 StatusCode::INTERNAL_SERVER_ERROR
@@ -0,0 +1,146 @@
+use crate::adapters::push_gateway;
+use crate::common::context::Context;
+use crate::settings::AppSettings;
+use axum::Router;
+use axum::routing::get;
+use metrics::{counter, gauge, histogram};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use std::net::SocketAddr;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tracing::{error, info};
+
+pub const ONLINE_SESSIONS: &str = "bancho_online_sessions";
+pub const ONLINE_PRESENCES: &str = "bancho_online_presences";
+pub const ACTIVE_MATCHES: &str = "bancho_active_matches";
+pub const SPECTATOR_HOSTS: &str = "bancho_spectator_hosts";
+pub const EVENTS: &str = "bancho_events_total";
+pub const EVENT_DURATION: &str = "bancho_event_duration_seconds";
+pub const COMMANDS: &str = "bancho_commands_total";
+pub const STREAM_LENGTH: &str = "bancho_stream_length";
+pub const STREAM_PENDING_MESSAGES: &str = "bancho_stream_pending_messages";
+pub const STREAM_PENDING_BYTES: &str = "bancho_stream_pending_bytes";
+pub const POOL_CONNECTIONS: &str = "bancho_pool_connections";
+pub const POOL_IDLE_CONNECTIONS: &str = "bancho_pool_idle_connections";
+pub const POOL_MAX_CONNECTIONS: &str = "bancho_pool_max_connections";
+pub const PUBSUB_MESSAGES: &str = "bancho_pubsub_messages_total";
+
+const EVENT_DURATION_BUCKETS: [f64; 10] =
+    [0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0];
+const PENDING_MESSAGES_BUCKETS: [f64; 8] = [0.0, 1.0, 2.0, 5.0, 10.0, 25.0, 50.0, 100.0];
+const PENDING_BYTES_BUCKETS: [f64; 8] =
+    [0.0, 64.0, 256.0, 1024.0, 4096.0, 16384.0, 65536.0, 262144.0];
+
+static HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
+
+/// Installs the global prometheus recorder, every metric is labelled with the app component.
+pub fn initialize(settings: &AppSettings) -> anyhow::Result<()> {
+    let handle = PrometheusBuilder::new()
+        .add_global_label("component", &settings.app_component)
+        .set_buckets_for_metric(
+            Matcher::Full(EVENT_DURATION.to_owned()),
+            &EVENT_DURATION_BUCKETS,
+        )?
+        .set_buckets_for_metric(
+            Matcher::Full(STREAM_PENDING_MESSAGES.to_owned()),
+            &PENDING_MESSAGES_BUCKETS,
+        )?
+        .set_buckets_for_metric(
+            Matcher::Full(STREAM_PENDING_BYTES.to_owned()),
+            &PENDING_BYTES_BUCKETS,
+        )?
+        .install_recorder()?;
+    let _ = HANDLE.set(handle);
+    Ok(())
+}
+
+/// Renders all metrics in the prometheus text format.
+pub fn render() -> String {
+    match HANDLE.get() {
+        Some(handle) => {
+            handle.run_upkeep();
+            handle.render()
+        }
+        None => String::new(),
+    }
+}
+
+/// Exposes the metrics of components without an HTTP server,
+/// through a listener on the metrics port and/or by pushing them to the push gateway.
+pub async fn spawn_exporter(settings: &AppSettings) -> anyhow::Result<()> {
+    if let Some(port) = settings.metrics.port {
+        let addr = SocketAddr::from((settings.app_host, port));
+        info!("Metrics listening on {addr}");
+        let listener = TcpListener::bind(addr).await?;
+        let app = Router::new().route("/metrics", get(render_metrics));
+        tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, app).await {
+                error!("Metrics listener stopped: {e:?}");
+            }
+        });
+    }
+
+    if settings.metrics.push_gateway_url.is_some() {
+        let push_interval = settings.metrics.push_interval;
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(push_interval);
+            loop {
+                interval.tick().await;
+                push().await;
+            }
+        });
+    }
+    Ok(())
+}
+
+async fn render_metrics() -> String {
+    render()
+}
+
+/// Pushes the current metrics to the push gateway, if one is configured.
+pub async fn push() {
+    if let Err(e) = push_gateway::push(render()).await {
+        error!("Failed to push metrics: {e:?}");
+    }
+}
+
+pub fn record_event(event_type: String, elapsed: Duration, succeeded: bool) {
+    let outcome = outcome_label(succeeded);
+    counter!(EVENTS, "event_type" => event_type.clone(), "outcome" => outcome).increment(1);
+    histogram!(EVENT_DURATION, "event_type" => event_type).record(elapsed.as_secs_f64());
+}
+
+pub fn record_command(command_name: &'static str) {
+    counter!(COMMANDS, "command" => command_name).increment(1);
+}
+
+/// Records how much data a single poll read from the session's streams.
+pub fn record_pending_data(message_count: usize, byte_count: usize) {
+    histogram!(STREAM_PENDING_MESSAGES).record(message_count as f64);
+    histogram!(STREAM_PENDING_BYTES).record(byte_count as f64);
+}
+
+pub fn record_pubsub_message(channel_name: String, succeeded: bool) {
+    let outcome = outcome_label(succeeded);
+    counter!(PUBSUB_MESSAGES, "channel" => channel_name, "outcome" => outcome).increment(1);
+}
+
+pub fn record_pool_utilization<C: Context>(ctx: &C) {
+    let db = ctx.db_pool();
+    gauge!(POOL_CONNECTIONS, "pool" => "mysql").set(db.size() as f64);
+    gauge!(POOL_IDLE_CONNECTIONS, "pool" => "mysql").set(db.num_idle() as f64);
+    gauge!(POOL_MAX_CONNECTIONS, "pool" => "mysql").set(db.options().get_max_connections() as f64);
+
+    let redis = ctx.redis_pool().status();
+    gauge!(POOL_CONNECTIONS, "pool" => "redis").set(redis.size as f64);
+    gauge!(POOL_IDLE_CONNECTIONS, "pool" => "redis").set(redis.available as f64);
+    gauge!(POOL_MAX_CONNECTIONS, "pool" => "redis").set(redis.max_size as f64);
+}
+
+const fn outcome_label(succeeded: bool) -> &'static str {
+    match succeeded {
+        true => "success",
+        false => "failure",
+    }
+}
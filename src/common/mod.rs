@@ -4,8 +4,11 @@ pub mod context;
 pub mod env;
 pub mod error;
 pub mod location;
+pub mod metrics;
 pub mod osu_assets;
 pub mod redis_json;
 pub mod redis_pool;
+pub mod shutdown;
 pub mod state;
+pub mod versioned_cache;
 pub mod website;
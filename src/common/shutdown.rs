@@ -0,0 +1,60 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+static SHUTDOWN: Notify = Notify::const_new();
+
+static ACTIVE_CONNECTIONS: AtomicUsize = AtomicUsize::new(0);
+static DRAINED: Notify = Notify::const_new();
+
+/// Marks a long-lived connection (websocket, event stream) that has to
+/// be closed before the process may exit.
+pub struct ConnectionGuard(());
+
+pub fn begin() {
+    SHUTTING_DOWN.store(true, Ordering::SeqCst);
+    SHUTDOWN.notify_waiters();
+}
+
+pub fn is_shutting_down() -> bool {
+    SHUTTING_DOWN.load(Ordering::SeqCst)
+}
+
+/// Resolves once the shutdown has begun.
+pub async fn wait() {
+    // the future is registered on creation, so no notification can be missed
+    let notified = SHUTDOWN.notified();
+    if is_shutting_down() {
+        return;
+    }
+    notified.await;
+}
+
+pub fn track_connection() -> ConnectionGuard {
+    ACTIVE_CONNECTIONS.fetch_add(1, Ordering::SeqCst);
+    ConnectionGuard(())
+}
+
+/// Waits until all tracked connections are closed, at most for `timeout`.
+/// Returns whether all connections were closed in time.
+pub async fn wait_for_connections(timeout: Duration) -> bool {
+    let drained = async {
+        loop {
+            let notified = DRAINED.notified();
+            if ACTIVE_CONNECTIONS.load(Ordering::SeqCst) == 0 {
+                return;
+            }
+            notified.await;
+        }
+    };
+    tokio::time::timeout(timeout, drained).await.is_ok()
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        if ACTIVE_CONNECTIONS.fetch_sub(1, Ordering::SeqCst) == 1 {
+            DRAINED.notify_waiters();
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use crate::common::context::Context;
+use crate::common::error::ServiceResult;
+use redis::AsyncCommands;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock, RwLockWriteGuard};
+use std::time::{Duration, Instant};
+
+/// How often replicas compare their cache against the version in redis.
+const VERSION_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+struct CacheEntry<T> {
+    version: u64,
+    /// `None` once the data is known to be outdated.
+    checked_at: Option<Instant>,
+    value: Arc<T>,
+}
+
+/// Keeps data loaded from the database in memory on every replica.
+///
+/// The version in redis is bumped whenever the data changes,
+/// replicas reload the data once their cached version differs.
+pub struct VersionedCache<T> {
+    version_key: &'static str,
+    reload_channel: &'static str,
+    entry: RwLock<Option<CacheEntry<T>>>,
+}
+
+impl<T> VersionedCache<T> {
+    pub const fn new(version_key: &'static str, reload_channel: &'static str) -> Self {
+        Self {
+            version_key,
+            reload_channel,
+            entry: RwLock::new(None),
+        }
+    }
+
+    /// Returns the cached value, calling `load` with the current version if it is outdated.
+    pub async fn fetch<C, F, Fut>(&self, ctx: &C, load: F) -> ServiceResult<Arc<T>>
+    where
+        C: Context,
+        F: FnOnce(u64) -> Fut,
+        Fut: Future<Output = ServiceResult<T>>,
+    {
+        if let Some(value) = self.fetch_recently_checked() {
+            return Ok(value);
+        }
+
+        let version = self.fetch_version(ctx).await?;
+        if let Some(value) = self.fetch_with_version(version) {
+            return Ok(value);
+        }
+
+        let value = Arc::new(load(version).await?);
+        *self.write() = Some(CacheEntry {
+            version,
+            checked_at: Some(Instant::now()),
+            value: value.clone(),
+        });
+        Ok(value)
+    }
+
+    /// Returns the last loaded value without checking for changes.
+    pub fn cached(&self) -> Option<Arc<T>> {
+        let entry = self.entry.read().expect("versioned cache poisoned");
+        entry.as_ref().map(|entry| entry.value.clone())
+    }
+
+    /// Bumps the version, so every replica reloads the data.
+    /// The new version is published as a hint for other services listening on the channel.
+    pub async fn reload<C: Context>(&self, ctx: &C) -> ServiceResult<()> {
+        // keep serving the local copy to `cached` until the next fetch replaces it
+        if let Some(entry) = self.write().as_mut() {
+            entry.checked_at = None;
+        }
+        let version = self.increment_version(ctx).await?;
+        let mut redis = ctx.redis().await?;
+        let _: () = redis.publish(self.reload_channel, version).await?;
+        Ok(())
+    }
+
+    /// Handles a message on the reload channel, bumping the version unless
+    /// the message was published by [`Self::reload`], which already did.
+    pub async fn handle_reload_request<C: Context>(
+        &self,
+        ctx: &C,
+        payload: &str,
+    ) -> ServiceResult<Option<u64>> {
+        if u64::from_str(payload).is_ok() {
+            return Ok(None);
+        }
+        self.increment_version(ctx).await.map(Some)
+    }
+
+    async fn increment_version<C: Context>(&self, ctx: &C) -> ServiceResult<u64> {
+        let mut redis = ctx.redis().await?;
+        Ok(redis.incr(self.version_key, 1).await?)
+    }
+
+    async fn fetch_version<C: Context>(&self, ctx: &C) -> ServiceResult<u64> {
+        let mut redis = ctx.redis().await?;
+        let version: Option<u64> = redis.get(self.version_key).await?;
+        Ok(version.unwrap_or_default())
+    }
+
+    fn fetch_recently_checked(&self) -> Option<Arc<T>> {
+        let entry = self.entry.read().expect("versioned cache poisoned");
+        let entry = entry.as_ref()?;
+        let checked_at = entry.checked_at?;
+        match checked_at.elapsed() < VERSION_CHECK_INTERVAL {
+            true => Some(entry.value.clone()),
+            false => None,
+        }
+    }
+
+    fn fetch_with_version(&self, version: u64) -> Option<Arc<T>> {
+        let mut entry = self.write();
+        let entry = entry.as_mut().filter(|entry| entry.version == version)?;
+        entry.checked_at = Some(Instant::now());
+        Some(entry.value.clone())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, Option<CacheEntry<T>>> {
+        self.entry.write().expect("versioned cache poisoned")
+    }
+}
@@ -0,0 +1,12 @@
+use chrono::{DateTime, Utc};
+
+#[derive(sqlx::FromRow)]
+pub struct ApiKey {
+    pub id: i64,
+    pub name: String,
+    pub key_hash: String,
+    pub scopes: String,
+    pub created_by: i64,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+
+#[derive(sqlx::FromRow)]
+pub struct AuditLog {
+    pub id: i64,
+    pub user_id: i64,
+    pub username: String,
+    pub api_key_id: Option<i64>,
+    pub action: String,
+    pub target_user_id: Option<i64>,
+    pub target: Option<String>,
+    pub arguments: Option<String>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+pub struct CreateAuditLogArgs<'a> {
+    pub user_id: i64,
+    pub username: &'a str,
+    pub api_key_id: Option<i64>,
+    pub action: &'a str,
+    pub target_user_id: Option<i64>,
+    pub target: Option<&'a str>,
+    pub arguments: Option<&'a str>,
+    pub error: Option<&'a str>,
+}
@@ -18,9 +18,22 @@ pub struct Channel {
     pub description: String,
     pub public_read: bool,
     pub public_write: bool,
+    pub read_privileges: i32,
+    pub write_privileges: i32,
+    pub owner_user_id: Option<i64>,
+    pub invite_only: bool,
+    pub history_size: i32,
+    pub slow_mode_seconds: i32,
     pub status: bool,
 }
 
+#[derive(sqlx::FromRow)]
+pub struct ChannelMember {
+    pub channel_id: i64,
+    pub user_id: i64,
+    pub role: i8,
+}
+
 impl<'a> ChannelName<'a> {
     pub fn from_key(key: &'a str) -> ServiceResult<Self> {
         match key.strip_prefix("#spectator_") {
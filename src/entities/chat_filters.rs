@@ -0,0 +1,13 @@
+use chrono::{DateTime, Utc};
+
+#[derive(sqlx::FromRow)]
+pub struct ChatFilter {
+    pub id: i64,
+    pub rule_type: String,
+    pub pattern: String,
+    pub action: String,
+    pub replacement: Option<String>,
+    pub silence_seconds: Option<i64>,
+    pub created_by: i64,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,12 @@
+use chrono::{DateTime, NaiveDate, Utc};
+
+#[derive(sqlx::FromRow)]
+pub struct ClientBuild {
+    pub id: i64,
+    pub version_date: NaiveDate,
+    pub version_minor: Option<i32>,
+    pub policy: String,
+    pub reason: String,
+    pub created_by: i64,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,13 @@
+use chrono::{DateTime, Utc};
+
+#[derive(sqlx::FromRow)]
+pub struct LoginNotice {
+    pub id: i64,
+    pub message: String,
+    pub target: String,
+    pub delivery: String,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub created_by: i64,
+    pub created_at: DateTime<Utc>,
+}
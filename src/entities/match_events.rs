@@ -7,6 +7,8 @@ pub enum MatchEventType {
     MatchUserLeft,
     MatchHostAssignment,
     MatchGamePlaythrough,
+    MatchMapPicked,
+    MatchMapBanned,
 }
 
 impl MatchEventType {
@@ -18,6 +20,8 @@ impl MatchEventType {
             MatchEventType::MatchUserLeft => "MATCH_USER_LEFT",
             MatchEventType::MatchHostAssignment => "MATCH_HOST_ASSIGNMENT",
             MatchEventType::MatchGamePlaythrough => "MATCH_GAME_PLAYTHOUGH",
+            MatchEventType::MatchMapPicked => "MATCH_MAP_PICK",
+            MatchEventType::MatchMapBanned => "MATCH_MAP_BAN",
         }
     }
 }
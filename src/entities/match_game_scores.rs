@@ -0,0 +1,95 @@
+use crate::entities::gamemodes::Gamemode;
+use bancho_protocol::structures::ScoreFrame;
+use serde::{Deserialize, Serialize};
+
+/// The most recent score frame a player sent during a game.
+#[derive(Copy, Clone, Debug, Deserialize, Serialize)]
+pub struct MatchScoreFrame {
+    pub slot_id: u8,
+    pub user_id: i64,
+    pub time: i32,
+    pub total_score: i32,
+    pub max_combo: u16,
+    pub current_combo: u16,
+    pub perfect: bool,
+    pub count_300: u16,
+    pub count_100: u16,
+    pub count_50: u16,
+    pub count_geki: u16,
+    pub count_katu: u16,
+    pub count_miss: u16,
+    pub current_hp: u8,
+}
+
+impl MatchScoreFrame {
+    pub fn from(user_id: i64, frame: &ScoreFrame) -> Self {
+        Self {
+            slot_id: frame.slot_id as _,
+            user_id,
+            time: frame.time as _,
+            total_score: frame.total_score as _,
+            max_combo: frame.max_combo as _,
+            current_combo: frame.current_combo as _,
+            perfect: frame.perfect,
+            count_300: frame.num300 as _,
+            count_100: frame.num100 as _,
+            count_50: frame.num50 as _,
+            count_geki: frame.num_geki as _,
+            count_katu: frame.num_katu as _,
+            count_miss: frame.num_miss as _,
+            current_hp: frame.current_hp as _,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+pub struct MatchGameScore {
+    pub game_id: i64,
+    pub user_id: i64,
+    pub slot_id: u8,
+    pub team: u8,
+    pub mods: u32,
+    pub score: i64,
+    pub max_combo: i32,
+    pub count_300: i32,
+    pub count_100: i32,
+    pub count_50: i32,
+    pub count_geki: i32,
+    pub count_katu: i32,
+    pub count_miss: i32,
+    pub perfect: bool,
+    pub passed: bool,
+}
+
+impl MatchGameScore {
+    pub fn accuracy(&self, mode: Gamemode) -> f64 {
+        let (n300, n100, n50) = (
+            self.count_300 as f64,
+            self.count_100 as f64,
+            self.count_50 as f64,
+        );
+        let (geki, katu, miss) = (
+            self.count_geki as f64,
+            self.count_katu as f64,
+            self.count_miss as f64,
+        );
+        let (hits, total) = match mode {
+            Gamemode::Taiko | Gamemode::TaikoRelax => (n300 + n100 * 0.5, n300 + n100 + miss),
+            Gamemode::Catch | Gamemode::CatchRelax => {
+                (n300 + n100 + n50, n300 + n100 + n50 + katu + miss)
+            }
+            Gamemode::Mania => (
+                (geki + n300) * 300.0 + katu * 200.0 + n100 * 100.0 + n50 * 50.0,
+                (geki + n300 + katu + n100 + n50 + miss) * 300.0,
+            ),
+            Gamemode::Standard | Gamemode::StandardRelax | Gamemode::StandardAutopilot => (
+                n300 * 300.0 + n100 * 100.0 + n50 * 50.0,
+                (n300 + n100 + n50 + miss) * 300.0,
+            ),
+        };
+        match total > 0.0 {
+            true => hits / total,
+            false => 0.0,
+        }
+    }
+}
@@ -1,19 +1,27 @@
+pub mod api_keys;
+pub mod audit_logs;
 pub mod badges;
 pub mod bancho_settings;
 pub mod beatmaps;
 pub mod bot;
 pub mod channels;
+pub mod chat_filters;
+pub mod client_builds;
 pub mod gamemodes;
 pub mod hardware_logs;
+pub mod login_notices;
 pub mod match_events;
+pub mod match_game_scores;
 pub mod messages;
 pub mod multiplayer;
 pub mod presences;
+pub mod punishments;
 pub mod relationships;
 pub mod scores;
 pub mod sessions;
 pub mod stats;
 pub mod streams;
 pub mod tillerino;
+pub mod tournaments;
 pub mod user_reports;
 pub mod users;
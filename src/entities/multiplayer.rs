@@ -66,3 +66,24 @@ impl MultiplayerMatchSlot {
         self.completed = false;
     }
 }
+
+/// Restrictions on the beatmaps that may be picked in a match.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct MapConstraints {
+    pub min_stars: Option<f32>,
+    pub max_stars: Option<f32>,
+    /// Maximum drain time in seconds
+    pub max_length: Option<i32>,
+    pub mode: Option<u8>,
+    pub ranked_only: bool,
+}
+
+impl MapConstraints {
+    pub fn is_empty(&self) -> bool {
+        self.min_stars.is_none()
+            && self.max_stars.is_none()
+            && self.max_length.is_none()
+            && self.mode.is_none()
+            && !self.ranked_only
+    }
+}
@@ -0,0 +1,13 @@
+use chrono::{DateTime, Utc};
+
+#[derive(sqlx::FromRow)]
+pub struct Punishment {
+    pub id: i64,
+    pub user_id: i64,
+    pub kind: i8,
+    pub reason: String,
+    pub created_by: i64,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub lifted_at: Option<DateTime<Utc>>,
+}
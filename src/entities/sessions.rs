@@ -18,6 +18,9 @@ pub struct Session {
     pub private_dms: bool,
     pub silence_end: Option<chrono::DateTime<chrono::Utc>>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
+    /// The osu! version the session was created with, none for IRC clients
+    #[serde(default)]
+    pub client_version: Option<String>,
 }
 
 pub struct CreateSessionArgs {
@@ -27,4 +30,5 @@ pub struct CreateSessionArgs {
     pub private_dms: bool,
     pub silence_end: Option<chrono::DateTime<chrono::Utc>>,
     pub ip_address: IpAddr,
+    pub client_version: Option<String>,
 }
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PoolMap {
+    /// e.g. NM1, HD2, DT1 or TB
+    pub code: String,
+    pub beatmap_id: i32,
+    pub mods: u32,
+    pub freemod: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TournamentPick {
+    pub code: String,
+    pub team: u8,
+    pub game_id: Option<i64>,
+    pub winning_team: Option<u8>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TournamentBan {
+    pub code: String,
+    pub team: u8,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Tournament {
+    pub match_id: i64,
+    pub best_of: u8,
+    #[serde(default)]
+    pub bans_per_team: u8,
+    /// The team that made the first ban or pick, teams alternate from there on
+    #[serde(default)]
+    pub first_team: Option<u8>,
+    pub pool: Vec<PoolMap>,
+    pub picks: Vec<TournamentPick>,
+    pub bans: Vec<TournamentBan>,
+    pub red_points: u8,
+    pub blue_points: u8,
+}
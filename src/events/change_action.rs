@@ -19,7 +19,8 @@ pub async fn handle(
         Ok(presence) => presence,
         Err(AppError::PresencesNotFound) => {
             let location =
-                location::get_location(session.create_ip_address, Country::Unknown, false).await;
+                location::get_location(ctx, session.create_ip_address, Country::Unknown, false)
+                    .await;
 
             Presence {
                 user_id: session.user_id,
@@ -1,4 +1,6 @@
-use crate::api::RequestContext;
+use crate::common::chat::format_duration;
+use crate::common::context::Context;
+use crate::common::error::AppError;
 use crate::entities::bot;
 use crate::events::EventResult;
 use crate::models::messages::Recipient;
@@ -11,16 +13,49 @@ use bancho_protocol::messages::server::ChatMessage;
 use bancho_protocol::serde::BinarySerialize;
 use bancho_protocol::structures::IrcMessage;
 
-// TODO: simplify all this lol
-pub async fn public_chat_message(
-    ctx: &RequestContext,
+pub async fn public_chat_message<C: Context>(
+    ctx: &C,
     session: &mut Session,
     args: PublicChatMessage<'_>,
 ) -> EventResult {
-    let channel_name = channels::get_channel_name(ctx, session, &args.message.recipient).await?;
+    send_public_message(ctx, session, args.message.recipient, args.message.text).await
+}
+
+pub async fn private_chat_message<C: Context>(
+    ctx: &C,
+    session: &mut Session,
+    args: PrivateChatMessage<'_>,
+) -> EventResult {
+    send_private_message(ctx, session, args.message.recipient, args.message.text).await
+}
+
+// TODO: simplify all this lol
+pub async fn send_public_message<C: Context>(
+    ctx: &C,
+    session: &mut Session,
+    channel: &str,
+    text: &str,
+) -> EventResult {
+    let channel_name = channels::get_channel_name(ctx, session, channel).await?;
     let recipient = Recipient::Channel(channel_name);
 
-    let result = messages::send(ctx, session, &recipient, args.message.text).await?;
+    // channel restrictions are explained by the bot instead of an alert
+    let result = match messages::send(ctx, session, &recipient, text).await {
+        Ok(result) => result,
+        Err(AppError::ChannelsMuted) => {
+            let notice =
+                match channels::fetch_mute_remaining(ctx, channel_name, session.user_id).await? {
+                    Some(mute_remaining) => format!(
+                        "You are muted in {channel} for another {}.",
+                        format_duration(mute_remaining)
+                    ),
+                    None => AppError::ChannelsMuted.message().to_owned(),
+                };
+            return Ok(Some(bot_notice(channel, &notice)));
+        }
+        Err(e @ AppError::ChannelsSlowMode) => return Ok(Some(bot_notice(channel, e.message()))),
+        Err(e) => return Err(e),
+    };
     match result.response {
         Some(cmd_response) => {
             let bot_response = cmd_response.answer.map(|answer| {
@@ -28,7 +63,7 @@ pub async fn public_chat_message(
                     sender: bot::BOT_NAME,
                     sender_id: bot::BOT_ID as _,
                     text: &answer,
-                    recipient: args.message.recipient,
+                    recipient: channel,
                 };
                 ChatMessage(&bot_response_msg).as_message().serialize()
             });
@@ -39,7 +74,7 @@ pub async fn public_chat_message(
                     let msg = IrcMessage {
                         sender: &session.username,
                         text: &result.message.content,
-                        recipient: args.message.recipient,
+                        recipient: channel,
                         sender_id: session.user_id as _,
                     };
                     streams::broadcast_message(
@@ -71,7 +106,7 @@ pub async fn public_chat_message(
             let msg = IrcMessage {
                 sender: &session.username,
                 text: &result.message.content,
-                recipient: args.message.recipient,
+                recipient: channel,
                 sender_id: session.user_id as _,
             };
             streams::broadcast_message(
@@ -87,12 +122,12 @@ pub async fn public_chat_message(
     }
 }
 
-pub async fn private_chat_message(
-    ctx: &RequestContext,
+pub async fn send_private_message<C: Context>(
+    ctx: &C,
     session: &mut Session,
-    args: PrivateChatMessage<'_>,
+    recipient_name: &str,
+    text: &str,
 ) -> EventResult {
-    let recipient_name = args.message.recipient;
     let recipient = match recipient_name == bot::BOT_NAME {
         true => Recipient::Bot,
         false => {
@@ -107,7 +142,7 @@ pub async fn private_chat_message(
         }
     };
 
-    let result = messages::send(ctx, session, &recipient, args.message.text).await?;
+    let result = messages::send(ctx, session, &recipient, text).await?;
     match recipient {
         Recipient::Channel(_) => unreachable!(),
         Recipient::UserSessions(recipient_sessions) => match result.response {
@@ -197,6 +232,16 @@ pub async fn private_chat_message(
     }
 }
 
+fn bot_notice(recipient: &str, text: &str) -> Vec<u8> {
+    let notice = IrcMessage {
+        sender: bot::BOT_NAME,
+        sender_id: bot::BOT_ID as _,
+        text,
+        recipient,
+    };
+    ChatMessage(&notice).as_message().serialize()
+}
+
 /*
 TODO: handle invalid syntax (probably rewrite error message system lol)
         Err(AppError::CommandsInvalidSyntax(syntax, _, typed)) => {
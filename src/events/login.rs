@@ -2,11 +2,12 @@ use crate::api::RequestContext;
 use crate::common::error::AppError;
 use crate::entities::bot;
 use crate::entities::channels::ChannelName;
-use crate::models::bancho::{BanchoResponse, LoginArgs, LoginError};
+use crate::models::bancho::{BanchoResponse, LoginArgs, LoginError, OsuVersion};
 use crate::models::sessions::Session;
 use crate::repositories::streams::StreamName;
 use crate::usecases::{
-    bancho_settings, channels, messages, presences, relationships, sessions, streams,
+    bancho_settings, channels, client_builds, login_attempts, login_notices, maintenance, messages,
+    presences, relationships, sessions, streams,
 };
 use bancho_protocol::concat_messages;
 use bancho_protocol::messages::server::{
@@ -19,23 +20,22 @@ use bancho_protocol::serde::osu_types::PrefixedVec;
 use bancho_protocol::structures::{IrcMessage, Privileges};
 use tracing::{error, info};
 
-const WELCOME_MESSAGE: &str = r#"
-             Welcome to Akatsuki!
-             Running banchus v0.1
- "#; // This space is needed for osu! to render the line
-
 fn login_error(e: AppError) -> BanchoResponse {
     let login_error = match e {
         AppError::SessionsInvalidCredentials => LoginError::InvalidCredentials,
         AppError::ClientTooOld => LoginError::OldVersion,
+        AppError::UnsupportedClientVersion => LoginError::OldVersion,
         AppError::SessionsLoginForbidden => LoginError::InvalidCredentials,
         AppError::SessionsLimitReached => LoginError::OldVersion,
+        AppError::MaintenanceModeEnabled => LoginError::InvalidCredentials,
         _ => LoginError::UnexpectedError,
     };
+    login_error_response(login_error, e.message())
+}
+
+fn login_error_response(login_error: LoginError, message: &str) -> BanchoResponse {
     let data = concat_messages!(
-        Alert {
-            message: e.message()
-        },
+        Alert { message },
         LoginResult {
             user_id: login_error as _,
         }
@@ -43,17 +43,51 @@ fn login_error(e: AppError) -> BanchoResponse {
     BanchoResponse::error_raw(None, data)
 }
 
+/// Tells the user why they can't log in, and when the maintenance window is expected to end.
+async fn maintenance_login_error(ctx: &RequestContext) -> BanchoResponse {
+    match maintenance::login_message(ctx).await {
+        Some(message) => login_error_response(LoginError::InvalidCredentials, &message),
+        None => login_error(AppError::MaintenanceModeEnabled),
+    }
+}
+
+/// Tells the user why their build is rejected, and which build they need instead.
+async fn unsupported_version_login_error(
+    ctx: &RequestContext,
+    osu_version: &OsuVersion,
+) -> BanchoResponse {
+    let settings = bancho_settings::cached();
+    match client_builds::unsupported_message(ctx, osu_version, &settings).await {
+        Ok(message) => login_error_response(LoginError::OldVersion, &message),
+        Err(e) => login_error(e),
+    }
+}
+
+/// Tells the user how long they are locked out for, instead of rejecting their credentials.
+async fn lockout_login_error(ctx: &RequestContext, username: &str) -> BanchoResponse {
+    let message = login_attempts::lockout_message(ctx, ctx.request_ip.ip_addr, username).await;
+    login_error_response(LoginError::UnexpectedError, &message)
+}
+
 pub async fn handle(ctx: &RequestContext, args: LoginArgs) -> BanchoResponse {
     match bancho_settings::in_maintenance_mode(ctx).await {
-        Ok(true) => return login_error(AppError::MaintenanceModeEnabled),
+        Ok(true) => return maintenance_login_error(ctx).await,
         Err(e) => return login_error(e),
         _ => {}
     }
 
+    let identifier = args.identifier.clone();
+    let osu_version = args.client_info.osu_version.clone();
     let (session, presence) = match sessions::create(ctx, args).await {
         Ok(res) => res,
+        Err(AppError::SessionsLockedOut) => return lockout_login_error(ctx, &identifier).await,
+        Err(AppError::UnsupportedClientVersion) => {
+            return unsupported_version_login_error(ctx, &osu_version).await;
+        }
         Err(e) => return login_error(e),
     };
+    // loaded while creating the session
+    let settings = bancho_settings::cached();
     let user_panel = presence.user_panel();
     if session.is_publicly_visible() {
         match streams::broadcast_data(ctx, StreamName::Main, &user_panel, None, None).await {
@@ -76,7 +110,7 @@ pub async fn handle(ctx: &RequestContext, args: LoginArgs) -> BanchoResponse {
             ProtocolVersion { version: bancho_protocol::PROTOCOL_VERSION },
             UserPrivileges { privileges: session.privileges.to_bancho() | Privileges::Supporter },
             ChannelInfoEnd,
-            Alert{ message: WELCOME_MESSAGE },
+            Alert{ message: &settings.welcome_message },
             FriendsList::from(friends),
         },
         user_panel,
@@ -114,13 +148,9 @@ pub async fn handle(ctx: &RequestContext, args: LoginArgs) -> BanchoResponse {
         join_special_channel(ctx, &mut response, &session, "#devlog").await;
     }
 
-    match channels::fetch_all(ctx).await {
+    match channels::fetch_listed(ctx, &session).await {
         Ok(channels) => {
             for channel in channels {
-                if !channel.can_read(session.privileges) {
-                    continue;
-                }
-
                 let member_count = channels::member_count(ctx, ChannelName::Chat(&channel.name))
                     .await
                     .unwrap_or_else(|e| {
@@ -138,6 +168,16 @@ pub async fn handle(ctx: &RequestContext, args: LoginArgs) -> BanchoResponse {
         Err(e) => error!("Failed to fetch channels during login: {e:?}"),
     }
 
+    if !settings.menu_icon_url.is_empty() {
+        let icon = format!("{}|{}", settings.menu_icon_url, settings.menu_icon_link);
+        response.push(serialize_main_menu_icon(&icon));
+    }
+
+    match login_notices::fetch_login_data(ctx, &session).await {
+        Ok(notices) => response.push(notices),
+        Err(e) => error!("Failed to fetch login notices: {e:?}"),
+    }
+
     match presences::fetch_user_ids(ctx).await {
         Ok(user_ids) => {
             let presence_bundle = UserPresenceBundle {
@@ -193,3 +233,31 @@ async fn join_special_channel<'a>(
         }
     }
 }
+
+/// The main menu icon packet is written by hand: the packet header
+/// followed by the `<image url>|<link>` string.
+fn serialize_main_menu_icon(icon: &str) -> Vec<u8> {
+    const MAIN_MENU_ICON_PACKET_ID: u16 = 76;
+
+    let mut payload = vec![0x0b];
+    let mut length = icon.len();
+    loop {
+        let byte = (length & 0x7f) as u8;
+        length >>= 7;
+        match length {
+            0 => {
+                payload.push(byte);
+                break;
+            }
+            _ => payload.push(byte | 0x80),
+        }
+    }
+    payload.extend_from_slice(icon.as_bytes());
+
+    let mut data = Vec::with_capacity(7 + payload.len());
+    data.extend_from_slice(&MAIN_MENU_ICON_PACKET_ID.to_le_bytes());
+    data.push(0);
+    data.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    data.extend_from_slice(&payload);
+    data
+}
@@ -1,12 +1,15 @@
 use crate::common::context::Context;
 use crate::common::error::AppError;
+use crate::entities::match_game_scores::MatchScoreFrame;
 use crate::events::EventResult;
+use crate::models::match_feeds::MatchFeedEvent;
 use crate::models::sessions::Session;
 use crate::repositories::streams::StreamName;
-use crate::usecases::{multiplayer, streams};
+use crate::usecases::{match_feeds, multiplayer, streams};
 use bancho_protocol::messages::client::UpdateMatchScore;
 use bancho_protocol::messages::server::MatchScoreUpdate;
 use bancho_protocol::structures::SlotStatus;
+use tracing::error;
 
 pub async fn handle<C: Context>(
     ctx: &C,
@@ -30,5 +33,13 @@ pub async fn handle<C: Context>(
         None,
     )
     .await?;
+    let frame = MatchScoreFrame::from(session.user_id, &args.score);
+    // a lost frame only affects the stored result, the next frame replaces it
+    if let Err(e) = multiplayer::record_score_frame(ctx, match_id, frame).await {
+        error!(match_id, "Failed to record score frame: {e:?}");
+    }
+    if let Err(e) = match_feeds::publish(ctx, match_id, &MatchFeedEvent::Score(frame)).await {
+        error!(match_id, "Failed to publish score frame: {e:?}");
+    }
     Ok(None)
 }
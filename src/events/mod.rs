@@ -3,7 +3,7 @@ pub mod cant_spectate;
 pub mod change_action;
 pub mod channel_join;
 pub mod channel_leave;
-mod chat;
+pub mod chat;
 pub mod lobby_join;
 pub mod lobby_leave;
 pub mod login;
@@ -47,19 +47,28 @@ pub mod user_stats_request;
 
 use crate::api::RequestContext;
 use crate::common::error::{AppError, ServiceResult};
+use crate::common::metrics;
+use crate::common::shutdown;
 use crate::models::bancho::{BanchoRequest, BanchoResponse};
 use crate::models::sessions::Session;
-use crate::usecases::{sessions, streams};
+use crate::usecases::{bancho_settings, sessions, streams};
 use axum::body::Bytes;
 use bancho_protocol::messages::message::HEADER_SIZE;
 use bancho_protocol::messages::server::{Alert, Restart};
 use bancho_protocol::messages::{Message, MessageHeader, MessageType};
 use bancho_protocol::serde::{BinaryDeserialize, BinaryReader};
+use std::time::Instant;
 use tracing::warn;
 
-const RECONNECT_DELAY: u32 = 750;
+/// Spreads the reconnects of clients handed off during a shutdown across the remaining replicas.
+const SHUTDOWN_RECONNECT_JITTER: u32 = 5000;
+
 pub async fn handle_request(ctx: &RequestContext, request: BanchoRequest) -> BanchoResponse {
     match request {
+        // let the client log in on one of the remaining replicas
+        BanchoRequest::Login(_) if shutdown::is_shutting_down() => {
+            BanchoResponse::error_raw(None, shutdown_restart_data())
+        }
         BanchoRequest::Login(args) => login::handle(ctx, args).await,
         BanchoRequest::HandleEvents(session_id, request_data) => {
             match sessions::extend(ctx, session_id).await {
@@ -67,7 +76,7 @@ pub async fn handle_request(ctx: &RequestContext, request: BanchoRequest) -> Ban
                 Err(AppError::SessionsNotFound) => BanchoResponse::error_raw(
                     None,
                     Message::serialize(Restart {
-                        milliseconds: RECONNECT_DELAY,
+                        milliseconds: bancho_settings::cached().reconnect_delay,
                     }),
                 ),
                 Err(e) => BanchoResponse::error(Some(session_id), e),
@@ -76,6 +85,15 @@ pub async fn handle_request(ctx: &RequestContext, request: BanchoRequest) -> Ban
     }
 }
 
+/// Makes the client reconnect after a random delay, used to hand off
+/// clients connected to this replica when it shuts down.
+pub fn shutdown_restart_data() -> Vec<u8> {
+    let jitter = rand::random_range(0..=SHUTDOWN_RECONNECT_JITTER);
+    Message::serialize(Restart {
+        milliseconds: bancho_settings::cached().reconnect_delay + jitter,
+    })
+}
+
 pub struct Event<'a> {
     pub event_type: MessageType,
     pub data: &'a [u8],
@@ -113,6 +131,22 @@ pub async fn handle_event(
     ctx: &RequestContext,
     session: &mut Session,
     event: Event<'_>,
+) -> EventResult {
+    let event_type = event.event_type;
+    let started_at = Instant::now();
+    let result = dispatch_event(ctx, session, event).await;
+    metrics::record_event(
+        format!("{event_type:?}"),
+        started_at.elapsed(),
+        result.is_ok(),
+    );
+    result
+}
+
+async fn dispatch_event(
+    ctx: &RequestContext,
+    session: &mut Session,
+    event: Event<'_>,
 ) -> EventResult {
     event_handlers!(ctx, session, event, [
         // Ignored events
@@ -189,14 +223,39 @@ pub async fn handle_events(
         );
     }*/
 
-    let events = match Events::try_from(&request_data) {
-        Ok(events) => events,
-        Err(e) => return BanchoResponse::error(Some(session.session_id), e),
-    };
+    let (mut response_data, logged_out) =
+        match handle_event_data(ctx, &mut session, &request_data).await {
+            Ok(res) => res,
+            Err(e) => return BanchoResponse::error(Some(session.session_id), e),
+        };
+    if logged_out {
+        return BanchoResponse::ok(session.session_id, response_data);
+    }
+
+    let pending_data = streams::read_pending_data(ctx, &session)
+        .await
+        .unwrap_or_else(|e| {
+            Message::serialize(Alert {
+                message: e.message(),
+            })
+        });
+    response_data.extend_from_slice(&pending_data);
+
+    BanchoResponse::ok(session.session_id, response_data)
+}
+
+/// Handles every event contained in the request data.
+/// Returns the response data and whether the session has logged out.
+pub async fn handle_event_data(
+    ctx: &RequestContext,
+    session: &mut Session,
+    request_data: &Bytes,
+) -> ServiceResult<(Vec<u8>, bool)> {
+    let events = Events::try_from(request_data)?;
     let mut response_data = vec![];
     for event in events.events {
         let event_type = event.event_type;
-        match handle_event(ctx, &mut session, event).await {
+        match handle_event(ctx, session, event).await {
             Ok(None) => (),
             Ok(Some(data)) => response_data.extend_from_slice(&data),
             Err(e) => {
@@ -208,20 +267,10 @@ pub async fn handle_events(
         }
 
         if event_type == MessageType::Logout {
-            return BanchoResponse::ok(session.session_id, response_data);
+            return Ok((response_data, true));
         }
     }
-
-    let pending_data = streams::read_pending_data(ctx, &session)
-        .await
-        .unwrap_or_else(|e| {
-            Message::serialize(Alert {
-                message: e.message(),
-            })
-        });
-    response_data.extend_from_slice(&pending_data);
-
-    BanchoResponse::ok(session.session_id, response_data)
+    Ok((response_data, false))
 }
 
 impl<'a> TryFrom<&'a Bytes> for Events<'a> {
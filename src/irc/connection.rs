@@ -0,0 +1,612 @@
+use crate::common::error::{AppError, ServiceResult};
+use crate::common::state::AppState;
+use crate::events::Events;
+use crate::events::chat;
+use crate::irc::protocol::{self, IrcCommand, SERVER_NAME};
+use crate::models::presences::Presence;
+use crate::models::sessions::Session;
+use crate::repositories::streams::StreamName;
+use crate::usecases::{channels, maintenance, presences, sessions, streams};
+use axum::body::Bytes;
+use bancho_protocol::messages::MessageType;
+use bancho_protocol::serde::BinaryDeserialize;
+use bancho_protocol::structures::IrcMessage;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader, BufWriter};
+use tokio::net::TcpStream;
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tracing::{error, info};
+
+const REGISTRATION_TIMEOUT: Duration = Duration::from_secs(30);
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+const SESSION_EXTEND_INTERVAL: Duration = Duration::from_secs(30);
+/// Maximum length of a line including the trailing CR-LF, as specified by RFC 1459.
+const MAX_LINE_LENGTH: usize = 512;
+
+const MOTD: &str = "Welcome to Akatsuki! Use !help to see the available commands.";
+
+/// Reads CR-LF terminated lines, failing once a line exceeds [`MAX_LINE_LENGTH`].
+struct LineReader {
+    reader: BufReader<OwnedReadHalf>,
+    buffer: Vec<u8>,
+}
+
+impl LineReader {
+    fn new(reader: OwnedReadHalf) -> Self {
+        Self {
+            reader: BufReader::new(reader),
+            buffer: Vec::with_capacity(MAX_LINE_LENGTH),
+        }
+    }
+
+    /// Cancel safe: a partially read line is kept until the next call.
+    async fn next_line(&mut self) -> std::io::Result<Option<String>> {
+        let remaining = MAX_LINE_LENGTH.saturating_sub(self.buffer.len());
+        (&mut self.reader)
+            .take(remaining as u64)
+            .read_until(b'\n', &mut self.buffer)
+            .await?;
+        // without a line feed, either the limit or the end of the stream has been reached
+        if self.buffer.last() != Some(&b'\n') {
+            if self.buffer.len() >= MAX_LINE_LENGTH {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "IRC line exceeds the maximum length",
+                ));
+            }
+            if self.buffer.is_empty() {
+                return Ok(None);
+            }
+        }
+
+        let line = std::mem::take(&mut self.buffer);
+        let line = String::from_utf8_lossy(&line);
+        Ok(Some(line.trim_end_matches(['\r', '\n']).to_owned()))
+    }
+}
+
+struct Credentials {
+    nickname: String,
+    password: String,
+}
+
+struct IrcClient {
+    ctx: AppState,
+    session: Session,
+    nickname: String,
+    writer: BufWriter<OwnedWriteHalf>,
+}
+
+pub async fn handle(ctx: AppState, stream: TcpStream, peer_addr: SocketAddr) -> ServiceResult<()> {
+    let (reader, writer) = stream.into_split();
+    let mut lines = LineReader::new(reader);
+    let mut writer = BufWriter::new(writer);
+
+    let credentials =
+        match tokio::time::timeout(REGISTRATION_TIMEOUT, register(&mut lines, &mut writer)).await {
+            Ok(credentials) => credentials?,
+            Err(_) => {
+                send_line(&mut writer, "ERROR :Registration timed out").await?;
+                return Ok(());
+            }
+        };
+    let Some(credentials) = credentials else {
+        return Ok(());
+    };
+
+    let (session, presence) = match sessions::create_irc(
+        &ctx,
+        &credentials.nickname,
+        &credentials.password,
+        peer_addr.ip(),
+    )
+    .await
+    {
+        Ok(res) => res,
+        Err(e) => {
+            let message = match e {
+                AppError::MaintenanceModeEnabled => maintenance::login_message(&ctx)
+                    .await
+                    .unwrap_or_else(|| e.message().to_owned()),
+                e => e.message().to_owned(),
+            };
+            let reply = protocol::reply(
+                protocol::ERR_PASSWDMISMATCH,
+                &credentials.nickname,
+                &format!(":{}", message.replace('\n', " ")),
+            );
+            send_line(&mut writer, &reply).await?;
+            send_line(&mut writer, "ERROR :Closing link").await?;
+            return Ok(());
+        }
+    };
+
+    info!(
+        user_id = session.user_id,
+        username = presence.username,
+        "User logged in over IRC."
+    );
+    let mut client = IrcClient {
+        ctx,
+        nickname: protocol::nickname(&presence.username),
+        session,
+        writer,
+    };
+    let result = client.serve(&mut lines, &presence).await;
+    match sessions::delete(&client.ctx, &client.session).await {
+        Ok(()) | Err(AppError::SessionsNotFound) => {}
+        Err(e) => error!("Failed to delete IRC session: {e:?}"),
+    }
+    info!(
+        user_id = client.session.user_id,
+        "User disconnected from IRC."
+    );
+    result
+}
+
+/// Reads the PASS/NICK/USER handshake of a connecting client.
+async fn register(
+    lines: &mut LineReader,
+    writer: &mut BufWriter<OwnedWriteHalf>,
+) -> ServiceResult<Option<Credentials>> {
+    let mut nickname = None;
+    let mut password = None;
+    let mut has_user = false;
+    while let Some(line) = lines.next_line().await? {
+        let Some(command) = IrcCommand::parse(&line) else {
+            continue;
+        };
+        match command.command.as_str() {
+            "CAP" if command.param(0) == Some("LS") => {
+                send_line(writer, &format!(":{SERVER_NAME} CAP * LS :")).await?;
+            }
+            "PASS" => password = command.param(0).map(str::to_string),
+            "NICK" => nickname = command.param(0).map(str::to_string),
+            "USER" => has_user = true,
+            "PING" => {
+                let token = command.param(0).unwrap_or(SERVER_NAME);
+                send_line(
+                    writer,
+                    &format!(":{SERVER_NAME} PONG {SERVER_NAME} :{token}"),
+                )
+                .await?;
+            }
+            "QUIT" => return Ok(None),
+            _ => {}
+        }
+
+        if !has_user {
+            continue;
+        }
+
+        if let Some(nickname) = nickname.take() {
+            return match password.take() {
+                Some(password) => Ok(Some(Credentials { nickname, password })),
+                None => {
+                    let reply = protocol::reply(
+                        protocol::ERR_PASSWDMISMATCH,
+                        &nickname,
+                        ":You must provide your IRC password using PASS",
+                    );
+                    send_line(writer, &reply).await?;
+                    Ok(None)
+                }
+            };
+        }
+    }
+    Ok(None)
+}
+
+async fn send_line(writer: &mut BufWriter<OwnedWriteHalf>, line: &str) -> ServiceResult<()> {
+    writer.write_all(line.as_bytes()).await?;
+    writer.write_all(b"\r\n").await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+impl IrcClient {
+    async fn serve(&mut self, lines: &mut LineReader, presence: &Presence) -> ServiceResult<()> {
+        self.welcome(presence).await?;
+
+        let mut poll_interval = tokio::time::interval(POLL_INTERVAL);
+        let mut extend_interval = tokio::time::interval(SESSION_EXTEND_INTERVAL);
+        loop {
+            tokio::select! {
+                line = lines.next_line() => {
+                    let Some(line) = line? else {
+                        break;
+                    };
+                    if !self.handle_line(&line).await? {
+                        break;
+                    }
+                }
+                _ = poll_interval.tick() => {
+                    let pending_data = streams::read_pending_data(&self.ctx, &self.session).await?;
+                    let replies = self.translate_bancho_data(pending_data);
+                    self.send_all(&replies).await?;
+                }
+                _ = extend_interval.tick() => {
+                    match sessions::extend(&self.ctx, self.session.session_id).await {
+                        Ok(session) => self.session = session,
+                        Err(AppError::SessionsNotFound) => {
+                            self.send("ERROR :Your session has ended").await?;
+                            break;
+                        }
+                        Err(e) => return Err(e),
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn welcome(&mut self, presence: &Presence) -> ServiceResult<()> {
+        let session_id = self.session.session_id;
+        streams::join(&self.ctx, session_id, StreamName::User(session_id)).await?;
+        streams::join(&self.ctx, session_id, StreamName::Main).await?;
+        if self.session.is_publicly_visible() {
+            let user_panel = presence.user_panel();
+            if let Err(e) =
+                streams::broadcast_data(&self.ctx, StreamName::Main, &user_panel, None, None).await
+            {
+                error!("Failed to broadcast user panel: {e:?}");
+            }
+        }
+
+        let nickname = &self.nickname;
+        let replies = [
+            protocol::reply(
+                protocol::RPL_WELCOME,
+                nickname,
+                &format!(":Welcome to Akatsuki, {nickname}"),
+            ),
+            protocol::reply(
+                protocol::RPL_YOURHOST,
+                nickname,
+                &format!(":Your host is {SERVER_NAME}"),
+            ),
+            protocol::reply(
+                protocol::RPL_CREATED,
+                nickname,
+                ":This server is part of bancho-service",
+            ),
+            protocol::reply(
+                protocol::RPL_MYINFO,
+                nickname,
+                &format!("{SERVER_NAME} bancho-service o o"),
+            ),
+            protocol::reply(
+                protocol::RPL_MOTDSTART,
+                nickname,
+                &format!(":- {SERVER_NAME} Message of the day -"),
+            ),
+            protocol::reply(protocol::RPL_MOTD, nickname, &format!(":- {MOTD}")),
+            protocol::reply(protocol::RPL_ENDOFMOTD, nickname, ":End of /MOTD command"),
+        ];
+        self.send_all(&replies).await
+    }
+
+    /// Returns false once the client wants to disconnect.
+    async fn handle_line(&mut self, line: &str) -> ServiceResult<bool> {
+        let Some(command) = IrcCommand::parse(line) else {
+            return Ok(true);
+        };
+
+        let result = match command.command.as_str() {
+            "QUIT" => return Ok(false),
+            "PING" => {
+                let token = command.param(0).unwrap_or(SERVER_NAME);
+                Ok(vec![format!(":{SERVER_NAME} PONG {SERVER_NAME} :{token}")])
+            }
+            "PONG" | "PASS" | "USER" | "NICK" | "CAP" | "MODE" | "NOTICE" => Ok(vec![]),
+            "JOIN" => self.join(&command).await,
+            "PART" => self.part(&command).await,
+            "PRIVMSG" => self.privmsg(&command).await,
+            "NAMES" => self.names(&command).await,
+            "WHOIS" => self.whois(&command).await,
+            unknown_command => Ok(vec![protocol::reply(
+                protocol::ERR_UNKNOWNCOMMAND,
+                &self.nickname,
+                &format!("{unknown_command} :Unknown command"),
+            )]),
+        };
+
+        match result {
+            Ok(replies) => self.send_all(&replies).await?,
+            Err(e) => {
+                let notice = protocol::notice(&self.nickname, e.message());
+                self.send(&notice).await?;
+            }
+        }
+        Ok(true)
+    }
+
+    async fn join(&self, command: &IrcCommand<'_>) -> ServiceResult<Vec<String>> {
+        let Some(channel_list) = command.param(0) else {
+            return Ok(vec![self.need_more_params("JOIN")]);
+        };
+
+        let mut replies = vec![];
+        for channel in channel_list.split(',').filter(|c| !c.is_empty()) {
+            let result = match channels::get_channel_name(&self.ctx, &self.session, channel).await {
+                Ok(channel_name) => channels::join(&self.ctx, &self.session, channel_name).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok((channel_info, _)) => {
+                    let prefix = protocol::user_prefix(&self.nickname);
+                    replies.push(format!(":{prefix} JOIN {channel}"));
+                    replies.push(match channel_info.description.is_empty() {
+                        true => protocol::reply(
+                            protocol::RPL_NOTOPIC,
+                            &self.nickname,
+                            &format!("{channel} :No topic is set"),
+                        ),
+                        false => protocol::reply(
+                            protocol::RPL_TOPIC,
+                            &self.nickname,
+                            &format!("{channel} :{}", channel_info.description),
+                        ),
+                    });
+                    replies.extend(self.channel_names(channel).await?);
+                }
+                Err(AppError::ChannelsNotFound | AppError::MultiplayerUserNotInMatch) => {
+                    replies.push(protocol::reply(
+                        protocol::ERR_NOSUCHCHANNEL,
+                        &self.nickname,
+                        &format!("{channel} :No such channel"),
+                    ));
+                }
+                Err(AppError::ChannelsUnauthorized | AppError::ChannelsBanned) => {
+                    replies.push(protocol::reply(
+                        protocol::ERR_BANNEDFROMCHAN,
+                        &self.nickname,
+                        &format!("{channel} :Cannot join channel"),
+                    ));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(replies)
+    }
+
+    async fn part(&self, command: &IrcCommand<'_>) -> ServiceResult<Vec<String>> {
+        let Some(channel_list) = command.param(0) else {
+            return Ok(vec![self.need_more_params("PART")]);
+        };
+
+        let mut replies = vec![];
+        for channel in channel_list.split(',').filter(|c| !c.is_empty()) {
+            let result = match channels::get_channel_name(&self.ctx, &self.session, channel).await {
+                Ok(channel_name) => {
+                    channels::leave(&self.ctx, self.session.session_id, channel_name).await
+                }
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(_) => {
+                    let prefix = protocol::user_prefix(&self.nickname);
+                    replies.push(format!(":{prefix} PART {channel}"));
+                }
+                Err(AppError::ChannelsNotFound | AppError::MultiplayerUserNotInMatch) => {
+                    replies.push(protocol::reply(
+                        protocol::ERR_NOSUCHCHANNEL,
+                        &self.nickname,
+                        &format!("{channel} :No such channel"),
+                    ));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(replies)
+    }
+
+    async fn privmsg(&mut self, command: &IrcCommand<'_>) -> ServiceResult<Vec<String>> {
+        let Some(target) = command.param(0) else {
+            return Ok(vec![protocol::reply(
+                protocol::ERR_NORECIPIENT,
+                &self.nickname,
+                ":No recipient given (PRIVMSG)",
+            )]);
+        };
+        let Some(text) = command.param(1).filter(|text| !text.is_empty()) else {
+            return Ok(vec![protocol::reply(
+                protocol::ERR_NOTEXTTOSEND,
+                &self.nickname,
+                ":No text to send",
+            )]);
+        };
+
+        let result = match target.starts_with('#') {
+            true => chat::send_public_message(&self.ctx, &mut self.session, target, text).await,
+            false => chat::send_private_message(&self.ctx, &mut self.session, target, text).await,
+        };
+        match result {
+            Ok(Some(response_data)) => Ok(self.translate_bancho_data(response_data)),
+            Ok(None) => Ok(vec![]),
+            Err(AppError::ChannelsNotFound | AppError::MultiplayerUserNotInMatch) => {
+                Ok(vec![protocol::reply(
+                    protocol::ERR_NOSUCHCHANNEL,
+                    &self.nickname,
+                    &format!("{target} :No such channel"),
+                )])
+            }
+            Err(AppError::UsersNotFound) => Ok(vec![protocol::reply(
+                protocol::ERR_NOSUCHNICK,
+                &self.nickname,
+                &format!("{target} :No such nick/channel"),
+            )]),
+            Err(
+                e @ (AppError::ChannelsUnauthorized
+                | AppError::ChannelsBanned
+                | AppError::ChannelsMuted
+                | AppError::ChannelsSlowMode
+                | AppError::InteractionBlocked),
+            ) => Ok(vec![protocol::reply(
+                protocol::ERR_CANNOTSENDTOCHAN,
+                &self.nickname,
+                &format!("{target} :{}", e.message()),
+            )]),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn names(&self, command: &IrcCommand<'_>) -> ServiceResult<Vec<String>> {
+        let Some(channel_list) = command.param(0) else {
+            return Ok(vec![self.need_more_params("NAMES")]);
+        };
+
+        let mut replies = vec![];
+        for channel in channel_list.split(',').filter(|c| !c.is_empty()) {
+            replies.extend(self.channel_names(channel).await?);
+        }
+        Ok(replies)
+    }
+
+    async fn channel_names(&self, channel: &str) -> ServiceResult<Vec<String>> {
+        let channel_name = channels::get_channel_name(&self.ctx, &self.session, channel).await?;
+        let channel_info = channels::fetch_one(&self.ctx, channel_name).await?;
+        if !channel_info.can_read(self.session.privileges) {
+            return Err(AppError::ChannelsUnauthorized);
+        }
+
+        let mut nicknames = vec![];
+        for session_id in channels::fetch_members(&self.ctx, channel_name).await? {
+            match sessions::fetch_one(&self.ctx, session_id).await {
+                Ok(session) => nicknames.push(protocol::nickname(&session.username)),
+                Err(AppError::SessionsNotFound) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        nicknames.sort_unstable();
+        nicknames.dedup();
+
+        let mut replies: Vec<String> = nicknames
+            .chunks(32)
+            .map(|chunk| {
+                protocol::reply(
+                    protocol::RPL_NAMREPLY,
+                    &self.nickname,
+                    &format!("= {channel} :{}", chunk.join(" ")),
+                )
+            })
+            .collect();
+        replies.push(protocol::reply(
+            protocol::RPL_ENDOFNAMES,
+            &self.nickname,
+            &format!("{channel} :End of /NAMES list"),
+        ));
+        Ok(replies)
+    }
+
+    async fn whois(&self, command: &IrcCommand<'_>) -> ServiceResult<Vec<String>> {
+        // WHOIS [server] nickname
+        let Some(target) = command.params.last().copied() else {
+            return Ok(vec![protocol::reply(
+                protocol::ERR_NONICKNAMEGIVEN,
+                &self.nickname,
+                ":No nickname given",
+            )]);
+        };
+
+        let target_session = sessions::fetch_by_username(&self.ctx, target)
+            .await?
+            .find(|session| session.is_publicly_visible() || self.session.privileges.is_staff());
+        let Some(target_session) = target_session else {
+            return Ok(vec![
+                protocol::reply(
+                    protocol::ERR_NOSUCHNICK,
+                    &self.nickname,
+                    &format!("{target} :No such nick/channel"),
+                ),
+                protocol::reply(
+                    protocol::RPL_ENDOFWHOIS,
+                    &self.nickname,
+                    &format!("{target} :End of /WHOIS list"),
+                ),
+            ]);
+        };
+
+        let presence = presences::fetch_one(&self.ctx, target_session.user_id).await?;
+        let target_nickname = protocol::nickname(&presence.username);
+        Ok(vec![
+            protocol::reply(
+                protocol::RPL_WHOISUSER,
+                &self.nickname,
+                &format!(
+                    "{target_nickname} {target_nickname} {SERVER_NAME} * :{} ({})",
+                    presence.username,
+                    presence.location.country.code(),
+                ),
+            ),
+            protocol::reply(
+                protocol::RPL_WHOISSERVER,
+                &self.nickname,
+                &format!("{target_nickname} {SERVER_NAME} :Akatsuki"),
+            ),
+            protocol::reply(
+                protocol::RPL_ENDOFWHOIS,
+                &self.nickname,
+                &format!("{target_nickname} :End of /WHOIS list"),
+            ),
+        ])
+    }
+
+    /// Converts bancho packets into IRC lines.
+    /// Only chat messages have an IRC equivalent, everything else is dropped.
+    fn translate_bancho_data(&self, data: Vec<u8>) -> Vec<String> {
+        let data = Bytes::from(data);
+        let packets = match Events::try_from(&data) {
+            Ok(packets) => packets,
+            Err(e) => {
+                error!("Failed to decode bancho packets for IRC: {e:?}");
+                return vec![];
+            }
+        };
+
+        let mut lines = vec![];
+        for packet in packets.events {
+            if packet.event_type != MessageType::ChatMessage {
+                continue;
+            }
+
+            let message: IrcMessage = match BinaryDeserialize::deserialize(packet.data) {
+                Ok(message) => message,
+                Err(_) => continue,
+            };
+            let sender = protocol::nickname(message.sender);
+            if sender == self.nickname {
+                continue;
+            }
+
+            let target = match message.recipient.starts_with('#') {
+                true => message.recipient,
+                false => self.nickname.as_str(),
+            };
+            lines.extend(protocol::privmsg(&sender, target, message.text));
+        }
+        lines
+    }
+
+    fn need_more_params(&self, command: &str) -> String {
+        protocol::reply(
+            protocol::ERR_NEEDMOREPARAMS,
+            &self.nickname,
+            &format!("{command} :Not enough parameters"),
+        )
+    }
+
+    async fn send(&mut self, line: &str) -> ServiceResult<()> {
+        send_line(&mut self.writer, line).await
+    }
+
+    async fn send_all(&mut self, lines: &[String]) -> ServiceResult<()> {
+        for line in lines {
+            self.writer.write_all(line.as_bytes()).await?;
+            self.writer.write_all(b"\r\n").await?;
+        }
+        self.writer.flush().await?;
+        Ok(())
+    }
+}
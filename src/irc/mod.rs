@@ -0,0 +1,35 @@
+mod connection;
+mod protocol;
+
+use crate::lifecycle;
+use crate::settings::AppSettings;
+use std::net::SocketAddr;
+use tokio::net::TcpListener;
+use tracing::{error, info};
+
+// TODO: change return type to anyhow::Result<!> when its stabilized
+pub async fn serve(settings: &AppSettings) -> anyhow::Result<()> {
+    let state = lifecycle::initialize_state(settings).await?;
+    let addr = SocketAddr::from((settings.app_host, settings.app_port));
+    info!("Listening for IRC connections on {addr}");
+    let listener = TcpListener::bind(addr).await?;
+    loop {
+        let (stream, peer_addr) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                error!("Failed to accept IRC connection: {e:?}");
+                continue;
+            }
+        };
+
+        let ctx = state.clone();
+        tokio::spawn(async move {
+            if let Err(e) = connection::handle(ctx, stream, peer_addr).await {
+                error!(
+                    peer_addr = peer_addr.to_string(),
+                    "Error handling IRC connection: {e:?}"
+                );
+            }
+        });
+    }
+}
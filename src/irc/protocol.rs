@@ -0,0 +1,111 @@
+pub const SERVER_NAME: &str = "irc.akatsuki.gg";
+
+pub const RPL_WELCOME: &str = "001";
+pub const RPL_YOURHOST: &str = "002";
+pub const RPL_CREATED: &str = "003";
+pub const RPL_MYINFO: &str = "004";
+pub const RPL_WHOISUSER: &str = "311";
+pub const RPL_WHOISSERVER: &str = "312";
+pub const RPL_ENDOFWHOIS: &str = "318";
+pub const RPL_NOTOPIC: &str = "331";
+pub const RPL_TOPIC: &str = "332";
+pub const RPL_NAMREPLY: &str = "353";
+pub const RPL_ENDOFNAMES: &str = "366";
+pub const RPL_MOTD: &str = "372";
+pub const RPL_MOTDSTART: &str = "375";
+pub const RPL_ENDOFMOTD: &str = "376";
+pub const ERR_NOSUCHNICK: &str = "401";
+pub const ERR_NOSUCHCHANNEL: &str = "403";
+pub const ERR_CANNOTSENDTOCHAN: &str = "404";
+pub const ERR_NORECIPIENT: &str = "411";
+pub const ERR_NOTEXTTOSEND: &str = "412";
+pub const ERR_UNKNOWNCOMMAND: &str = "421";
+pub const ERR_NONICKNAMEGIVEN: &str = "431";
+pub const ERR_NEEDMOREPARAMS: &str = "461";
+pub const ERR_PASSWDMISMATCH: &str = "464";
+pub const ERR_BANNEDFROMCHAN: &str = "474";
+
+/// A single line sent by an IRC client, as described in RFC 1459 section 2.3.
+#[derive(Debug)]
+pub struct IrcCommand<'a> {
+    pub command: String,
+    pub params: Vec<&'a str>,
+}
+
+impl<'a> IrcCommand<'a> {
+    pub fn parse(line: &'a str) -> Option<Self> {
+        let mut line = line.trim_end_matches(['\r', '\n']).trim_start();
+        // clients may send their own prefix, which we ignore
+        if line.starts_with(':') {
+            let (_prefix, rest) = line.split_once(' ')?;
+            line = rest.trim_start();
+        }
+
+        let (command, mut rest) = match line.split_once(' ') {
+            Some((command, rest)) => (command, rest),
+            None => (line, ""),
+        };
+        if command.is_empty() {
+            return None;
+        }
+
+        let mut params = vec![];
+        loop {
+            rest = rest.trim_start_matches(' ');
+            if rest.is_empty() {
+                break;
+            }
+
+            if let Some(trailing) = rest.strip_prefix(':') {
+                params.push(trailing);
+                break;
+            }
+
+            match rest.split_once(' ') {
+                Some((param, remaining)) => {
+                    params.push(param);
+                    rest = remaining;
+                }
+                None => {
+                    params.push(rest);
+                    break;
+                }
+            }
+        }
+
+        Some(Self {
+            command: command.to_ascii_uppercase(),
+            params,
+        })
+    }
+
+    pub fn param(&self, index: usize) -> Option<&'a str> {
+        self.params.get(index).copied()
+    }
+}
+
+/// IRC nicknames cannot contain spaces.
+pub fn nickname(username: &str) -> String {
+    username.replace(' ', "_")
+}
+
+pub fn user_prefix(nickname: &str) -> String {
+    format!("{nickname}!{nickname}@{SERVER_NAME}")
+}
+
+pub fn reply(numeric: &str, nickname: &str, params: &str) -> String {
+    format!(":{SERVER_NAME} {numeric} {nickname} {params}")
+}
+
+pub fn notice(nickname: &str, text: &str) -> String {
+    format!(":{SERVER_NAME} NOTICE {nickname} :{text}")
+}
+
+/// Builds one PRIVMSG line per line of text, as IRC messages cannot span multiple lines.
+pub fn privmsg(sender: &str, target: &str, text: &str) -> Vec<String> {
+    let prefix = user_prefix(sender);
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| format!(":{prefix} PRIVMSG {target} :{line}"))
+        .collect()
+}
@@ -4,6 +4,7 @@ pub mod commands;
 pub mod common;
 pub mod entities;
 pub mod events;
+pub mod irc;
 pub mod lifecycle;
 pub mod models;
 pub mod repositories;
@@ -1,11 +1,17 @@
 use crate::common::redis_pool::{RedisPool, RedisPoolManager};
+use crate::common::shutdown;
 use crate::common::state::AppState;
 use crate::settings::AppSettings;
+use crate::usecases::bancho_settings;
 use deadpool::Runtime;
+use redis::aio::MultiplexedConnection;
 use redis::io::tcp::TcpSettings;
 use redis::{AsyncConnectionConfig, Commands};
 use sqlx::mysql::MySqlPoolOptions;
 use sqlx::{MySql, Pool};
+use std::time::Duration;
+use tokio::signal::unix::{SignalKind, signal};
+use tracing::info;
 
 pub fn initialize_logging(settings: &AppSettings) {
     tracing_subscriber::fmt()
@@ -19,7 +25,12 @@ pub fn initialize_logging(settings: &AppSettings) {
 pub async fn initialize_state(settings: &AppSettings) -> anyhow::Result<AppState> {
     let db = initialize_db(&settings).await?;
     let redis = initialize_redis(&settings)?;
-    Ok(AppState { db, redis })
+    let state = AppState { db, redis };
+    // later read without waiting on the database, see `bancho_settings::cached`
+    if let Err(e) = bancho_settings::fetch_current(&state).await {
+        anyhow::bail!("Failed to load bancho settings: {e:?}");
+    }
+    Ok(state)
 }
 
 pub fn initialize_db(settings: &AppSettings) -> impl Future<Output = sqlx::Result<Pool<MySql>>> {
@@ -46,3 +57,30 @@ pub fn initialize_redis(settings: &AppSettings) -> anyhow::Result<RedisPool> {
         .build()?;
     Ok(redis)
 }
+
+/// Opens a redis connection outside of the pool for commands
+/// that block for longer than the configured response timeout.
+pub async fn connect_redis_blocking(
+    settings: &AppSettings,
+    block_timeout: Duration,
+) -> anyhow::Result<MultiplexedConnection> {
+    let redis_client = redis::Client::open(settings.redis_url.as_str())?;
+    let redis_cfg = AsyncConnectionConfig::new()
+        .set_connection_timeout(settings.redis_connection_timeout)
+        .set_response_timeout(settings.redis_response_timeout + block_timeout);
+    let connection = redis_client
+        .get_multiplexed_async_connection_with_config(&redis_cfg)
+        .await?;
+    Ok(connection)
+}
+
+/// Resolves once SIGTERM or SIGINT is received and marks the process as shutting down.
+pub async fn shutdown_signal() {
+    let mut terminate = signal(SignalKind::terminate()).expect("Failed to install SIGTERM handler");
+    tokio::select! {
+        _ = terminate.recv() => {}
+        _ = tokio::signal::ctrl_c() => {}
+    }
+    info!("Shutdown signal received, draining connections...");
+    shutdown::begin();
+}
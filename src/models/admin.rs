@@ -0,0 +1,73 @@
+use crate::models::beatmaps::RankedStatus;
+use crate::models::users::User;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+pub struct ReasonArgs {
+    pub reason: String,
+}
+
+#[derive(Deserialize)]
+pub struct PunishArgs {
+    pub reason: String,
+    /// Permanent when not given
+    pub duration_seconds: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub struct SilenceArgs {
+    pub seconds: i64,
+    pub reason: String,
+}
+
+#[derive(Deserialize)]
+pub struct AlertArgs {
+    pub message: String,
+}
+
+#[derive(Deserialize)]
+pub struct MapStatusArgs {
+    pub status: RankedStatus,
+}
+
+#[derive(Deserialize)]
+pub struct AuditLogQuery {
+    /// Only entries performed by or against this user
+    pub user_id: Option<i64>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct MaintenanceStatus {
+    pub enabled: bool,
+}
+
+#[derive(Serialize)]
+pub struct UserActionResponse {
+    pub user_id: i64,
+    pub username: String,
+}
+
+impl From<User> for UserActionResponse {
+    fn from(user: User) -> Self {
+        Self {
+            user_id: user.user_id,
+            username: user.username,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct SessionsActionResponse {
+    pub user_id: i64,
+    /// How many online sessions of the user were affected
+    pub sessions: usize,
+}
+
+#[derive(Serialize)]
+pub struct MapStatusResponse {
+    pub beatmap_id: i32,
+    pub beatmapset_id: i32,
+    pub previous_status: RankedStatus,
+    pub status: RankedStatus,
+}
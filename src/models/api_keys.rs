@@ -0,0 +1,114 @@
+use crate::common::error::{AppError, ServiceResult};
+use crate::entities::api_keys::ApiKey as Entity;
+use std::str::FromStr;
+
+/// What an API key may be used for.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ApiKeyScope {
+    /// Grants every other scope
+    All,
+    /// Kicking and logging out users
+    Kick,
+    Silence,
+    Restrict,
+    Ban,
+    Freeze,
+    Alert,
+    Beatmaps,
+    Maintenance,
+    /// Reading the audit log
+    Audit,
+    /// Sending messages as the bot through the legacy ripple API
+    Chatbot,
+}
+
+pub struct ApiKey {
+    pub id: i64,
+    pub name: String,
+    pub scopes: Vec<ApiKeyScope>,
+    pub created_by: i64,
+}
+
+impl ApiKeyScope {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            ApiKeyScope::All => "*",
+            ApiKeyScope::Kick => "kick",
+            ApiKeyScope::Silence => "silence",
+            ApiKeyScope::Restrict => "restrict",
+            ApiKeyScope::Ban => "ban",
+            ApiKeyScope::Freeze => "freeze",
+            ApiKeyScope::Alert => "alert",
+            ApiKeyScope::Beatmaps => "beatmaps",
+            ApiKeyScope::Maintenance => "maintenance",
+            ApiKeyScope::Audit => "audit",
+            ApiKeyScope::Chatbot => "chatbot",
+        }
+    }
+
+    /// Parses a comma separated list of scopes.
+    pub fn parse_list(scopes: &str) -> ServiceResult<Vec<Self>> {
+        scopes
+            .split(',')
+            .map(str::trim)
+            .filter(|scope| !scope.is_empty())
+            .map(ApiKeyScope::from_str)
+            .collect()
+    }
+
+    pub fn format_list(scopes: &[Self]) -> String {
+        scopes
+            .iter()
+            .map(ApiKeyScope::as_str)
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+impl FromStr for ApiKeyScope {
+    type Err = AppError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "*" => Ok(ApiKeyScope::All),
+            "kick" => Ok(ApiKeyScope::Kick),
+            "silence" => Ok(ApiKeyScope::Silence),
+            "restrict" => Ok(ApiKeyScope::Restrict),
+            "ban" => Ok(ApiKeyScope::Ban),
+            "freeze" => Ok(ApiKeyScope::Freeze),
+            "alert" => Ok(ApiKeyScope::Alert),
+            "beatmaps" => Ok(ApiKeyScope::Beatmaps),
+            "maintenance" => Ok(ApiKeyScope::Maintenance),
+            "audit" => Ok(ApiKeyScope::Audit),
+            "chatbot" => Ok(ApiKeyScope::Chatbot),
+            _ => Err(AppError::ApiKeysInvalidScope),
+        }
+    }
+}
+
+impl ApiKey {
+    pub fn has_scope(&self, scope: ApiKeyScope) -> bool {
+        self.scopes
+            .iter()
+            .any(|granted| *granted == ApiKeyScope::All || *granted == scope)
+    }
+
+    pub fn require_scope(&self, scope: ApiKeyScope) -> ServiceResult<()> {
+        match self.has_scope(scope) {
+            true => Ok(()),
+            false => Err(AppError::ApiKeysMissingScope),
+        }
+    }
+}
+
+impl TryFrom<Entity> for ApiKey {
+    type Error = AppError;
+
+    fn try_from(value: Entity) -> ServiceResult<Self> {
+        Ok(Self {
+            id: value.id,
+            name: value.name,
+            scopes: ApiKeyScope::parse_list(&value.scopes)?,
+            created_by: value.created_by,
+        })
+    }
+}
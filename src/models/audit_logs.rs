@@ -0,0 +1,37 @@
+use crate::entities::audit_logs::AuditLog as Entity;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct AuditLog {
+    pub id: i64,
+    /// The staff member who performed the action, or the creator of the API key used.
+    pub user_id: i64,
+    /// The staff member's username, or the name of the API key used.
+    pub username: String,
+    pub api_key_id: Option<i64>,
+    pub action: String,
+    pub target_user_id: Option<i64>,
+    pub target: Option<String>,
+    pub arguments: Option<String>,
+    /// The error code of a failed action, `None` if it succeeded
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Entity> for AuditLog {
+    fn from(value: Entity) -> Self {
+        Self {
+            id: value.id,
+            user_id: value.user_id,
+            username: value.username,
+            api_key_id: value.api_key_id,
+            action: value.action,
+            target_user_id: value.target_user_id,
+            target: value.target,
+            arguments: value.arguments,
+            error: value.error,
+            created_at: value.created_at,
+        }
+    }
+}
@@ -1,9 +1,12 @@
 use crate::common::error::AppError;
+use crate::models::bancho_settings::BanchoSettings;
+use crate::models::client_builds::BuildVersion;
 use axum::extract::{FromRequest, Request};
 use axum::response::{IntoResponse, Response};
 use bancho_protocol::messages::Message;
 use bancho_protocol::messages::server::Alert;
 use chrono::{Months, NaiveDate};
+use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 use uuid::Uuid;
 
@@ -80,7 +83,7 @@ pub struct ClientInfo {
     pub pm_private: bool,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum ReleaseStream {
     Stable,
     Beta,
@@ -88,7 +91,7 @@ pub enum ReleaseStream {
     Tourney,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct OsuVersion {
     pub release_stream: ReleaseStream,
     pub version_date: NaiveDate,
@@ -96,18 +99,38 @@ pub struct OsuVersion {
 }
 
 impl OsuVersion {
-    pub fn is_outdated(&self) -> bool {
+    pub fn is_outdated(&self, settings: &BanchoSettings) -> bool {
         let today = chrono::Utc::now().date_naive();
-        let version_expiration_months = match self.release_stream {
-            ReleaseStream::Tourney => 24,
-            ReleaseStream::Stable => 24,
-            ReleaseStream::Beta => 24,
-            ReleaseStream::CuttingEdge => 12,
-        };
+        let version_expiration_months = settings.expiration_months(&self.release_stream);
         let version_expiration_date = self.version_date + Months::new(version_expiration_months);
         // Version is outdated
         today > version_expiration_date
     }
+
+    pub const fn build(&self) -> BuildVersion {
+        BuildVersion {
+            date: self.version_date,
+            minor: self.version_minor,
+        }
+    }
+}
+
+impl ReleaseStream {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            ReleaseStream::Stable => "",
+            ReleaseStream::Beta => "beta",
+            ReleaseStream::CuttingEdge => "cuttingedge",
+            ReleaseStream::Tourney => "tourney",
+        }
+    }
+}
+
+/// Formats the version the way the client sends it, e.g. `b20240123.2cuttingedge`.
+impl Display for OsuVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "b{}{}", self.build(), self.release_stream.as_str())
+    }
 }
 
 impl FromStr for OsuVersion {
@@ -0,0 +1,345 @@
+use crate::common::error::AppError;
+use crate::models::bancho::ReleaseStream;
+use crate::models::client_builds::BuildVersion;
+use crate::models::rate_limits::{RateLimit, RateLimitAction};
+use std::str::FromStr;
+
+const DEFAULT_WELCOME_MESSAGE: &str = r#"
+             Welcome to Akatsuki!
+             Running banchus v0.1
+ "#; // This space is needed for osu! to render the line
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BanchoSettingKey {
+    UserSessionsLimit,
+    TournamentStaffSessionsLimit,
+    ChatRateLimit,
+    ChannelChatRateLimit,
+    ReportRateLimit,
+    RollRateLimit,
+    MatchCreateRateLimit,
+    FriendsRateLimit,
+    StreamMessageTtl,
+    StreamClearInterval,
+    MaxRoll,
+    ReconnectDelay,
+    WelcomeMessage,
+    MenuIconUrl,
+    MenuIconLink,
+    StableExpirationMonths,
+    BetaExpirationMonths,
+    CuttingEdgeExpirationMonths,
+    TourneyExpirationMonths,
+    StableMinBuild,
+    BetaMinBuild,
+    CuttingEdgeMinBuild,
+    TourneyMinBuild,
+}
+
+impl BanchoSettingKey {
+    pub const ALL: [BanchoSettingKey; 23] = [
+        BanchoSettingKey::UserSessionsLimit,
+        BanchoSettingKey::TournamentStaffSessionsLimit,
+        BanchoSettingKey::ChatRateLimit,
+        BanchoSettingKey::ChannelChatRateLimit,
+        BanchoSettingKey::ReportRateLimit,
+        BanchoSettingKey::RollRateLimit,
+        BanchoSettingKey::MatchCreateRateLimit,
+        BanchoSettingKey::FriendsRateLimit,
+        BanchoSettingKey::StreamMessageTtl,
+        BanchoSettingKey::StreamClearInterval,
+        BanchoSettingKey::MaxRoll,
+        BanchoSettingKey::ReconnectDelay,
+        BanchoSettingKey::WelcomeMessage,
+        BanchoSettingKey::MenuIconUrl,
+        BanchoSettingKey::MenuIconLink,
+        BanchoSettingKey::StableExpirationMonths,
+        BanchoSettingKey::BetaExpirationMonths,
+        BanchoSettingKey::CuttingEdgeExpirationMonths,
+        BanchoSettingKey::TourneyExpirationMonths,
+        BanchoSettingKey::StableMinBuild,
+        BanchoSettingKey::BetaMinBuild,
+        BanchoSettingKey::CuttingEdgeMinBuild,
+        BanchoSettingKey::TourneyMinBuild,
+    ];
+
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            BanchoSettingKey::UserSessionsLimit => "user_sessions_limit",
+            BanchoSettingKey::TournamentStaffSessionsLimit => "tournament_staff_sessions_limit",
+            BanchoSettingKey::ChatRateLimit => "chat_rate_limit",
+            BanchoSettingKey::ChannelChatRateLimit => "channel_chat_rate_limit",
+            BanchoSettingKey::ReportRateLimit => "report_rate_limit",
+            BanchoSettingKey::RollRateLimit => "roll_rate_limit",
+            BanchoSettingKey::MatchCreateRateLimit => "match_create_rate_limit",
+            BanchoSettingKey::FriendsRateLimit => "friends_rate_limit",
+            BanchoSettingKey::StreamMessageTtl => "stream_message_ttl",
+            BanchoSettingKey::StreamClearInterval => "stream_clear_interval",
+            BanchoSettingKey::MaxRoll => "max_roll",
+            BanchoSettingKey::ReconnectDelay => "reconnect_delay",
+            BanchoSettingKey::WelcomeMessage => "welcome_message",
+            BanchoSettingKey::MenuIconUrl => "menu_icon_url",
+            BanchoSettingKey::MenuIconLink => "menu_icon_link",
+            BanchoSettingKey::StableExpirationMonths => "stable_expiration_months",
+            BanchoSettingKey::BetaExpirationMonths => "beta_expiration_months",
+            BanchoSettingKey::CuttingEdgeExpirationMonths => "cutting_edge_expiration_months",
+            BanchoSettingKey::TourneyExpirationMonths => "tourney_expiration_months",
+            BanchoSettingKey::StableMinBuild => "stable_min_build",
+            BanchoSettingKey::BetaMinBuild => "beta_min_build",
+            BanchoSettingKey::CuttingEdgeMinBuild => "cutting_edge_min_build",
+            BanchoSettingKey::TourneyMinBuild => "tourney_min_build",
+        }
+    }
+
+    /// Integer settings are stored in `value_int`, everything else in `value_string`.
+    pub const fn is_int(&self) -> bool {
+        !matches!(
+            self,
+            BanchoSettingKey::ChatRateLimit
+                | BanchoSettingKey::ChannelChatRateLimit
+                | BanchoSettingKey::ReportRateLimit
+                | BanchoSettingKey::RollRateLimit
+                | BanchoSettingKey::MatchCreateRateLimit
+                | BanchoSettingKey::FriendsRateLimit
+                | BanchoSettingKey::WelcomeMessage
+                | BanchoSettingKey::MenuIconUrl
+                | BanchoSettingKey::MenuIconLink
+                | BanchoSettingKey::StableMinBuild
+                | BanchoSettingKey::BetaMinBuild
+                | BanchoSettingKey::CuttingEdgeMinBuild
+                | BanchoSettingKey::TourneyMinBuild
+        )
+    }
+}
+
+impl FromStr for BanchoSettingKey {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        BanchoSettingKey::ALL
+            .into_iter()
+            .find(|key| key.as_str() == s)
+            .ok_or(AppError::SettingsInvalidKey)
+    }
+}
+
+/// Tunables which can be changed at runtime, stored in the `bancho_settings` table.
+/// Settings without a row keep their default value.
+#[derive(Debug, Clone)]
+pub struct BanchoSettings {
+    pub user_sessions_limit: u64,
+    pub tournament_staff_sessions_limit: u64,
+    pub chat_rate_limit: RateLimit,
+    pub channel_chat_rate_limit: RateLimit,
+    pub report_rate_limit: RateLimit,
+    pub roll_rate_limit: RateLimit,
+    pub match_create_rate_limit: RateLimit,
+    pub friends_rate_limit: RateLimit,
+    /// Seconds messages are kept in a stream
+    pub stream_message_ttl: u64,
+    /// Streams without any new message within this many seconds are cleared
+    pub stream_clear_interval: u64,
+    pub max_roll: i32,
+    /// Milliseconds clients wait before reconnecting
+    pub reconnect_delay: u32,
+    pub welcome_message: String,
+    /// Image shown in the main menu, nothing is shown if empty
+    pub menu_icon_url: String,
+    /// Opened when clicking the main menu icon
+    pub menu_icon_link: String,
+    pub stable_expiration_months: u32,
+    pub beta_expiration_months: u32,
+    pub cutting_edge_expiration_months: u32,
+    pub tourney_expiration_months: u32,
+    /// Older builds of the stream are rejected on login, there is no minimum if unset
+    pub stable_min_build: Option<BuildVersion>,
+    pub beta_min_build: Option<BuildVersion>,
+    pub cutting_edge_min_build: Option<BuildVersion>,
+    pub tourney_min_build: Option<BuildVersion>,
+}
+
+impl Default for BanchoSettings {
+    fn default() -> Self {
+        Self {
+            user_sessions_limit: 20,
+            tournament_staff_sessions_limit: 40,
+            chat_rate_limit: RateLimit::new(10, 10),
+            channel_chat_rate_limit: RateLimit::new(6, 5),
+            report_rate_limit: RateLimit::new(3, 5 * 60),
+            roll_rate_limit: RateLimit::new(5, 30),
+            match_create_rate_limit: RateLimit::new(5, 60),
+            friends_rate_limit: RateLimit::new(20, 60),
+            stream_message_ttl: 5 * 60,
+            stream_clear_interval: 10 * 60,
+            max_roll: 1_000_000,
+            reconnect_delay: 750,
+            welcome_message: DEFAULT_WELCOME_MESSAGE.to_owned(),
+            menu_icon_url: String::new(),
+            menu_icon_link: String::new(),
+            stable_expiration_months: 24,
+            beta_expiration_months: 24,
+            cutting_edge_expiration_months: 12,
+            tourney_expiration_months: 24,
+            stable_min_build: None,
+            beta_min_build: None,
+            cutting_edge_min_build: None,
+            tourney_min_build: None,
+        }
+    }
+}
+
+impl BanchoSettings {
+    pub fn get(&self, key: BanchoSettingKey) -> String {
+        match key {
+            BanchoSettingKey::UserSessionsLimit => self.user_sessions_limit.to_string(),
+            BanchoSettingKey::TournamentStaffSessionsLimit => {
+                self.tournament_staff_sessions_limit.to_string()
+            }
+            BanchoSettingKey::ChatRateLimit => format_rate_limit(&self.chat_rate_limit),
+            BanchoSettingKey::ChannelChatRateLimit => {
+                format_rate_limit(&self.channel_chat_rate_limit)
+            }
+            BanchoSettingKey::ReportRateLimit => format_rate_limit(&self.report_rate_limit),
+            BanchoSettingKey::RollRateLimit => format_rate_limit(&self.roll_rate_limit),
+            BanchoSettingKey::MatchCreateRateLimit => {
+                format_rate_limit(&self.match_create_rate_limit)
+            }
+            BanchoSettingKey::FriendsRateLimit => format_rate_limit(&self.friends_rate_limit),
+            BanchoSettingKey::StreamMessageTtl => self.stream_message_ttl.to_string(),
+            BanchoSettingKey::StreamClearInterval => self.stream_clear_interval.to_string(),
+            BanchoSettingKey::MaxRoll => self.max_roll.to_string(),
+            BanchoSettingKey::ReconnectDelay => self.reconnect_delay.to_string(),
+            BanchoSettingKey::WelcomeMessage => self.welcome_message.clone(),
+            BanchoSettingKey::MenuIconUrl => self.menu_icon_url.clone(),
+            BanchoSettingKey::MenuIconLink => self.menu_icon_link.clone(),
+            BanchoSettingKey::StableExpirationMonths => self.stable_expiration_months.to_string(),
+            BanchoSettingKey::BetaExpirationMonths => self.beta_expiration_months.to_string(),
+            BanchoSettingKey::CuttingEdgeExpirationMonths => {
+                self.cutting_edge_expiration_months.to_string()
+            }
+            BanchoSettingKey::TourneyExpirationMonths => self.tourney_expiration_months.to_string(),
+            BanchoSettingKey::StableMinBuild => format_build(self.stable_min_build),
+            BanchoSettingKey::BetaMinBuild => format_build(self.beta_min_build),
+            BanchoSettingKey::CuttingEdgeMinBuild => format_build(self.cutting_edge_min_build),
+            BanchoSettingKey::TourneyMinBuild => format_build(self.tourney_min_build),
+        }
+    }
+
+    /// Parses and applies the value, the settings are left untouched if it is invalid.
+    pub fn set(&mut self, key: BanchoSettingKey, value: &str) -> Result<(), AppError> {
+        match key {
+            BanchoSettingKey::UserSessionsLimit => {
+                self.user_sessions_limit = parse_positive(value)?
+            }
+            BanchoSettingKey::TournamentStaffSessionsLimit => {
+                self.tournament_staff_sessions_limit = parse_positive(value)?
+            }
+            BanchoSettingKey::ChatRateLimit => self.chat_rate_limit = parse_rate_limit(value)?,
+            BanchoSettingKey::ChannelChatRateLimit => {
+                self.channel_chat_rate_limit = parse_rate_limit(value)?
+            }
+            BanchoSettingKey::ReportRateLimit => self.report_rate_limit = parse_rate_limit(value)?,
+            BanchoSettingKey::RollRateLimit => self.roll_rate_limit = parse_rate_limit(value)?,
+            BanchoSettingKey::MatchCreateRateLimit => {
+                self.match_create_rate_limit = parse_rate_limit(value)?
+            }
+            BanchoSettingKey::FriendsRateLimit => {
+                self.friends_rate_limit = parse_rate_limit(value)?
+            }
+            BanchoSettingKey::StreamMessageTtl => self.stream_message_ttl = parse_positive(value)?,
+            BanchoSettingKey::StreamClearInterval => {
+                self.stream_clear_interval = parse_positive(value)?
+            }
+            BanchoSettingKey::MaxRoll => self.max_roll = parse_positive(value)?,
+            BanchoSettingKey::ReconnectDelay => self.reconnect_delay = parse(value)?,
+            BanchoSettingKey::WelcomeMessage => self.welcome_message = value.to_owned(),
+            BanchoSettingKey::MenuIconUrl => self.menu_icon_url = value.trim().to_owned(),
+            BanchoSettingKey::MenuIconLink => self.menu_icon_link = value.trim().to_owned(),
+            BanchoSettingKey::StableExpirationMonths => {
+                self.stable_expiration_months = parse_positive(value)?
+            }
+            BanchoSettingKey::BetaExpirationMonths => {
+                self.beta_expiration_months = parse_positive(value)?
+            }
+            BanchoSettingKey::CuttingEdgeExpirationMonths => {
+                self.cutting_edge_expiration_months = parse_positive(value)?
+            }
+            BanchoSettingKey::TourneyExpirationMonths => {
+                self.tourney_expiration_months = parse_positive(value)?
+            }
+            BanchoSettingKey::StableMinBuild => self.stable_min_build = parse_build(value)?,
+            BanchoSettingKey::BetaMinBuild => self.beta_min_build = parse_build(value)?,
+            BanchoSettingKey::CuttingEdgeMinBuild => {
+                self.cutting_edge_min_build = parse_build(value)?
+            }
+            BanchoSettingKey::TourneyMinBuild => self.tourney_min_build = parse_build(value)?,
+        }
+        Ok(())
+    }
+
+    pub const fn rate_limit(&self, action: RateLimitAction) -> RateLimit {
+        match action {
+            RateLimitAction::Chat => self.chat_rate_limit,
+            RateLimitAction::ChannelChat => self.channel_chat_rate_limit,
+            RateLimitAction::Report => self.report_rate_limit,
+            RateLimitAction::Roll => self.roll_rate_limit,
+            RateLimitAction::MatchCreate => self.match_create_rate_limit,
+            RateLimitAction::Friends => self.friends_rate_limit,
+        }
+    }
+
+    pub const fn expiration_months(&self, release_stream: &ReleaseStream) -> u32 {
+        match release_stream {
+            ReleaseStream::Tourney => self.tourney_expiration_months,
+            ReleaseStream::Stable => self.stable_expiration_months,
+            ReleaseStream::Beta => self.beta_expiration_months,
+            ReleaseStream::CuttingEdge => self.cutting_edge_expiration_months,
+        }
+    }
+
+    pub const fn min_build(&self, release_stream: &ReleaseStream) -> Option<BuildVersion> {
+        match release_stream {
+            ReleaseStream::Tourney => self.tourney_min_build,
+            ReleaseStream::Stable => self.stable_min_build,
+            ReleaseStream::Beta => self.beta_min_build,
+            ReleaseStream::CuttingEdge => self.cutting_edge_min_build,
+        }
+    }
+}
+
+fn parse<T: FromStr>(value: &str) -> Result<T, AppError> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| AppError::SettingsInvalidValue)
+}
+
+fn parse_positive<T: FromStr + Default + PartialOrd>(value: &str) -> Result<T, AppError> {
+    match parse(value)? {
+        value if value > T::default() => Ok(value),
+        _ => Err(AppError::SettingsInvalidValue),
+    }
+}
+
+fn parse_rate_limit(value: &str) -> Result<RateLimit, AppError> {
+    RateLimit::from_str(value).map_err(|_| AppError::SettingsInvalidValue)
+}
+
+/// An empty value removes the minimum build.
+fn parse_build(value: &str) -> Result<Option<BuildVersion>, AppError> {
+    match value.trim() {
+        "" => Ok(None),
+        value => match BuildVersion::from_str(value) {
+            Ok(build) => Ok(Some(build)),
+            Err(_) => Err(AppError::SettingsInvalidValue),
+        },
+    }
+}
+
+fn format_build(build: Option<BuildVersion>) -> String {
+    build.map(|build| build.to_string()).unwrap_or_default()
+}
+
+fn format_rate_limit(limit: &RateLimit) -> String {
+    format!("{}/{}", limit.count, limit.window.as_secs())
+}
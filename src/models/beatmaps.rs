@@ -1,9 +1,11 @@
 use bancho_protocol::structures::Mode;
+use serde::{Deserialize, Serialize};
 
 use crate::entities::beatmaps::Beatmap as BeatmapEntity;
 
 #[repr(i8)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum RankedStatus {
     Pending = 0,
     Ranked = 2,
@@ -1,15 +1,37 @@
+use crate::common::error::AppError;
 use crate::entities::channels::Channel as Entity;
 use crate::models::privileges::Privileges;
 
+/// How many messages are replayed when joining a spectator or multiplayer channel.
+const SCOPED_HISTORY_SIZE: usize = 20;
+
 pub struct Channel {
+    pub id: i64,
     pub name: String,
     pub description: String,
     pub read_privileges: Privileges,
     pub write_privileges: Privileges,
+    /// Only set for channels created by users
+    pub owner_user_id: Option<i64>,
+    pub invite_only: bool,
+    /// How many recent messages are replayed to users joining the channel
+    pub history_size: usize,
+    /// Minimum seconds between messages of a user, 0 if slow mode is disabled
+    pub slow_mode_seconds: u32,
     pub status: bool,
 }
 
-// TODO: modify channels table to remove this
+/// The role of a user in a channel, the owner is stored on the channel itself.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[repr(i8)]
+pub enum ChannelRole {
+    Banned = -1,
+    /// Invited to an invite-only channel
+    Member = 0,
+    Moderator = 1,
+}
+
+// TODO: backfill read_privileges and write_privileges of system channels to remove this
 fn privileges_from(name: &str, public: bool) -> Privileges {
     match name {
         "#plus" | "#supporter" | "#premium" => Privileges::Donator | Privileges::AkatsukiPlus,
@@ -34,22 +56,41 @@ impl Channel {
         self.write_privileges.is_empty() || privs.intersects(self.write_privileges)
     }
 
+    pub fn is_owner(&self, user_id: i64) -> bool {
+        self.owner_user_id == Some(user_id)
+    }
+
+    /// System channels are the ones not created by users.
+    pub fn is_system(&self) -> bool {
+        self.owner_user_id.is_none()
+    }
+
     pub fn spectator() -> Self {
         Self {
+            id: 0,
             name: "#spectator".to_owned(),
             description: "Spectator Channel".to_owned(),
             read_privileges: Privileges::None,
             write_privileges: Privileges::None,
+            owner_user_id: None,
+            invite_only: false,
+            history_size: SCOPED_HISTORY_SIZE,
+            slow_mode_seconds: 0,
             status: true,
         }
     }
 
     pub fn multiplayer() -> Self {
         Self {
+            id: 0,
             name: "#multiplayer".to_owned(),
             description: "Multiplayer Channel".to_owned(),
             read_privileges: Privileges::None,
             write_privileges: Privileges::None,
+            owner_user_id: None,
+            invite_only: false,
+            history_size: SCOPED_HISTORY_SIZE,
+            slow_mode_seconds: 0,
             status: false,
         }
     }
@@ -57,15 +98,41 @@ impl Channel {
 
 impl From<Entity> for Channel {
     fn from(value: Entity) -> Self {
-        let read_privileges = privileges_from(&value.name, value.public_read);
-        let write_privileges = privileges_from(&value.name, value.public_write);
+        // system channels without privilege masks keep their previous hardcoded privileges
+        let (read_privileges, write_privileges) = match value.owner_user_id {
+            None if value.read_privileges == 0 && value.write_privileges == 0 => (
+                privileges_from(&value.name, value.public_read),
+                privileges_from(&value.name, value.public_write),
+            ),
+            _ => (
+                Privileges::from_bits_retain(value.read_privileges),
+                Privileges::from_bits_retain(value.write_privileges),
+            ),
+        };
 
         Self {
+            id: value.id,
             name: value.name,
             description: value.description,
-            status: value.status,
             read_privileges,
             write_privileges,
+            owner_user_id: value.owner_user_id,
+            invite_only: value.invite_only,
+            history_size: value.history_size.max(0) as _,
+            slow_mode_seconds: value.slow_mode_seconds.max(0) as _,
+            status: value.status,
+        }
+    }
+}
+
+impl TryFrom<i8> for ChannelRole {
+    type Error = AppError;
+    fn try_from(value: i8) -> Result<Self, Self::Error> {
+        match value {
+            -1 => Ok(ChannelRole::Banned),
+            0 => Ok(ChannelRole::Member),
+            1 => Ok(ChannelRole::Moderator),
+            _ => Err(AppError::InternalServerError("invalid channel role value")),
         }
     }
 }
@@ -0,0 +1,162 @@
+use crate::common::error::AppError;
+use crate::entities::chat_filters::ChatFilter as Entity;
+use regex::Regex;
+use std::ops::Range;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+/// Only text with a scheme or www. counts as a link, so e.g. node.js is left alone.
+static LINK_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:https?://|www\.)((?:[a-z0-9-]+\.)+[a-z]{2,})(?:[/:?#][^\s\]]*)?")
+        .expect("invalid link regex")
+});
+static INVITE_LINK_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:https?://)?(?:www\.)?(?:discord\.gg|discord(?:app)?\.com/invite|t\.me/joinchat)/[^\s\]]+")
+        .expect("invalid invite link regex")
+});
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum FilterRuleType {
+    Literal,
+    Regex,
+    /// Matches links to any domain that is not on the list
+    AllowDomains,
+    /// Matches links to any domain on the list
+    DenyDomains,
+    InviteLinks,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FilterAction {
+    Censor(String),
+    Drop,
+    Silence(i64),
+    Flag,
+}
+
+pub struct ChatFilter {
+    pub id: i64,
+    pub rule_type: FilterRuleType,
+    pub pattern: String,
+    pub action: FilterAction,
+    matcher: FilterMatcher,
+}
+
+enum FilterMatcher {
+    Pattern(Regex),
+    Domains(Vec<String>),
+}
+
+impl FilterRuleType {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            FilterRuleType::Literal => "literal",
+            FilterRuleType::Regex => "regex",
+            FilterRuleType::AllowDomains => "allow_domains",
+            FilterRuleType::DenyDomains => "deny_domains",
+            FilterRuleType::InviteLinks => "invite_links",
+        }
+    }
+}
+
+impl FromStr for FilterRuleType {
+    type Err = AppError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "literal" => Ok(FilterRuleType::Literal),
+            "regex" => Ok(FilterRuleType::Regex),
+            "allow_domains" => Ok(FilterRuleType::AllowDomains),
+            "deny_domains" => Ok(FilterRuleType::DenyDomains),
+            "invite_links" => Ok(FilterRuleType::InviteLinks),
+            _ => Err(AppError::ChatFiltersInvalidRule),
+        }
+    }
+}
+
+impl FilterAction {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            FilterAction::Censor(_) => "censor",
+            FilterAction::Drop => "drop",
+            FilterAction::Silence(_) => "silence",
+            FilterAction::Flag => "flag",
+        }
+    }
+}
+
+impl ChatFilter {
+    pub fn new(
+        id: i64,
+        rule_type: FilterRuleType,
+        pattern: String,
+        action: FilterAction,
+    ) -> Result<Self, AppError> {
+        let matcher = match rule_type {
+            FilterRuleType::Literal => {
+                FilterMatcher::Pattern(compile(&format!("(?i){}", regex::escape(&pattern)))?)
+            }
+            FilterRuleType::Regex => FilterMatcher::Pattern(compile(&pattern)?),
+            FilterRuleType::AllowDomains | FilterRuleType::DenyDomains => {
+                let domains = pattern
+                    .split(',')
+                    .map(|domain| domain.trim().to_lowercase())
+                    .filter(|domain| !domain.is_empty())
+                    .collect();
+                FilterMatcher::Domains(domains)
+            }
+            FilterRuleType::InviteLinks => FilterMatcher::Pattern(INVITE_LINK_REGEX.clone()),
+        };
+        Ok(Self {
+            id,
+            rule_type,
+            pattern,
+            action,
+            matcher,
+        })
+    }
+
+    /// Returns the byte ranges of the text matched by this filter.
+    pub fn find_matches(&self, text: &str) -> Vec<Range<usize>> {
+        match &self.matcher {
+            FilterMatcher::Pattern(regex) => regex
+                .find_iter(text)
+                .map(|m| m.range())
+                .filter(|range| !range.is_empty())
+                .collect(),
+            FilterMatcher::Domains(domains) => {
+                let deny = self.rule_type == FilterRuleType::DenyDomains;
+                LINK_REGEX
+                    .captures_iter(text)
+                    .filter(|captures| {
+                        let domain = captures[1].to_lowercase();
+                        let listed = domains.iter().any(|listed| {
+                            domain == *listed || domain.ends_with(&format!(".{listed}"))
+                        });
+                        listed == deny
+                    })
+                    .filter_map(|captures| captures.get(0))
+                    .map(|m| m.range())
+                    .collect()
+            }
+        }
+    }
+}
+
+impl TryFrom<Entity> for ChatFilter {
+    type Error = AppError;
+    fn try_from(value: Entity) -> Result<Self, Self::Error> {
+        let rule_type = FilterRuleType::from_str(&value.rule_type)?;
+        let action = match value.action.as_str() {
+            "censor" => FilterAction::Censor(value.replacement.unwrap_or_default()),
+            "drop" => FilterAction::Drop,
+            "silence" => FilterAction::Silence(value.silence_seconds.unwrap_or_default()),
+            "flag" => FilterAction::Flag,
+            _ => return Err(AppError::ChatFiltersInvalidAction),
+        };
+        ChatFilter::new(value.id, rule_type, value.pattern, action)
+    }
+}
+
+fn compile(pattern: &str) -> Result<Regex, AppError> {
+    Regex::new(pattern).map_err(|_| AppError::ChatFiltersInvalidPattern)
+}
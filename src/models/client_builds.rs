@@ -0,0 +1,162 @@
+use crate::common::error::AppError;
+use crate::entities::client_builds::ClientBuild as Entity;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// A client build, written as `<yyyymmdd>[.<minor>]`, e.g. `20240123.2`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BuildVersion {
+    pub date: NaiveDate,
+    pub minor: Option<i32>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum BuildPolicy {
+    /// Allowed regardless of its age or the minimum version of its release stream
+    Allow,
+    Deny,
+}
+
+pub struct ClientBuild {
+    pub id: i64,
+    /// Without a minor version, every build of that date is matched
+    pub version: BuildVersion,
+    pub policy: BuildPolicy,
+    pub reason: String,
+    pub created_by: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ClientBuild {
+    pub fn matches(&self, version: BuildVersion) -> bool {
+        self.version.date == version.date
+            && (self.version.minor.is_none() || self.version.minor == version.minor)
+    }
+}
+
+impl FromStr for BuildVersion {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.strip_prefix('b').unwrap_or(s);
+        let (date, minor) = match s.split_once('.') {
+            Some((date, minor)) => (date, Some(minor)),
+            None => (s, None),
+        };
+        let date = NaiveDate::parse_from_str(date, "%Y%m%d")
+            .map_err(|_| AppError::ClientBuildsInvalidVersion)?;
+        let minor = match minor {
+            Some(minor) => {
+                Some(i32::from_str(minor).map_err(|_| AppError::ClientBuildsInvalidVersion)?)
+            }
+            None => None,
+        };
+        Ok(Self { date, minor })
+    }
+}
+
+impl Display for BuildVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.date.format("%Y%m%d"))?;
+        if let Some(minor) = self.minor {
+            write!(f, ".{minor}")?;
+        }
+        Ok(())
+    }
+}
+
+impl BuildPolicy {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            BuildPolicy::Allow => "allow",
+            BuildPolicy::Deny => "deny",
+        }
+    }
+}
+
+impl FromStr for BuildPolicy {
+    type Err = AppError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "allow" => Ok(BuildPolicy::Allow),
+            "deny" => Ok(BuildPolicy::Deny),
+            _ => Err(AppError::ClientBuildsInvalidPolicy),
+        }
+    }
+}
+
+impl TryFrom<Entity> for ClientBuild {
+    type Error = AppError;
+
+    fn try_from(value: Entity) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: value.id,
+            version: BuildVersion {
+                date: value.version_date,
+                minor: value.version_minor,
+            },
+            policy: BuildPolicy::from_str(&value.policy)?,
+            reason: value.reason,
+            created_by: value.created_by,
+            created_at: value.created_at,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(s: &str) -> BuildVersion {
+        BuildVersion::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn parses_date_and_minor() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 23).unwrap();
+        assert_eq!(version("20240123"), BuildVersion { date, minor: None });
+        assert_eq!(
+            version("20240123.2"),
+            BuildVersion {
+                date,
+                minor: Some(2)
+            }
+        );
+        assert_eq!(
+            version("b20240123.2"),
+            BuildVersion {
+                date,
+                minor: Some(2)
+            }
+        );
+        assert_eq!(version("20240123.2").to_string(), "20240123.2");
+    }
+
+    #[test]
+    fn rejects_invalid_versions() {
+        for s in ["", "20241323", "20240123.", "20240123.x", "latest"] {
+            assert!(
+                matches!(
+                    BuildVersion::from_str(s),
+                    Err(AppError::ClientBuildsInvalidVersion)
+                ),
+                "{s:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn orders_by_date_then_minor() {
+        assert!(version("20240122.9") < version("20240123"));
+        assert!(version("20240123.1") < version("20240123.2"));
+        assert!(version("20240123.10") > version("20240123.9"));
+    }
+
+    #[test]
+    fn build_without_minor_comes_first_of_its_date() {
+        assert!(version("20240123") < version("20240123.0"));
+        assert!(version("20240123") < version("20240123.1"));
+        assert!(version("20240123") > version("20240122.1"));
+    }
+}
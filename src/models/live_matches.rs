@@ -0,0 +1,96 @@
+use crate::models::multiplayer::{MultiplayerMatch, MultiplayerMatchSlots};
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct LiveMatchBeatmap {
+    pub beatmap_id: i32,
+    pub beatmap_md5: String,
+    pub beatmap_name: String,
+}
+
+#[derive(Serialize)]
+pub struct LiveMatchSlot {
+    pub slot_id: usize,
+    pub status: u8,
+    pub team: u8,
+    pub mods: u32,
+    pub user_id: Option<i64>,
+    pub loaded: bool,
+    pub skipped: bool,
+    pub failed: bool,
+    pub completed: bool,
+}
+
+#[derive(Serialize)]
+pub struct LiveMatchTimer {
+    pub timer_type: &'static str,
+    pub remaining_seconds: i64,
+}
+
+/// Public view of a multiplayer match, the password is never exposed.
+#[derive(Serialize)]
+pub struct LiveMatch {
+    pub match_id: i64,
+    pub name: String,
+    pub has_password: bool,
+    pub in_progress: bool,
+    pub powerplay: bool,
+    pub mods: u32,
+    pub freemod_enabled: bool,
+    pub beatmap: LiveMatchBeatmap,
+    pub host_user_id: i64,
+    pub mode: u8,
+    pub win_condition: u8,
+    pub team_type: u8,
+    pub last_game_id: Option<i64>,
+    pub slots: Vec<LiveMatchSlot>,
+    pub referees: Vec<i64>,
+    pub timers: Vec<LiveMatchTimer>,
+}
+
+impl LiveMatch {
+    pub fn from(
+        mp_match: MultiplayerMatch,
+        slots: MultiplayerMatchSlots,
+        referees: Vec<i64>,
+        timers: Vec<LiveMatchTimer>,
+    ) -> Self {
+        let slots = slots
+            .iter()
+            .enumerate()
+            .map(|(slot_id, slot)| LiveMatchSlot {
+                slot_id,
+                status: slot.status.bits(),
+                team: slot.team as _,
+                mods: slot.mods.bits(),
+                user_id: slot.user.map(|user| user.user_id),
+                loaded: slot.loaded,
+                skipped: slot.skipped,
+                failed: slot.failed,
+                completed: slot.completed,
+            })
+            .collect();
+        Self {
+            match_id: mp_match.match_id,
+            name: mp_match.name,
+            has_password: !mp_match.password.is_empty(),
+            in_progress: mp_match.in_progress,
+            powerplay: mp_match.powerplay,
+            mods: mp_match.mods.bits(),
+            freemod_enabled: mp_match.freemod_enabled,
+            beatmap: LiveMatchBeatmap {
+                beatmap_id: mp_match.beatmap_id,
+                beatmap_md5: mp_match.beatmap_md5,
+                beatmap_name: mp_match.beatmap_name,
+            },
+            host_user_id: mp_match.host_user_id,
+            mode: mp_match.mode as _,
+            win_condition: mp_match.win_condition as _,
+            team_type: mp_match.team_type as _,
+            last_game_id: mp_match.last_game_id,
+            slots,
+            referees,
+            timers,
+        }
+    }
+}
@@ -1,5 +1,22 @@
 use crate::common::location;
 use bancho_protocol::structures::Country;
+use serde::{Deserialize, Serialize};
+
+/// Location of an IP address as resolved by a geolocation provider.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct IpLocation {
+    /// ISO 3166-1 alpha-2 country code
+    pub country_code: Option<String>,
+    pub latitude: Option<f32>,
+    pub longitude: Option<f32>,
+}
+
+impl IpLocation {
+    /// Providers may know an address without knowing anything about its location.
+    pub const fn is_empty(&self) -> bool {
+        self.country_code.is_none() && self.latitude.is_none() && self.longitude.is_none()
+    }
+}
 
 pub struct LocationInformation {
     pub country: Country,
@@ -0,0 +1,94 @@
+use crate::common::chat::safe_username;
+use std::fmt::{Display, Formatter};
+use std::net::{IpAddr, Ipv6Addr};
+
+/// What failed login attempts are counted against.
+#[derive(Debug, Clone, Copy)]
+pub enum LoginAttemptScope<'a> {
+    /// IPv6 addresses are counted per /64 network, as that is usually what a single host gets.
+    Ip(IpAddr),
+    Username(&'a str),
+}
+
+impl LoginAttemptScope<'_> {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            LoginAttemptScope::Ip(_) => "ip",
+            LoginAttemptScope::Username(_) => "username",
+        }
+    }
+}
+
+impl Display for LoginAttemptScope<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoginAttemptScope::Ip(ip_address) => write!(f, "ips:{}", network(*ip_address)),
+            LoginAttemptScope::Username(username) => {
+                write!(f, "usernames:{}", safe_username(username))
+            }
+        }
+    }
+}
+
+fn network(ip_address: IpAddr) -> IpAddr {
+    match ip_address.to_canonical() {
+        IpAddr::V6(ip_address) => {
+            let segments = ip_address.segments();
+            IpAddr::V6(Ipv6Addr::new(
+                segments[0],
+                segments[1],
+                segments[2],
+                segments[3],
+                0,
+                0,
+                0,
+                0,
+            ))
+        }
+        ip_address => ip_address,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+    use std::str::FromStr;
+
+    fn ip(s: &str) -> IpAddr {
+        IpAddr::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn ipv6_is_counted_per_64_network() {
+        assert_eq!(network(ip("2001:db8:1:2:3:4:5:6")), ip("2001:db8:1:2::"));
+        assert_eq!(
+            network(ip("2001:db8:1:2:3:4:5:6")),
+            network(ip("2001:db8:1:2:ffff::1"))
+        );
+        assert_ne!(
+            network(ip("2001:db8:1:2::1")),
+            network(ip("2001:db8:1:3::1"))
+        );
+    }
+
+    #[test]
+    fn ipv4_is_counted_per_address() {
+        assert_eq!(network(ip("192.0.2.1")), ip("192.0.2.1"));
+        assert_eq!(
+            network(IpAddr::V6(Ipv4Addr::new(192, 0, 2, 1).to_ipv6_mapped())),
+            ip("192.0.2.1")
+        );
+    }
+
+    #[test]
+    fn scope_keys() {
+        let scope = LoginAttemptScope::Ip(ip("2001:db8::1"));
+        assert_eq!(scope.to_string(), "ips:2001:db8::");
+        let scope = LoginAttemptScope::Username("Some User");
+        assert_eq!(
+            scope.to_string(),
+            format!("usernames:{}", safe_username("Some User"))
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use crate::common::error::AppError;
+use crate::entities::login_notices::LoginNotice as Entity;
+use crate::models::users::User;
+use chrono::{DateTime, Utc};
+use std::str::FromStr;
+
+/// Who a login notice is shown to.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum NoticeTarget {
+    Everyone,
+    Donors,
+    Staff,
+    Restricted,
+    Frozen,
+    PendingVerification,
+}
+
+/// How a login notice is shown to the user.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum NoticeDelivery {
+    Alert,
+    /// Sent by the bot as a private message
+    BotMessage,
+}
+
+pub struct LoginNotice {
+    pub id: i64,
+    pub message: String,
+    pub target: NoticeTarget,
+    pub delivery: NoticeDelivery,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub created_by: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl NoticeTarget {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            NoticeTarget::Everyone => "everyone",
+            NoticeTarget::Donors => "donors",
+            NoticeTarget::Staff => "staff",
+            NoticeTarget::Restricted => "restricted",
+            NoticeTarget::Frozen => "frozen",
+            NoticeTarget::PendingVerification => "pending_verification",
+        }
+    }
+
+    pub fn matches(&self, user: &User) -> bool {
+        match self {
+            NoticeTarget::Everyone => true,
+            NoticeTarget::Donors => user.privileges.is_donor(),
+            NoticeTarget::Staff => user.privileges.is_staff(),
+            NoticeTarget::Restricted => !user.privileges.is_publicly_visible(),
+            NoticeTarget::Frozen => user.frozen,
+            NoticeTarget::PendingVerification => user.privileges.is_pending_verification(),
+        }
+    }
+}
+
+impl FromStr for NoticeTarget {
+    type Err = AppError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "everyone" => Ok(NoticeTarget::Everyone),
+            "donors" => Ok(NoticeTarget::Donors),
+            "staff" => Ok(NoticeTarget::Staff),
+            "restricted" => Ok(NoticeTarget::Restricted),
+            "frozen" => Ok(NoticeTarget::Frozen),
+            "pending_verification" => Ok(NoticeTarget::PendingVerification),
+            _ => Err(AppError::LoginNoticesInvalidTarget),
+        }
+    }
+}
+
+impl NoticeDelivery {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            NoticeDelivery::Alert => "alert",
+            NoticeDelivery::BotMessage => "bot_message",
+        }
+    }
+}
+
+impl FromStr for NoticeDelivery {
+    type Err = AppError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "alert" => Ok(NoticeDelivery::Alert),
+            "bot_message" => Ok(NoticeDelivery::BotMessage),
+            _ => Err(AppError::LoginNoticesInvalidDelivery),
+        }
+    }
+}
+
+impl TryFrom<Entity> for LoginNotice {
+    type Error = AppError;
+
+    fn try_from(value: Entity) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: value.id,
+            target: NoticeTarget::from_str(&value.target)?,
+            delivery: NoticeDelivery::from_str(&value.delivery)?,
+            message: value.message,
+            starts_at: value.starts_at,
+            ends_at: value.ends_at,
+            created_by: value.created_by,
+            created_at: value.created_at,
+        })
+    }
+}
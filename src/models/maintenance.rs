@@ -0,0 +1,20 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A scheduled maintenance window, shared by all replicas through `bancho_settings`.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MaintenanceWindow {
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub reason: String,
+}
+
+impl MaintenanceWindow {
+    pub fn has_started(&self, now: DateTime<Utc>) -> bool {
+        now >= self.starts_at
+    }
+
+    pub fn has_ended(&self, now: DateTime<Utc>) -> bool {
+        now >= self.ends_at
+    }
+}
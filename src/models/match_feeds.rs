@@ -0,0 +1,23 @@
+use crate::entities::match_game_scores::MatchScoreFrame;
+use serde::Serialize;
+
+/// Events published to the read-only match feed, consumed by broadcast overlays.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MatchFeedEvent {
+    Start {
+        game_id: i64,
+        beatmap_id: i32,
+        beatmap_md5: String,
+        mods: u32,
+        mode: u8,
+    },
+    Score(MatchScoreFrame),
+    Complete {
+        game_id: Option<i64>,
+    },
+    Abort {
+        game_id: Option<i64>,
+    },
+    Disband,
+}
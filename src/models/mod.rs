@@ -1,14 +1,27 @@
+pub mod admin;
+pub mod api_keys;
+pub mod audit_logs;
 pub mod badges;
 pub mod bancho;
+pub mod bancho_settings;
 pub mod beatmaps;
 pub mod channels;
+pub mod chat_filters;
+pub mod client_builds;
 pub mod hardware_logs;
+pub mod live_matches;
 pub mod location;
+pub mod login_attempts;
+pub mod login_notices;
+pub mod maintenance;
+pub mod match_feeds;
 pub mod messages;
 pub mod multiplayer;
 pub mod performance;
 pub mod presences;
 pub mod privileges;
+pub mod punishments;
+pub mod rate_limits;
 pub mod relationships;
 pub mod ripple;
 pub mod scores;
@@ -0,0 +1,46 @@
+use crate::entities::punishments::Punishment as Entity;
+use chrono::{DateTime, Utc};
+
+#[repr(i8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PunishmentKind {
+    Restrict = 0,
+    Ban = 1,
+}
+
+impl From<i8> for PunishmentKind {
+    fn from(value: i8) -> Self {
+        match value {
+            1 => Self::Ban,
+            _ => Self::Restrict,
+        }
+    }
+}
+
+/// A restriction or ban in the user's privileges history.
+pub struct Punishment {
+    pub id: i64,
+    pub user_id: i64,
+    pub kind: PunishmentKind,
+    pub reason: String,
+    pub created_by: i64,
+    pub created_at: DateTime<Utc>,
+    /// `None` for permanent punishments
+    pub expires_at: Option<DateTime<Utc>>,
+    pub lifted_at: Option<DateTime<Utc>>,
+}
+
+impl From<Entity> for Punishment {
+    fn from(value: Entity) -> Self {
+        Self {
+            id: value.id,
+            user_id: value.user_id,
+            kind: PunishmentKind::from(value.kind),
+            reason: value.reason,
+            created_by: value.created_by,
+            created_at: value.created_at,
+            expires_at: value.expires_at,
+            lifted_at: value.lifted_at,
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use crate::entities::channels::ChannelName;
+use std::fmt::{Display, Formatter};
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Allows `count` hits within a sliding window of `window`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub count: u32,
+    pub window: Duration,
+}
+
+impl RateLimit {
+    pub const fn new(count: u32, window_secs: u64) -> Self {
+        Self {
+            count,
+            window: Duration::from_secs(window_secs),
+        }
+    }
+}
+
+/// Parses rate limits in the `<count>/<seconds>` format, e.g. `10/10`.
+impl FromStr for RateLimit {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let Some((count, window_secs)) = s.split_once('/') else {
+            anyhow::bail!("invalid rate limit {s:?}, expected <count>/<seconds>");
+        };
+        let count = count.trim().parse()?;
+        let window_secs = window_secs.trim().parse()?;
+        match window_secs {
+            0 => anyhow::bail!("invalid rate limit {s:?}, the window must not be empty"),
+            window_secs => Ok(Self::new(count, window_secs)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitAction {
+    Chat,
+    ChannelChat,
+    Report,
+    Roll,
+    MatchCreate,
+    Friends,
+}
+
+impl RateLimitAction {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            RateLimitAction::Chat => "chat",
+            RateLimitAction::ChannelChat => "channel_chat",
+            RateLimitAction::Report => "report",
+            RateLimitAction::Roll => "roll",
+            RateLimitAction::MatchCreate => "match_create",
+            RateLimitAction::Friends => "friends",
+        }
+    }
+}
+
+/// Who a rate limit is counted against.
+#[derive(Debug, Clone, Copy)]
+pub enum RateLimitScope<'a> {
+    User(i64),
+    /// A single user within a single channel.
+    Channel(i64, ChannelName<'a>),
+    Ip(IpAddr),
+}
+
+impl Display for RateLimitScope<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RateLimitScope::User(user_id) => write!(f, "users:{user_id}"),
+            RateLimitScope::Channel(user_id, channel_name) => {
+                write!(f, "channels:{channel_name}:{user_id}")
+            }
+            RateLimitScope::Ip(ip_address) => write!(f, "ips:{ip_address}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_count_and_window() {
+        assert_eq!(
+            RateLimit::from_str("10/10").unwrap(),
+            RateLimit::new(10, 10)
+        );
+        assert_eq!(
+            RateLimit::from_str(" 5 / 60 ").unwrap(),
+            RateLimit::new(5, 60)
+        );
+        assert_eq!(RateLimit::from_str("0/1").unwrap(), RateLimit::new(0, 1));
+    }
+
+    #[test]
+    fn rejects_invalid_rate_limits() {
+        assert!(RateLimit::from_str("10").is_err());
+        assert!(RateLimit::from_str("10/0").is_err());
+        assert!(RateLimit::from_str("-1/10").is_err());
+        assert!(RateLimit::from_str("a/b").is_err());
+        assert!(RateLimit::from_str("").is_err());
+    }
+}
@@ -0,0 +1,65 @@
+use crate::common::context::Context;
+use crate::entities::api_keys::ApiKey;
+
+const TABLE_NAME: &str = "bancho_api_keys";
+const READ_FIELDS: &str = "id, name, key_hash, scopes, created_by, created_at, revoked_at";
+
+pub async fn fetch_active<C: Context>(ctx: &C) -> sqlx::Result<Vec<ApiKey>> {
+    const QUERY: &str = const_str::concat!(
+        "SELECT ",
+        READ_FIELDS,
+        " FROM ",
+        TABLE_NAME,
+        " WHERE revoked_at IS NULL ORDER BY id"
+    );
+    sqlx::query_as(QUERY).fetch_all(ctx.db()).await
+}
+
+pub async fn fetch_active_by_hash<C: Context>(
+    ctx: &C,
+    key_hash: &str,
+) -> sqlx::Result<Option<ApiKey>> {
+    const QUERY: &str = const_str::concat!(
+        "SELECT ",
+        READ_FIELDS,
+        " FROM ",
+        TABLE_NAME,
+        " WHERE key_hash = ? AND revoked_at IS NULL"
+    );
+    sqlx::query_as(QUERY)
+        .bind(key_hash)
+        .fetch_optional(ctx.db())
+        .await
+}
+
+pub async fn create<C: Context>(
+    ctx: &C,
+    name: &str,
+    key_hash: &str,
+    scopes: &str,
+    created_by: i64,
+) -> sqlx::Result<u64> {
+    const QUERY: &str = const_str::concat!(
+        "INSERT INTO ",
+        TABLE_NAME,
+        " (name, key_hash, scopes, created_by) VALUES (?, ?, ?, ?)"
+    );
+    let query_result = sqlx::query(QUERY)
+        .bind(name)
+        .bind(key_hash)
+        .bind(scopes)
+        .bind(created_by)
+        .execute(ctx.db())
+        .await?;
+    Ok(query_result.last_insert_id())
+}
+
+pub async fn revoke<C: Context>(ctx: &C, key_id: i64) -> sqlx::Result<bool> {
+    const QUERY: &str = const_str::concat!(
+        "UPDATE ",
+        TABLE_NAME,
+        " SET revoked_at = CURRENT_TIMESTAMP WHERE id = ? AND revoked_at IS NULL"
+    );
+    let query_result = sqlx::query(QUERY).bind(key_id).execute(ctx.db()).await?;
+    Ok(query_result.rows_affected() != 0)
+}
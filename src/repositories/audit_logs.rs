@@ -0,0 +1,59 @@
+use crate::common::context::Context;
+use crate::entities::audit_logs::{AuditLog, CreateAuditLogArgs};
+
+const TABLE_NAME: &str = "bancho_audit_logs";
+const READ_FIELDS: &str = "id, user_id, username, api_key_id, action, target_user_id, target, \
+arguments, error, created_at";
+
+pub async fn create<C: Context>(ctx: &C, args: CreateAuditLogArgs<'_>) -> sqlx::Result<u64> {
+    const QUERY: &str = const_str::concat!(
+        "INSERT INTO ",
+        TABLE_NAME,
+        " (user_id, username, api_key_id, action, target_user_id, target, arguments, error) ",
+        "VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+    );
+    let query_result = sqlx::query(QUERY)
+        .bind(args.user_id)
+        .bind(args.username)
+        .bind(args.api_key_id)
+        .bind(args.action)
+        .bind(args.target_user_id)
+        .bind(args.target)
+        .bind(args.arguments)
+        .bind(args.error)
+        .execute(ctx.db())
+        .await?;
+    Ok(query_result.last_insert_id())
+}
+
+/// Fetches the most recent entries performed by or against the user.
+pub async fn fetch_by_user_id<C: Context>(
+    ctx: &C,
+    user_id: i64,
+    limit: u32,
+) -> sqlx::Result<Vec<AuditLog>> {
+    const QUERY: &str = const_str::concat!(
+        "SELECT ",
+        READ_FIELDS,
+        " FROM ",
+        TABLE_NAME,
+        " WHERE user_id = ? OR target_user_id = ? ORDER BY id DESC LIMIT ?"
+    );
+    sqlx::query_as(QUERY)
+        .bind(user_id)
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(ctx.db())
+        .await
+}
+
+pub async fn fetch_recent<C: Context>(ctx: &C, limit: u32) -> sqlx::Result<Vec<AuditLog>> {
+    const QUERY: &str = const_str::concat!(
+        "SELECT ",
+        READ_FIELDS,
+        " FROM ",
+        TABLE_NAME,
+        " ORDER BY id DESC LIMIT ?"
+    );
+    sqlx::query_as(QUERY).bind(limit).fetch_all(ctx.db()).await
+}
@@ -42,3 +42,25 @@ pub async fn update<C: Context>(
         .await?;
     Ok(())
 }
+
+pub async fn create<C: Context>(
+    ctx: &C,
+    key: &str,
+    value_int: i32,
+    value_str: &str,
+) -> sqlx::Result<()> {
+    const QUERY: &str =
+        "INSERT INTO bancho_settings (name, value_int, value_string) VALUES (?, ?, ?)";
+    sqlx::query(QUERY)
+        .bind(key)
+        .bind(value_int)
+        .bind(value_str)
+        .execute(ctx.db())
+        .await?;
+    Ok(())
+}
+
+pub async fn fetch_all<C: Context>(ctx: &C) -> sqlx::Result<Vec<BanchoSetting>> {
+    const QUERY: &str = "SELECT id, name, value_int, value_string FROM bancho_settings";
+    sqlx::query_as(QUERY).fetch_all(ctx.db()).await
+}
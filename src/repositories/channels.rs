@@ -1,11 +1,15 @@
 use crate::common::context::{Context, PoolContext};
-use crate::entities::channels::{Channel, ChannelName};
-use redis::AsyncCommands;
+use crate::entities::channels::{Channel, ChannelMember, ChannelName};
+use redis::{AsyncCommands, ExistenceCheck, SetExpiry, SetOptions};
 use std::ops::DerefMut;
 use uuid::Uuid;
 
 const TABLE_NAME: &str = "bancho_channels";
-const READ_FIELDS: &str = "id, name, description, public_read, public_write, status";
+const READ_FIELDS: &str = r#"id, name, description, public_read, public_write,
+read_privileges, write_privileges, owner_user_id, invite_only, history_size, slow_mode_seconds, status"#;
+
+const MEMBERS_TABLE_NAME: &str = "bancho_channel_members";
+const MEMBERS_READ_FIELDS: &str = "channel_id, user_id, role";
 
 pub async fn fetch_one<C: Context>(ctx: &C, channel_name: &str) -> sqlx::Result<Channel> {
     const QUERY: &str = const_str::concat!(
@@ -26,6 +30,157 @@ pub async fn fetch_all<C: Context>(ctx: &C) -> sqlx::Result<Vec<Channel>> {
     sqlx::query_as(QUERY).fetch_all(ctx.db()).await
 }
 
+pub async fn create<C: Context>(
+    ctx: &C,
+    channel_name: &str,
+    description: &str,
+    owner_user_id: i64,
+) -> sqlx::Result<Channel> {
+    const QUERY: &str = const_str::concat!(
+        "INSERT INTO ",
+        TABLE_NAME,
+        " (name, description, public_read, public_write, read_privileges, write_privileges, ",
+        "owner_user_id, invite_only, status) ",
+        "VALUES (?, ?, TRUE, TRUE, 0, 0, ?, FALSE, TRUE)"
+    );
+    sqlx::query(QUERY)
+        .bind(channel_name)
+        .bind(description)
+        .bind(owner_user_id)
+        .execute(ctx.db())
+        .await?;
+    fetch_one(ctx, channel_name).await
+}
+
+pub async fn update<C: Context>(ctx: &C, channel: &Channel) -> sqlx::Result<()> {
+    const QUERY: &str = const_str::concat!(
+        "UPDATE ",
+        TABLE_NAME,
+        " SET description = ?, public_read = ?, public_write = ?, ",
+        "read_privileges = ?, write_privileges = ?, ",
+        "owner_user_id = ?, invite_only = ?, history_size = ?, slow_mode_seconds = ? ",
+        "WHERE id = ?"
+    );
+    sqlx::query(QUERY)
+        .bind(&channel.description)
+        .bind(channel.public_read)
+        .bind(channel.public_write)
+        .bind(channel.read_privileges)
+        .bind(channel.write_privileges)
+        .bind(channel.owner_user_id)
+        .bind(channel.invite_only)
+        .bind(channel.history_size)
+        .bind(channel.slow_mode_seconds)
+        .bind(channel.id)
+        .execute(ctx.db())
+        .await?;
+    Ok(())
+}
+
+pub async fn delete<C: Context>(ctx: &C, channel_id: i64) -> sqlx::Result<()> {
+    const QUERY: &str = const_str::concat!("DELETE FROM ", TABLE_NAME, " WHERE id = ?");
+    const MEMBERS_QUERY: &str =
+        const_str::concat!("DELETE FROM ", MEMBERS_TABLE_NAME, " WHERE channel_id = ?");
+    sqlx::query(MEMBERS_QUERY)
+        .bind(channel_id)
+        .execute(ctx.db())
+        .await?;
+    sqlx::query(QUERY)
+        .bind(channel_id)
+        .execute(ctx.db())
+        .await?;
+    Ok(())
+}
+
+pub async fn fetch_member<C: Context>(
+    ctx: &C,
+    channel_id: i64,
+    user_id: i64,
+) -> sqlx::Result<Option<ChannelMember>> {
+    const QUERY: &str = const_str::concat!(
+        "SELECT ",
+        MEMBERS_READ_FIELDS,
+        " FROM ",
+        MEMBERS_TABLE_NAME,
+        " WHERE channel_id = ? AND user_id = ?"
+    );
+    sqlx::query_as(QUERY)
+        .bind(channel_id)
+        .bind(user_id)
+        .fetch_optional(ctx.db())
+        .await
+}
+
+pub async fn fetch_members<C: Context>(
+    ctx: &C,
+    channel_id: i64,
+) -> sqlx::Result<Vec<ChannelMember>> {
+    const QUERY: &str = const_str::concat!(
+        "SELECT ",
+        MEMBERS_READ_FIELDS,
+        " FROM ",
+        MEMBERS_TABLE_NAME,
+        " WHERE channel_id = ?"
+    );
+    sqlx::query_as(QUERY)
+        .bind(channel_id)
+        .fetch_all(ctx.db())
+        .await
+}
+
+pub async fn fetch_user_memberships<C: Context>(
+    ctx: &C,
+    user_id: i64,
+) -> sqlx::Result<Vec<ChannelMember>> {
+    const QUERY: &str = const_str::concat!(
+        "SELECT ",
+        MEMBERS_READ_FIELDS,
+        " FROM ",
+        MEMBERS_TABLE_NAME,
+        " WHERE user_id = ?"
+    );
+    sqlx::query_as(QUERY)
+        .bind(user_id)
+        .fetch_all(ctx.db())
+        .await
+}
+
+pub async fn set_member_role<C: Context>(
+    ctx: &C,
+    channel_id: i64,
+    user_id: i64,
+    role: i8,
+) -> sqlx::Result<()> {
+    const QUERY: &str = const_str::concat!(
+        "INSERT INTO ",
+        MEMBERS_TABLE_NAME,
+        " (",
+        MEMBERS_READ_FIELDS,
+        ") VALUES (?, ?, ?) ON DUPLICATE KEY UPDATE role = VALUES(role)"
+    );
+    sqlx::query(QUERY)
+        .bind(channel_id)
+        .bind(user_id)
+        .bind(role)
+        .execute(ctx.db())
+        .await?;
+    Ok(())
+}
+
+pub async fn remove_member<C: Context>(ctx: &C, channel_id: i64, user_id: i64) -> sqlx::Result<()> {
+    const QUERY: &str = const_str::concat!(
+        "DELETE FROM ",
+        MEMBERS_TABLE_NAME,
+        " WHERE channel_id = ? AND user_id = ?"
+    );
+    sqlx::query(QUERY)
+        .bind(channel_id)
+        .bind(user_id)
+        .execute(ctx.db())
+        .await?;
+    Ok(())
+}
+
 fn make_channel_members_key(channel_name: &ChannelName) -> String {
     format!("akatsuki:bancho:channels:{channel_name}:members")
 }
@@ -34,6 +189,62 @@ fn make_session_channels_key(session_id: Uuid) -> String {
     format!("akatsuki:bancho:session:{session_id}:channels")
 }
 
+fn make_mute_key(channel_name: &str, user_id: i64) -> String {
+    format!("akatsuki:bancho:channels:{channel_name}:mutes:{user_id}")
+}
+
+fn make_slow_mode_key(channel_name: &str, user_id: i64) -> String {
+    format!("akatsuki:bancho:channels:{channel_name}:slow_mode:{user_id}")
+}
+
+pub async fn mute<C: Context>(
+    ctx: &C,
+    channel_name: &str,
+    user_id: i64,
+    mute_seconds: u64,
+) -> anyhow::Result<()> {
+    let mut redis = ctx.redis().await?;
+    let _: () = redis
+        .set_ex(make_mute_key(channel_name, user_id), true, mute_seconds)
+        .await?;
+    Ok(())
+}
+
+/// Returns whether the user was muted.
+pub async fn unmute<C: Context>(ctx: &C, channel_name: &str, user_id: i64) -> anyhow::Result<bool> {
+    let mut redis = ctx.redis().await?;
+    let removed: u64 = redis.del(make_mute_key(channel_name, user_id)).await?;
+    Ok(removed != 0)
+}
+
+/// Returns the remaining seconds of the user's mute, if they are muted.
+pub async fn fetch_mute_ttl<C: Context>(
+    ctx: &C,
+    channel_name: &str,
+    user_id: i64,
+) -> anyhow::Result<Option<u64>> {
+    let mut redis = ctx.redis().await?;
+    let ttl: i64 = redis.ttl(make_mute_key(channel_name, user_id)).await?;
+    Ok(u64::try_from(ttl).ok())
+}
+
+/// Records a message for slow mode, returning false if the user has to wait.
+pub async fn try_consume_slow_mode<C: Context>(
+    ctx: &C,
+    channel_name: &str,
+    user_id: i64,
+    slow_mode_seconds: u64,
+) -> anyhow::Result<bool> {
+    let mut redis = ctx.redis().await?;
+    let opts = SetOptions::default()
+        .conditional_set(ExistenceCheck::NX)
+        .with_expiration(SetExpiry::EX(slow_mode_seconds));
+    let result: Option<String> = redis
+        .set_options(make_slow_mode_key(channel_name, user_id), true, opts)
+        .await?;
+    Ok(result.is_some())
+}
+
 pub async fn fetch_session_channels<C: Context>(
     ctx: &C,
     session_id: Uuid,
@@ -0,0 +1,45 @@
+use crate::common::context::{Context, PoolContext};
+use crate::entities::chat_filters::ChatFilter;
+
+const TABLE_NAME: &str = "bancho_chat_filters";
+const READ_FIELDS: &str = r#"id, rule_type, pattern, action, replacement, silence_seconds,
+created_by, created_at"#;
+
+pub async fn fetch_all<C: Context>(ctx: &C) -> sqlx::Result<Vec<ChatFilter>> {
+    const QUERY: &str =
+        const_str::concat!("SELECT ", READ_FIELDS, " FROM ", TABLE_NAME, " ORDER BY id");
+    sqlx::query_as(QUERY).fetch_all(ctx.db()).await
+}
+
+pub async fn create<C: Context>(
+    ctx: &C,
+    rule_type: &str,
+    pattern: &str,
+    action: &str,
+    replacement: Option<&str>,
+    silence_seconds: Option<i64>,
+    created_by: i64,
+) -> sqlx::Result<u64> {
+    const QUERY: &str = const_str::concat!(
+        "INSERT INTO ",
+        TABLE_NAME,
+        " (rule_type, pattern, action, replacement, silence_seconds, created_by) ",
+        "VALUES (?, ?, ?, ?, ?, ?)"
+    );
+    let query_result = sqlx::query(QUERY)
+        .bind(rule_type)
+        .bind(pattern)
+        .bind(action)
+        .bind(replacement)
+        .bind(silence_seconds)
+        .bind(created_by)
+        .execute(ctx.db())
+        .await?;
+    Ok(query_result.last_insert_id())
+}
+
+pub async fn delete<C: Context>(ctx: &C, filter_id: i64) -> sqlx::Result<bool> {
+    const QUERY: &str = const_str::concat!("DELETE FROM ", TABLE_NAME, " WHERE id = ?");
+    let query_result = sqlx::query(QUERY).bind(filter_id).execute(ctx.db()).await?;
+    Ok(query_result.rows_affected() != 0)
+}
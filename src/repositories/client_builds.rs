@@ -0,0 +1,89 @@
+use crate::common::context::{Context, PoolContext};
+use crate::entities::client_builds::ClientBuild;
+use chrono::NaiveDate;
+use redis::{AsyncCommands, ExistenceCheck, SetExpiry, SetOptions};
+
+const TABLE_NAME: &str = "bancho_client_builds";
+const READ_FIELDS: &str = "id, version_date, version_minor, policy, reason, created_by, created_at";
+
+/// Denied builds are reported at most once per user and build within this window.
+const REPORT_COOLDOWN_SECONDS: u64 = 60 * 60;
+
+fn make_report_key(user_id: i64, build: &str) -> String {
+    format!("akatsuki:bancho:client_builds:reported:{user_id}:{build}")
+}
+
+pub async fn fetch_all<C: Context>(ctx: &C) -> sqlx::Result<Vec<ClientBuild>> {
+    const QUERY: &str = const_str::concat!(
+        "SELECT ",
+        READ_FIELDS,
+        " FROM ",
+        TABLE_NAME,
+        " ORDER BY version_date, version_minor"
+    );
+    sqlx::query_as(QUERY).fetch_all(ctx.db()).await
+}
+
+pub async fn fetch_by_date<C: Context>(
+    ctx: &C,
+    version_date: NaiveDate,
+) -> sqlx::Result<Vec<ClientBuild>> {
+    const QUERY: &str = const_str::concat!(
+        "SELECT ",
+        READ_FIELDS,
+        " FROM ",
+        TABLE_NAME,
+        " WHERE version_date = ?"
+    );
+    sqlx::query_as(QUERY)
+        .bind(version_date)
+        .fetch_all(ctx.db())
+        .await
+}
+
+pub async fn create<C: Context>(
+    ctx: &C,
+    version_date: NaiveDate,
+    version_minor: Option<i32>,
+    policy: &str,
+    reason: &str,
+    created_by: i64,
+) -> sqlx::Result<u64> {
+    const QUERY: &str = const_str::concat!(
+        "INSERT INTO ",
+        TABLE_NAME,
+        " (version_date, version_minor, policy, reason, created_by) ",
+        "VALUES (?, ?, ?, ?, ?)"
+    );
+    let query_result = sqlx::query(QUERY)
+        .bind(version_date)
+        .bind(version_minor)
+        .bind(policy)
+        .bind(reason)
+        .bind(created_by)
+        .execute(ctx.db())
+        .await?;
+    Ok(query_result.last_insert_id())
+}
+
+pub async fn delete<C: Context>(ctx: &C, build_id: i64) -> sqlx::Result<bool> {
+    const QUERY: &str = const_str::concat!("DELETE FROM ", TABLE_NAME, " WHERE id = ?");
+    let query_result = sqlx::query(QUERY).bind(build_id).execute(ctx.db()).await?;
+    Ok(query_result.rows_affected() != 0)
+}
+
+/// Returns false if the user's login with this build has been reported recently.
+pub async fn try_claim_report<C: Context>(
+    ctx: &C,
+    user_id: i64,
+    build: &str,
+) -> anyhow::Result<bool> {
+    let mut redis = ctx.redis().await?;
+    let opts = SetOptions::default()
+        .conditional_set(ExistenceCheck::NX)
+        .with_expiration(SetExpiry::EX(REPORT_COOLDOWN_SECONDS));
+    let result: Option<String> = redis
+        .set_options(make_report_key(user_id, build), true, opts)
+        .await?;
+    Ok(result.is_some())
+}
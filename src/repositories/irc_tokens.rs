@@ -0,0 +1,10 @@
+use crate::common::context::{Context, PoolContext};
+
+/// Fetches the bcrypt hash of the user's IRC password.
+pub async fn fetch_token_hash<C: Context>(ctx: &C, user_id: i64) -> sqlx::Result<String> {
+    const QUERY: &str = "SELECT token_hash FROM irc_tokens WHERE user_id = ?";
+    sqlx::query_scalar(QUERY)
+        .bind(user_id)
+        .fetch_one(ctx.db())
+        .await
+}
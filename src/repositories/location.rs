@@ -0,0 +1,31 @@
+use crate::common::context::Context;
+use crate::common::redis_json::Json;
+use crate::models::location::IpLocation;
+use redis::AsyncCommands;
+use std::net::IpAddr;
+
+fn make_key(ip_address: IpAddr) -> String {
+    format!("akatsuki:bancho:locations:{ip_address}")
+}
+
+pub async fn fetch_one<C: Context>(
+    ctx: &C,
+    ip_address: IpAddr,
+) -> anyhow::Result<Option<IpLocation>> {
+    let mut redis = ctx.redis().await?;
+    let location: Option<Json<IpLocation>> = redis.get(make_key(ip_address)).await?;
+    Ok(location.map(Json::into_inner))
+}
+
+pub async fn create<C: Context>(
+    ctx: &C,
+    ip_address: IpAddr,
+    location: &IpLocation,
+    ttl_seconds: u64,
+) -> anyhow::Result<()> {
+    let mut redis = ctx.redis().await?;
+    let _: () = redis
+        .set_ex(make_key(ip_address), Json(location), ttl_seconds)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use crate::common::context::Context;
+use redis::AsyncCommands;
+use std::ops::DerefMut;
+
+const LOCKOUTS_TTL_SECONDS: i64 = 24 * 60 * 60;
+
+fn make_failures_key(scope: &str) -> String {
+    format!("akatsuki:bancho:login_attempts:failures:{scope}")
+}
+
+fn make_lockouts_key(scope: &str) -> String {
+    format!("akatsuki:bancho:login_attempts:lockouts:{scope}")
+}
+
+fn make_lock_key(scope: &str) -> String {
+    format!("akatsuki:bancho:login_attempts:locked:{scope}")
+}
+
+/// Increments the failed attempt counter, which expires `window_seconds` after the last failure.
+pub async fn increment_failures<C: Context>(
+    ctx: &C,
+    scope: &str,
+    window_seconds: i64,
+) -> anyhow::Result<u32> {
+    let mut redis = ctx.redis().await?;
+    let key = make_failures_key(scope);
+    let (failures,): (u32,) = redis::pipe()
+        .atomic()
+        .incr(&key, 1)
+        .expire(&key, window_seconds)
+        .ignore()
+        .query_async(redis.deref_mut())
+        .await?;
+    Ok(failures)
+}
+
+pub async fn clear_failures<C: Context>(ctx: &C, scope: &str) -> anyhow::Result<()> {
+    let mut redis = ctx.redis().await?;
+    let _: () = redis.del(make_failures_key(scope)).await?;
+    Ok(())
+}
+
+/// Increments the lockout counter, which expires a day after the last lockout.
+pub async fn increment_lockouts<C: Context>(ctx: &C, scope: &str) -> anyhow::Result<u32> {
+    let mut redis = ctx.redis().await?;
+    let key = make_lockouts_key(scope);
+    let (lockouts,): (u32,) = redis::pipe()
+        .atomic()
+        .incr(&key, 1)
+        .expire(&key, LOCKOUTS_TTL_SECONDS)
+        .ignore()
+        .query_async(redis.deref_mut())
+        .await?;
+    Ok(lockouts)
+}
+
+/// Locks the scope out and resets its failed attempts.
+pub async fn lock<C: Context>(ctx: &C, scope: &str, lock_seconds: u64) -> anyhow::Result<()> {
+    let mut redis = ctx.redis().await?;
+    redis::pipe()
+        .atomic()
+        .set_ex(make_lock_key(scope), true, lock_seconds)
+        .ignore()
+        .del(make_failures_key(scope))
+        .ignore()
+        .exec_async(redis.deref_mut())
+        .await?;
+    Ok(())
+}
+
+/// Returns the remaining seconds of the lockout, if the scope is locked out.
+pub async fn fetch_lock_ttl<C: Context>(ctx: &C, scope: &str) -> anyhow::Result<Option<u64>> {
+    let mut redis = ctx.redis().await?;
+    let ttl: i64 = redis.ttl(make_lock_key(scope)).await?;
+    Ok(u64::try_from(ttl).ok())
+}
+
+/// Returns whether the scope has failed attempts or lockouts which haven't expired yet.
+pub async fn has_failures<C: Context>(ctx: &C, scope: &str) -> anyhow::Result<bool> {
+    let mut redis = ctx.redis().await?;
+    let keys = [make_failures_key(scope), make_lockouts_key(scope)];
+    let existing: u32 = redis.exists(&keys).await?;
+    Ok(existing != 0)
+}
+
+/// Lifts the lockout and forgets all failed attempts and previous lockouts,
+/// returning whether there was anything to forget.
+pub async fn unlock<C: Context>(ctx: &C, scope: &str) -> anyhow::Result<bool> {
+    let mut redis = ctx.redis().await?;
+    let keys = [
+        make_lock_key(scope),
+        make_failures_key(scope),
+        make_lockouts_key(scope),
+    ];
+    let removed: u32 = redis.del(&keys).await?;
+    Ok(removed != 0)
+}
@@ -0,0 +1,59 @@
+use crate::common::context::{Context, PoolContext};
+use crate::entities::login_notices::LoginNotice;
+use chrono::{DateTime, Utc};
+
+const TABLE_NAME: &str = "bancho_login_notices";
+const READ_FIELDS: &str =
+    "id, message, target, delivery, starts_at, ends_at, created_by, created_at";
+
+pub async fn fetch_all<C: Context>(ctx: &C) -> sqlx::Result<Vec<LoginNotice>> {
+    const QUERY: &str =
+        const_str::concat!("SELECT ", READ_FIELDS, " FROM ", TABLE_NAME, " ORDER BY id");
+    sqlx::query_as(QUERY).fetch_all(ctx.db()).await
+}
+
+/// Fetches the notices which have started and not yet ended.
+pub async fn fetch_active<C: Context>(ctx: &C) -> sqlx::Result<Vec<LoginNotice>> {
+    const QUERY: &str = const_str::concat!(
+        "SELECT ",
+        READ_FIELDS,
+        " FROM ",
+        TABLE_NAME,
+        " WHERE (starts_at IS NULL OR starts_at <= NOW())",
+        " AND (ends_at IS NULL OR ends_at > NOW()) ORDER BY id"
+    );
+    sqlx::query_as(QUERY).fetch_all(ctx.db()).await
+}
+
+pub async fn create<C: Context>(
+    ctx: &C,
+    message: &str,
+    target: &str,
+    delivery: &str,
+    starts_at: Option<DateTime<Utc>>,
+    ends_at: Option<DateTime<Utc>>,
+    created_by: i64,
+) -> sqlx::Result<u64> {
+    const QUERY: &str = const_str::concat!(
+        "INSERT INTO ",
+        TABLE_NAME,
+        " (message, target, delivery, starts_at, ends_at, created_by) ",
+        "VALUES (?, ?, ?, ?, ?, ?)"
+    );
+    let query_result = sqlx::query(QUERY)
+        .bind(message)
+        .bind(target)
+        .bind(delivery)
+        .bind(starts_at)
+        .bind(ends_at)
+        .bind(created_by)
+        .execute(ctx.db())
+        .await?;
+    Ok(query_result.last_insert_id())
+}
+
+pub async fn delete<C: Context>(ctx: &C, notice_id: i64) -> sqlx::Result<bool> {
+    const QUERY: &str = const_str::concat!("DELETE FROM ", TABLE_NAME, " WHERE id = ?");
+    let query_result = sqlx::query(QUERY).bind(notice_id).execute(ctx.db()).await?;
+    Ok(query_result.rows_affected() != 0)
+}
@@ -0,0 +1,24 @@
+use crate::common::context::Context;
+use redis::{AsyncCommands, ExistenceCheck, SetExpiry, SetOptions};
+
+fn make_step_key(starts_at: i64, step: &str) -> String {
+    format!("akatsuki:bancho:maintenance:{starts_at}:{step}")
+}
+
+/// Returns true only for the first caller claiming the step of the maintenance window
+/// starting at `starts_at`, the claim is kept for `ttl_seconds`.
+pub async fn claim_step<C: Context>(
+    ctx: &C,
+    starts_at: i64,
+    step: &str,
+    ttl_seconds: u64,
+) -> anyhow::Result<bool> {
+    let mut redis = ctx.redis().await?;
+    let opts = SetOptions::default()
+        .conditional_set(ExistenceCheck::NX)
+        .with_expiration(SetExpiry::EX(ttl_seconds));
+    let result: Option<String> = redis
+        .set_options(make_step_key(starts_at, step), true, opts)
+        .await?;
+    Ok(result.is_some())
+}
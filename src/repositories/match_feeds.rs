@@ -0,0 +1,80 @@
+use crate::common::context::Context;
+use redis::AsyncCommands;
+use redis::aio::MultiplexedConnection;
+use redis::streams::{StreamMaxlen, StreamRangeReply, StreamReadOptions, StreamReadReply};
+use std::time::Duration;
+
+/// Only the most recent events are kept, overlays joining late fetch the match state instead.
+const FEED_MAX_LENGTH: usize = 256;
+/// Keeps the feed around for a little while after the match is disbanded,
+/// so connected viewers still receive the final event.
+const FEED_DISBAND_EXPIRY_SECONDS: i64 = 60;
+
+fn make_key(match_id: i64) -> String {
+    format!("akatsuki:bancho:multiplayer:{match_id}:feed")
+}
+
+pub struct MatchFeedEntry {
+    pub event_id: String,
+    pub event: String,
+}
+
+pub async fn publish<C: Context>(ctx: &C, match_id: i64, event: &str) -> anyhow::Result<()> {
+    let mut redis = ctx.redis().await?;
+    let key = make_key(match_id);
+    let _: () = redis
+        .xadd_maxlen(
+            key,
+            StreamMaxlen::Approx(FEED_MAX_LENGTH),
+            "*",
+            &[("event", event)],
+        )
+        .await?;
+    Ok(())
+}
+
+pub async fn expire<C: Context>(ctx: &C, match_id: i64) -> anyhow::Result<()> {
+    let mut redis = ctx.redis().await?;
+    let key = make_key(match_id);
+    let _: () = redis.expire(key, FEED_DISBAND_EXPIRY_SECONDS).await?;
+    Ok(())
+}
+
+pub async fn fetch_latest_event_id<C: Context>(ctx: &C, match_id: i64) -> anyhow::Result<String> {
+    let mut redis = ctx.redis().await?;
+    let key = make_key(match_id);
+    let event_ids: StreamRangeReply = redis.xrevrange_count(key, "+", "-", 1).await?;
+    match event_ids.ids.first() {
+        Some(event_id) => Ok(event_id.id.clone()),
+        None => Ok("0-0".to_string()),
+    }
+}
+
+/// Blocks on the given connection until an event newer than `last_event_id`
+/// is published or the timeout elapses.
+/// Use a dedicated connection, a blocked connection can't serve other commands.
+pub async fn wait_for_events(
+    redis: &mut MultiplexedConnection,
+    match_id: i64,
+    last_event_id: &str,
+    timeout: Duration,
+) -> anyhow::Result<Vec<MatchFeedEntry>> {
+    let key = make_key(match_id);
+    let options = StreamReadOptions::default().block(timeout.as_millis() as _);
+    let reply: Option<StreamReadReply> = redis
+        .xread_options(&[key], &[last_event_id], &options)
+        .await?;
+    let entries = reply
+        .into_iter()
+        .flat_map(|reply| reply.keys)
+        .flat_map(|stream| stream.ids)
+        .filter_map(|entry| {
+            let event = entry.get("event")?;
+            Some(MatchFeedEntry {
+                event_id: entry.id,
+                event,
+            })
+        })
+        .collect();
+    Ok(entries)
+}
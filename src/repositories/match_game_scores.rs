@@ -0,0 +1,57 @@
+use crate::common::context::{Context, PoolContext};
+use crate::entities::match_game_scores::MatchGameScore;
+
+const TABLE_NAME: &str = "match_game_scores";
+const READ_FIELDS: &str = r#"game_id, user_id, slot_id, team, mods, score, max_combo,
+count_300, count_100, count_50, count_geki, count_katu, count_miss, perfect, passed"#;
+
+/// Stores the result of a player, a player who failed and then completed
+/// the map replaces their previous result.
+pub async fn save<C: Context>(ctx: &C, score: &MatchGameScore) -> sqlx::Result<()> {
+    const QUERY: &str = const_str::concat!(
+        "INSERT INTO ",
+        TABLE_NAME,
+        " (",
+        READ_FIELDS,
+        ") VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) ",
+        "ON DUPLICATE KEY UPDATE slot_id = VALUES(slot_id), team = VALUES(team), ",
+        "mods = VALUES(mods), score = VALUES(score), max_combo = VALUES(max_combo), ",
+        "count_300 = VALUES(count_300), count_100 = VALUES(count_100), ",
+        "count_50 = VALUES(count_50), count_geki = VALUES(count_geki), ",
+        "count_katu = VALUES(count_katu), count_miss = VALUES(count_miss), ",
+        "perfect = VALUES(perfect), passed = VALUES(passed)",
+    );
+    sqlx::query(QUERY)
+        .bind(score.game_id)
+        .bind(score.user_id)
+        .bind(score.slot_id)
+        .bind(score.team)
+        .bind(score.mods)
+        .bind(score.score)
+        .bind(score.max_combo)
+        .bind(score.count_300)
+        .bind(score.count_100)
+        .bind(score.count_50)
+        .bind(score.count_geki)
+        .bind(score.count_katu)
+        .bind(score.count_miss)
+        .bind(score.perfect)
+        .bind(score.passed)
+        .execute(ctx.db())
+        .await?;
+    Ok(())
+}
+
+pub async fn fetch_all<C: Context>(ctx: &C, game_id: i64) -> sqlx::Result<Vec<MatchGameScore>> {
+    const QUERY: &str = const_str::concat!(
+        "SELECT ",
+        READ_FIELDS,
+        " FROM ",
+        TABLE_NAME,
+        " WHERE game_id = ? ORDER BY slot_id"
+    );
+    sqlx::query_as(QUERY)
+        .bind(game_id)
+        .fetch_all(ctx.db())
+        .await
+}
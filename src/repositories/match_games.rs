@@ -31,3 +31,19 @@ pub async fn game_ended<C: Context>(ctx: &C, match_id: i64) -> sqlx::Result<()>
     sqlx::query(QUERY).bind(match_id).execute(ctx.db()).await?;
     Ok(())
 }
+
+pub async fn set_winner<C: Context>(
+    ctx: &C,
+    game_id: i64,
+    winning_team: Option<u8>,
+    winning_user_id: Option<i64>,
+) -> sqlx::Result<()> {
+    const QUERY: &str = "UPDATE match_games SET winning_team = ?, winning_user_id = ? WHERE id = ?";
+    sqlx::query(QUERY)
+        .bind(winning_team)
+        .bind(winning_user_id)
+        .bind(game_id)
+        .execute(ctx.db())
+        .await?;
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use crate::common::context::{Context, PoolContext};
 use crate::entities::channels::ChannelName;
 use crate::entities::messages::Message;
+use crate::models::privileges::Privileges;
 use chrono::Utc;
 
 /*pub async fn fetch_history<C: Context>(
@@ -48,6 +49,31 @@ pub async fn fetch_unread_messages<C: Context>(
         .await
 }
 
+/// Returns the most recent messages sent to the channel by publicly visible users, newest first.
+pub async fn fetch_channel_history<C: Context>(
+    ctx: &C,
+    channel_name: ChannelName<'_>,
+    excluded_prefix: &str,
+    limit: usize,
+) -> sqlx::Result<Vec<Message>> {
+    const QUERY: &str = const_str::concat!(
+        "SELECT m.id, m.sender_id, m.recipient_id, m.recipient_channel,",
+        "m.content, m.read_at, m.created_at, m.deleted_at, users.username as sender_name ",
+        "FROM messages m INNER JOIN users ON sender_id = users.id ",
+        "WHERE recipient_channel = ? AND deleted_at IS NULL AND (users.privileges & ?) != 0 ",
+        "AND LEFT(m.content, CHAR_LENGTH(?)) != ? ",
+        "ORDER BY m.id DESC LIMIT ?"
+    );
+    sqlx::query_as(QUERY)
+        .bind(channel_name.to_string())
+        .bind(Privileges::PubliclyVisible.bits())
+        .bind(excluded_prefix)
+        .bind(excluded_prefix)
+        .bind(limit as u64)
+        .fetch_all(ctx.db())
+        .await
+}
+
 pub async fn mark_all_read<C: Context>(ctx: &C, recipient_id: i64) -> sqlx::Result<()> {
     const QUERY: &str = const_str::concat!(
         "UPDATE messages SET read_at = CURRENT_TIMESTAMP ",
@@ -101,23 +127,6 @@ pub async fn send<C: Context>(
     })
 }
 
-pub async fn message_count<C: Context>(
-    ctx: &C,
-    sender_id: i64,
-    delta_seconds: u64,
-) -> sqlx::Result<i64> {
-    const QUERY: &str = const_str::concat!(
-        "SELECT COUNT(*) FROM messages ",
-        "WHERE sender_id = ? AND created_at > (CURRENT_TIMESTAMP - ?)"
-    );
-    let msg_count = sqlx::query_scalar(QUERY)
-        .bind(sender_id)
-        .bind(delta_seconds)
-        .fetch_one(ctx.db())
-        .await?;
-    Ok(msg_count)
-}
-
 pub async fn delete_recent<C: Context>(
     ctx: &C,
     sender_id: i64,
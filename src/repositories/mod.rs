@@ -1,14 +1,27 @@
+pub mod api_keys;
+pub mod audit_logs;
 pub mod badges;
 pub mod bancho_settings;
 pub mod beatmaps;
 pub mod channels;
+pub mod chat_filters;
+pub mod client_builds;
 pub mod hardware_logs;
 pub mod ip_logs;
+pub mod irc_tokens;
+pub mod location;
+pub mod login_attempts;
+pub mod login_notices;
+pub mod maintenance;
 pub mod match_events;
+pub mod match_feeds;
+pub mod match_game_scores;
 pub mod match_games;
 pub mod messages;
 pub mod multiplayer;
 pub mod presences;
+pub mod punishments;
+pub mod rate_limits;
 pub mod relationships;
 pub mod scores;
 pub mod sessions;
@@ -16,5 +29,6 @@ pub mod spectators;
 pub mod stats;
 pub mod streams;
 pub mod tillerino;
+pub mod tournaments;
 pub mod user_reports;
 pub mod users;
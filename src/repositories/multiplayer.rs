@@ -1,10 +1,13 @@
 use crate::common::context::{Context, PoolContext};
 use crate::common::redis_json::Json;
-use crate::entities::multiplayer::{MultiplayerMatch, MultiplayerMatchSlot};
+use crate::entities::match_game_scores::MatchScoreFrame;
+use crate::entities::multiplayer::{MapConstraints, MultiplayerMatch, MultiplayerMatchSlot};
 use crate::entities::sessions::SessionIdentity;
 use bancho_protocol::structures::SlotStatus;
-use redis::AsyncCommands;
+use chrono::{DateTime, Utc};
+use redis::{AsyncCommands, Script};
 use std::ops::DerefMut;
+use std::sync::LazyLock;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -13,8 +16,35 @@ pub enum TimerType {
     MatchStart,
 }
 
+impl TimerType {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            TimerType::Regular => "regular",
+            TimerType::MatchStart => "match_start",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "regular" => Some(TimerType::Regular),
+            "match_start" => Some(TimerType::MatchStart),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MultiplayerTimer {
+    pub match_id: i64,
+    pub timer_type: TimerType,
+    pub deadline: DateTime<Utc>,
+}
+
 const KEY: &str = "akatsuki:bancho:multiplayer";
+const TIMERS_KEY: &str = "akatsuki:bancho:multiplayer:timers";
 const SESSIONS_MATCHES_KEY: &str = "akatsuki:bancho:sessions:multiplayer";
+const AUTO_HOST_KEY: &str = "akatsuki:bancho:multiplayer:auto_host";
+const CONSTRAINTS_KEY: &str = "akatsuki:bancho:multiplayer:constraints";
 pub const MULTIPLAYER_MAX_SIZE: usize = 16;
 
 fn make_referees_key(match_id: i64) -> String {
@@ -25,6 +55,14 @@ fn make_slots_key(match_id: i64) -> String {
     format!("akatsuki:bancho:multiplayer:{match_id}")
 }
 
+fn make_host_queue_key(match_id: i64) -> String {
+    format!("akatsuki:bancho:multiplayer:host_queue:{match_id}")
+}
+
+fn make_score_frames_key(match_id: i64) -> String {
+    format!("akatsuki:bancho:multiplayer:score_frames:{match_id}")
+}
+
 fn make_timer_key(match_id: i64, timer_type: TimerType) -> String {
     match timer_type {
         TimerType::Regular => format!("akatsuki:bancho:multiplayer:timer:{match_id}"),
@@ -32,6 +70,17 @@ fn make_timer_key(match_id: i64, timer_type: TimerType) -> String {
     }
 }
 
+fn make_timer_member(match_id: i64, timer_type: TimerType) -> String {
+    format!("{match_id}:{}", timer_type.as_str())
+}
+
+fn parse_timer_member(member: &str) -> Option<(i64, TimerType)> {
+    let (match_id, timer_type) = member.split_once(':')?;
+    let match_id = match_id.parse().ok()?;
+    let timer_type = TimerType::from_name(timer_type)?;
+    Some((match_id, timer_type))
+}
+
 pub async fn create<C: Context>(
     ctx: &C,
     host_identity: SessionIdentity,
@@ -101,16 +150,34 @@ pub async fn delete<C: Context>(ctx: &C, match_id: i64) -> anyhow::Result<()> {
     let referees_key = make_referees_key(match_id);
     let timer_key = make_timer_key(match_id, TimerType::Regular);
     let start_timer_key = make_timer_key(match_id, TimerType::MatchStart);
+    let score_frames_key = make_score_frames_key(match_id);
+    let host_queue_key = make_host_queue_key(match_id);
     redis::pipe()
         .atomic()
         .del(slots_key)
         .ignore()
+        .del(score_frames_key)
+        .ignore()
+        .del(host_queue_key)
+        .ignore()
+        .srem(AUTO_HOST_KEY, match_id)
+        .ignore()
+        .hdel(CONSTRAINTS_KEY, match_id)
+        .ignore()
         .del(referees_key)
         .ignore()
         .del(timer_key)
         .ignore()
         .del(start_timer_key)
         .ignore()
+        .zrem(
+            TIMERS_KEY,
+            &[
+                make_timer_member(match_id, TimerType::Regular),
+                make_timer_member(match_id, TimerType::MatchStart),
+            ],
+        )
+        .ignore()
         .hdel(KEY, match_id)
         .ignore()
         .exec_async(redis.deref_mut())
@@ -212,6 +279,11 @@ pub async fn fetch_all<C: Context>(
     Ok(matches.into_iter().map(Json::into_inner))
 }
 
+pub async fn fetch_count<C: Context>(ctx: &C) -> anyhow::Result<u64> {
+    let mut redis = ctx.redis().await?;
+    Ok(redis.hlen(KEY).await?)
+}
+
 pub async fn fetch_slot<C: Context>(
     ctx: &C,
     match_id: i64,
@@ -278,6 +350,36 @@ pub async fn update_slot<C: Context>(
     Ok(())
 }
 
+pub async fn set_score_frame<C: Context>(
+    ctx: &C,
+    match_id: i64,
+    slot_id: usize,
+    frame: MatchScoreFrame,
+) -> anyhow::Result<()> {
+    let mut redis = ctx.redis().await?;
+    let score_frames_key = make_score_frames_key(match_id);
+    let _: () = redis.hset(score_frames_key, slot_id, Json(frame)).await?;
+    Ok(())
+}
+
+pub async fn fetch_score_frame<C: Context>(
+    ctx: &C,
+    match_id: i64,
+    slot_id: usize,
+) -> anyhow::Result<Option<MatchScoreFrame>> {
+    let mut redis = ctx.redis().await?;
+    let score_frames_key = make_score_frames_key(match_id);
+    let frame: Option<Json<MatchScoreFrame>> = redis.hget(score_frames_key, slot_id).await?;
+    Ok(frame.map(Json::into_inner))
+}
+
+pub async fn clear_score_frames<C: Context>(ctx: &C, match_id: i64) -> anyhow::Result<()> {
+    let mut redis = ctx.redis().await?;
+    let score_frames_key = make_score_frames_key(match_id);
+    let _: () = redis.del(score_frames_key).await?;
+    Ok(())
+}
+
 pub async fn update_slots<const N: usize, C: Context>(
     ctx: &C,
     match_id: i64,
@@ -346,15 +448,65 @@ pub async fn clear_referees<C: Context>(ctx: &C, match_id: i64) -> anyhow::Resul
 
 // Timers
 
+/// Only marks an announcement as sent if the timer still runs with the same deadline,
+/// so a late claim cannot resurrect an aborted timer or announce for a restarted one.
+static CLAIM_ANNOUNCEMENT_SCRIPT: LazyLock<Script> = LazyLock::new(|| {
+    Script::new(
+        r"
+        if tonumber(redis.call('HGET', KEYS[1], 'deadline')) ~= tonumber(ARGV[2]) then
+            return 0
+        end
+        return redis.call('HSETNX', KEYS[1], ARGV[1], 1)
+        ",
+    )
+});
+
+/// Only removes the timer if it still runs with the same deadline,
+/// so a timer restarted in the meantime is not expired early.
+static CLAIM_EXPIRY_SCRIPT: LazyLock<Script> = LazyLock::new(|| {
+    Script::new(
+        r"
+        if tonumber(redis.call('ZSCORE', KEYS[1], ARGV[1])) ~= tonumber(ARGV[2]) then
+            return 0
+        end
+        redis.call('ZREM', KEYS[1], ARGV[1])
+        redis.call('DEL', KEYS[2])
+        return 1
+        ",
+    )
+});
+
+fn make_announcement_field(remaining_seconds: i64) -> String {
+    format!("announced:{remaining_seconds}")
+}
+
 pub async fn set_timer<C: Context>(
     ctx: &C,
     match_id: i64,
     timer_type: TimerType,
-    seconds: u64,
+    deadline: DateTime<Utc>,
+    duration_seconds: i64,
 ) -> anyhow::Result<()> {
     let mut redis = ctx.redis().await?;
     let timer_key = make_timer_key(match_id, timer_type);
-    let _: () = redis.set(timer_key, seconds).await?;
+    let deadline = deadline.timestamp_millis();
+    redis::pipe()
+        .atomic()
+        .del(&timer_key)
+        .ignore()
+        .hset(&timer_key, "deadline", deadline)
+        .ignore()
+        // the starting duration is already part of the message starting the timer
+        .hset(&timer_key, make_announcement_field(duration_seconds), 1)
+        .ignore()
+        .zadd(
+            TIMERS_KEY,
+            make_timer_member(match_id, timer_type),
+            deadline,
+        )
+        .ignore()
+        .exec_async(redis.deref_mut())
+        .await?;
     Ok(())
 }
 
@@ -362,22 +514,64 @@ pub async fn get_timer<C: Context>(
     ctx: &C,
     match_id: i64,
     timer_type: TimerType,
-) -> anyhow::Result<Option<i64>> {
+) -> anyhow::Result<Option<DateTime<Utc>>> {
     let mut redis = ctx.redis().await?;
-    let timer_key = make_timer_key(match_id, timer_type);
-    let remaining_seconds = redis.get(timer_key).await?;
-    Ok(remaining_seconds)
+    let member = make_timer_member(match_id, timer_type);
+    let deadline: Option<f64> = redis.zscore(TIMERS_KEY, member).await?;
+    Ok(deadline.and_then(|deadline| DateTime::from_timestamp_millis(deadline as i64)))
+}
+
+pub async fn fetch_all_timers<C: Context>(ctx: &C) -> anyhow::Result<Vec<MultiplayerTimer>> {
+    let mut redis = ctx.redis().await?;
+    let members: Vec<(String, f64)> = redis.zrange_withscores(TIMERS_KEY, 0, -1).await?;
+    let timers = members
+        .into_iter()
+        .filter_map(|(member, deadline)| {
+            let (match_id, timer_type) = parse_timer_member(&member)?;
+            let deadline = DateTime::from_timestamp_millis(deadline as i64)?;
+            Some(MultiplayerTimer {
+                match_id,
+                timer_type,
+                deadline,
+            })
+        })
+        .collect();
+    Ok(timers)
 }
 
-pub async fn decrease_timer<C: Context>(
+/// Returns true only for the first caller claiming the announcement
+/// of `remaining_seconds` for the timer, as long as it is still running.
+pub async fn claim_timer_announcement<C: Context>(
     ctx: &C,
-    match_id: i64,
-    timer_type: TimerType,
-) -> anyhow::Result<i64> {
+    timer: MultiplayerTimer,
+    remaining_seconds: i64,
+) -> anyhow::Result<bool> {
     let mut redis = ctx.redis().await?;
-    let timer_key = make_timer_key(match_id, timer_type);
-    let remaining_seconds = redis.decr(timer_key, 1).await?;
-    Ok(remaining_seconds)
+    let timer_key = make_timer_key(timer.match_id, timer.timer_type);
+    let claimed: i32 = CLAIM_ANNOUNCEMENT_SCRIPT
+        .key(timer_key)
+        .arg(make_announcement_field(remaining_seconds))
+        .arg(timer.deadline.timestamp_millis())
+        .invoke_async(redis.deref_mut())
+        .await?;
+    Ok(claimed == 1)
+}
+
+/// Removes the timer, returning true only for the caller that actually removed it.
+/// Nothing is removed if the timer has been restarted with another deadline.
+pub async fn claim_timer_expiry<C: Context>(
+    ctx: &C,
+    timer: MultiplayerTimer,
+) -> anyhow::Result<bool> {
+    let mut redis = ctx.redis().await?;
+    let removed: i32 = CLAIM_EXPIRY_SCRIPT
+        .key(TIMERS_KEY)
+        .key(make_timer_key(timer.match_id, timer.timer_type))
+        .arg(make_timer_member(timer.match_id, timer.timer_type))
+        .arg(timer.deadline.timestamp_millis())
+        .invoke_async(redis.deref_mut())
+        .await?;
+    Ok(removed == 1)
 }
 
 pub async fn abort_timer<C: Context>(
@@ -387,7 +581,14 @@ pub async fn abort_timer<C: Context>(
 ) -> anyhow::Result<()> {
     let mut redis = ctx.redis().await?;
     let timer_key = make_timer_key(match_id, timer_type);
-    let _: () = redis.del(timer_key).await?;
+    redis::pipe()
+        .atomic()
+        .zrem(TIMERS_KEY, make_timer_member(match_id, timer_type))
+        .ignore()
+        .del(timer_key)
+        .ignore()
+        .exec_async(redis.deref_mut())
+        .await?;
     Ok(())
 }
 
@@ -405,3 +606,131 @@ fn slots_from_json_with_index(
 ) -> [MultiplayerMatchSlot; MULTIPLAYER_MAX_SIZE] {
     std::array::from_fn(|i| json[i].1.0)
 }
+
+static ENQUEUE_HOST_SCRIPT: LazyLock<Script> = LazyLock::new(|| {
+    Script::new(
+        r"
+        if redis.call('SISMEMBER', KEYS[1], ARGV[1]) == 0 then
+            return 0
+        end
+        redis.call('LREM', KEYS[2], 0, ARGV[2])
+        return redis.call('RPUSH', KEYS[2], ARGV[2])
+        ",
+    )
+});
+
+static ROTATE_HOST_QUEUE_SCRIPT: LazyLock<Script> = LazyLock::new(|| {
+    Script::new(
+        r"
+        local user_id = redis.call('LPOP', KEYS[1])
+        if user_id then
+            redis.call('RPUSH', KEYS[1], user_id)
+        end
+        return redis.call('LINDEX', KEYS[1], 0)
+        ",
+    )
+});
+
+pub async fn is_auto_host<C: Context>(ctx: &C, match_id: i64) -> anyhow::Result<bool> {
+    let mut redis = ctx.redis().await?;
+    Ok(redis.sismember(AUTO_HOST_KEY, match_id).await?)
+}
+
+/// Enables auto host rotation, the first user of the queue is the current host.
+pub async fn enable_auto_host<C: Context>(
+    ctx: &C,
+    match_id: i64,
+    host_queue: &[i64],
+) -> anyhow::Result<()> {
+    let mut redis = ctx.redis().await?;
+    let host_queue_key = make_host_queue_key(match_id);
+    let mut pipe = redis::pipe();
+    pipe.atomic()
+        .sadd(AUTO_HOST_KEY, match_id)
+        .ignore()
+        .del(&host_queue_key)
+        .ignore();
+    if !host_queue.is_empty() {
+        pipe.rpush(&host_queue_key, host_queue).ignore();
+    }
+    pipe.exec_async(redis.deref_mut()).await?;
+    Ok(())
+}
+
+pub async fn disable_auto_host<C: Context>(ctx: &C, match_id: i64) -> anyhow::Result<()> {
+    let mut redis = ctx.redis().await?;
+    let host_queue_key = make_host_queue_key(match_id);
+    redis::pipe()
+        .atomic()
+        .srem(AUTO_HOST_KEY, match_id)
+        .ignore()
+        .del(host_queue_key)
+        .ignore()
+        .exec_async(redis.deref_mut())
+        .await?;
+    Ok(())
+}
+
+pub async fn fetch_host_queue<C: Context>(ctx: &C, match_id: i64) -> anyhow::Result<Vec<i64>> {
+    let mut redis = ctx.redis().await?;
+    let host_queue_key = make_host_queue_key(match_id);
+    Ok(redis.lrange(host_queue_key, 0, -1).await?)
+}
+
+/// Appends the user to the host queue, if auto host is enabled for the match.
+pub async fn enqueue_host<C: Context>(ctx: &C, match_id: i64, user_id: i64) -> anyhow::Result<()> {
+    let mut redis = ctx.redis().await?;
+    let host_queue_key = make_host_queue_key(match_id);
+    let _: i64 = ENQUEUE_HOST_SCRIPT
+        .key(AUTO_HOST_KEY)
+        .key(host_queue_key)
+        .arg(match_id)
+        .arg(user_id)
+        .invoke_async(redis.deref_mut())
+        .await?;
+    Ok(())
+}
+
+pub async fn dequeue_host<C: Context>(ctx: &C, match_id: i64, user_id: i64) -> anyhow::Result<()> {
+    let mut redis = ctx.redis().await?;
+    let host_queue_key = make_host_queue_key(match_id);
+    let _: () = redis.lrem(host_queue_key, 0, user_id).await?;
+    Ok(())
+}
+
+/// Moves the first user of the host queue to its end and returns the new first user.
+pub async fn rotate_host_queue<C: Context>(ctx: &C, match_id: i64) -> anyhow::Result<Option<i64>> {
+    let mut redis = ctx.redis().await?;
+    let host_queue_key = make_host_queue_key(match_id);
+    let next_host: Option<i64> = ROTATE_HOST_QUEUE_SCRIPT
+        .key(host_queue_key)
+        .invoke_async(redis.deref_mut())
+        .await?;
+    Ok(next_host)
+}
+
+pub async fn fetch_constraints<C: Context>(
+    ctx: &C,
+    match_id: i64,
+) -> anyhow::Result<Option<MapConstraints>> {
+    let mut redis = ctx.redis().await?;
+    let constraints: Option<Json<MapConstraints>> = redis.hget(CONSTRAINTS_KEY, match_id).await?;
+    Ok(constraints.map(Json::into_inner))
+}
+
+pub async fn set_constraints<C: Context>(
+    ctx: &C,
+    match_id: i64,
+    constraints: &MapConstraints,
+) -> anyhow::Result<()> {
+    let mut redis = ctx.redis().await?;
+    let _: () = match constraints.is_empty() {
+        true => redis.hdel(CONSTRAINTS_KEY, match_id).await?,
+        false => {
+            redis
+                .hset(CONSTRAINTS_KEY, match_id, Json(constraints))
+                .await?
+        }
+    };
+    Ok(())
+}
@@ -94,6 +94,11 @@ pub async fn fetch_user_ids<C: Context>(ctx: &C) -> anyhow::Result<Vec<i32>> {
     Ok(user_ids)
 }
 
+pub async fn fetch_count<C: Context>(ctx: &C) -> anyhow::Result<u64> {
+    let mut redis = ctx.redis().await?;
+    Ok(redis.hlen(KEY).await?)
+}
+
 pub async fn fetch_all<C: Context>(ctx: &C) -> anyhow::Result<impl Iterator<Item = Presence>> {
     let mut redis = ctx.redis().await?;
     let mut presences: Vec<Json<Presence>> = redis.hvals(KEY).await?;
@@ -0,0 +1,86 @@
+use crate::common::context::Context;
+use crate::entities::punishments::Punishment;
+use chrono::{DateTime, Utc};
+
+const TABLE_NAME: &str = "bancho_punishments";
+const READ_FIELDS: &str =
+    "id, user_id, kind, reason, created_by, created_at, expires_at, lifted_at";
+
+pub async fn create<C: Context>(
+    ctx: &C,
+    user_id: i64,
+    kind: i8,
+    reason: &str,
+    created_by: i64,
+    expires_at: Option<DateTime<Utc>>,
+) -> sqlx::Result<u64> {
+    const QUERY: &str = const_str::concat!(
+        "INSERT INTO ",
+        TABLE_NAME,
+        " (user_id, kind, reason, created_by, expires_at) VALUES (?, ?, ?, ?, ?)"
+    );
+    let query_result = sqlx::query(QUERY)
+        .bind(user_id)
+        .bind(kind)
+        .bind(reason)
+        .bind(created_by)
+        .bind(expires_at)
+        .execute(ctx.db())
+        .await?;
+    Ok(query_result.last_insert_id())
+}
+
+/// Marks all active punishments of the kind as lifted, returns how many were affected.
+pub async fn lift_active<C: Context>(ctx: &C, user_id: i64, kind: i8) -> sqlx::Result<u64> {
+    const QUERY: &str = const_str::concat!(
+        "UPDATE ",
+        TABLE_NAME,
+        " SET lifted_at = CURRENT_TIMESTAMP WHERE user_id = ? AND kind = ? AND lifted_at IS NULL"
+    );
+    let query_result = sqlx::query(QUERY)
+        .bind(user_id)
+        .bind(kind)
+        .execute(ctx.db())
+        .await?;
+    Ok(query_result.rows_affected())
+}
+
+pub async fn lift<C: Context>(ctx: &C, punishment_id: i64) -> sqlx::Result<()> {
+    const QUERY: &str = const_str::concat!(
+        "UPDATE ",
+        TABLE_NAME,
+        " SET lifted_at = CURRENT_TIMESTAMP WHERE id = ? AND lifted_at IS NULL"
+    );
+    sqlx::query(QUERY)
+        .bind(punishment_id)
+        .execute(ctx.db())
+        .await?;
+    Ok(())
+}
+
+/// Whether the user has a punishment of the kind that is neither lifted nor expired.
+pub async fn has_active<C: Context>(ctx: &C, user_id: i64, kind: i8) -> sqlx::Result<bool> {
+    const QUERY: &str = const_str::concat!(
+        "SELECT EXISTS(SELECT 1 FROM ",
+        TABLE_NAME,
+        " WHERE user_id = ? AND kind = ? AND lifted_at IS NULL ",
+        "AND (expires_at IS NULL OR expires_at > CURRENT_TIMESTAMP)) AS is_active"
+    );
+    let is_active: bool = sqlx::query_scalar(QUERY)
+        .bind(user_id)
+        .bind(kind)
+        .fetch_one(ctx.db())
+        .await?;
+    Ok(is_active)
+}
+
+pub async fn fetch_expired<C: Context>(ctx: &C) -> sqlx::Result<Vec<Punishment>> {
+    const QUERY: &str = const_str::concat!(
+        "SELECT ",
+        READ_FIELDS,
+        " FROM ",
+        TABLE_NAME,
+        " WHERE lifted_at IS NULL AND expires_at <= CURRENT_TIMESTAMP ORDER BY id"
+    );
+    sqlx::query_as(QUERY).fetch_all(ctx.db()).await
+}
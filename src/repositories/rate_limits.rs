@@ -0,0 +1,69 @@
+use crate::common::context::Context;
+use redis::Script;
+use std::ops::DerefMut;
+use std::sync::LazyLock;
+use std::time::Duration;
+use uuid::Uuid;
+
+const OFFENCES_TTL_SECONDS: i64 = 24 * 60 * 60;
+
+fn make_key(action: &str, scope: &str) -> String {
+    format!("akatsuki:bancho:rate_limits:{action}:{scope}")
+}
+
+fn make_offences_key(user_id: i64) -> String {
+    format!("akatsuki:bancho:rate_limits:offences:{user_id}")
+}
+
+/// Sliding window over a sorted set scored by timestamp.
+/// Only records the hit if it is within the limit.
+static HIT_SCRIPT: LazyLock<Script> = LazyLock::new(|| {
+    Script::new(
+        r"
+        local now = tonumber(ARGV[1])
+        local window = tonumber(ARGV[2])
+        redis.call('ZREMRANGEBYSCORE', KEYS[1], '-inf', now - window)
+        if redis.call('ZCARD', KEYS[1]) >= tonumber(ARGV[3]) then
+            return 0
+        end
+        redis.call('ZADD', KEYS[1], now, ARGV[4])
+        redis.call('PEXPIRE', KEYS[1], window)
+        return 1
+        ",
+    )
+});
+
+/// Records a hit, returning false if the limit has already been reached within the window.
+pub async fn hit<C: Context>(
+    ctx: &C,
+    action: &str,
+    scope: &str,
+    count: u32,
+    window: Duration,
+) -> anyhow::Result<bool> {
+    let mut redis = ctx.redis().await?;
+    let now = chrono::Utc::now().timestamp_millis();
+    let allowed: i32 = HIT_SCRIPT
+        .key(make_key(action, scope))
+        .arg(now)
+        .arg(window.as_millis() as u64)
+        .arg(count)
+        .arg(Uuid::new_v4().to_string())
+        .invoke_async(redis.deref_mut())
+        .await?;
+    Ok(allowed == 1)
+}
+
+/// Increments the user's offence counter, which expires a day after the last offence.
+pub async fn increment_offences<C: Context>(ctx: &C, user_id: i64) -> anyhow::Result<u32> {
+    let mut redis = ctx.redis().await?;
+    let key = make_offences_key(user_id);
+    let (offences,): (u32,) = redis::pipe()
+        .atomic()
+        .incr(&key, 1)
+        .expire(&key, OFFENCES_TTL_SECONDS)
+        .ignore()
+        .query_async(redis.deref_mut())
+        .await?;
+    Ok(offences)
+}
@@ -27,6 +27,7 @@ pub async fn create<C: Context>(ctx: &C, args: CreateSessionArgs) -> anyhow::Res
         private_dms: args.private_dms,
         silence_end: args.silence_end,
         updated_at: chrono::Utc::now(),
+        client_version: args.client_version,
     };
     let user_id_key = make_id_key(args.user_id);
     let username_key = make_username_key(&session.username);
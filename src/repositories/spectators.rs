@@ -2,6 +2,7 @@ use crate::common::context::{Context, PoolContext};
 use crate::common::redis_json::Json;
 use crate::entities::sessions::SessionIdentity;
 use redis::AsyncCommands;
+use std::collections::HashSet;
 use std::ops::DerefMut;
 use uuid::Uuid;
 
@@ -19,6 +20,13 @@ pub async fn remove_spectating<C: Context>(ctx: &C, session_id: Uuid) -> anyhow:
     Ok(redis.hdel(SPECTATING_KEY, session_id).await?)
 }
 
+/// Counts the sessions that are being spectated by at least one other session.
+pub async fn fetch_host_count<C: Context>(ctx: &C) -> anyhow::Result<usize> {
+    let mut redis = ctx.redis().await?;
+    let host_session_ids: HashSet<Uuid> = redis.hvals(SPECTATING_KEY).await?;
+    Ok(host_session_ids.len())
+}
+
 fn make_key(host_session_id: Uuid) -> String {
     format!("akatsuki:bancho:spectator:{host_session_id}")
 }
@@ -3,11 +3,14 @@ use crate::common::error::{AppError, ServiceResult};
 use crate::entities::channels::ChannelName;
 use crate::entities::streams::{MessageInfo, StreamMessage, StreamReadMessage, StreamReadReply};
 use hashbrown::HashMap;
-use redis::AsyncCommands;
 use redis::aio::MultiplexedConnection;
-use redis::streams::{StreamRangeReply, StreamTrimOptions, StreamTrimmingMode};
+use redis::streams::{StreamRangeReply, StreamReadOptions, StreamTrimOptions, StreamTrimmingMode};
+use redis::{AsyncCommands, Script};
 use std::fmt::{Display, Formatter};
+use std::ops::DerefMut;
 use std::str::FromStr;
+use std::sync::LazyLock;
+use std::time::Duration;
 use uuid::Uuid;
 
 #[derive(Copy, Clone)]
@@ -79,6 +82,24 @@ impl<'a> StreamName<'a> {
     }
 }
 
+impl StreamName<'_> {
+    /// The type of the stream without its identifier, used as a metrics label.
+    pub const fn kind(&self) -> &'static str {
+        match self {
+            StreamName::User(_) => "user",
+            StreamName::Main => "main",
+            StreamName::Lobby => "lobby",
+            StreamName::Donator => "donator",
+            StreamName::Staff => "staff",
+            StreamName::Dev => "dev",
+            StreamName::Channel(_) => "channel",
+            StreamName::Spectator(_) => "spectator",
+            StreamName::Multiplayer(_) => "multiplayer",
+            StreamName::Multiplaying(_) => "multiplaying",
+        }
+    }
+}
+
 impl Display for StreamName<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -106,6 +127,10 @@ fn make_offsets_key<T: Display>(session_id: T) -> String {
     format!("akatsuki:bancho:sessions:{session_id}:stream_offsets")
 }
 
+fn make_push_key(session_id: Uuid) -> String {
+    format!("akatsuki:bancho:sessions:{session_id}:push")
+}
+
 pub async fn fetch_all<C: Context>(ctx: &C) -> anyhow::Result<Vec<String>> {
     let mut redis = ctx.redis().await?;
     let mut iter: redis::AsyncIter<String> = redis.scan_match(ALL_KEY).await?;
@@ -134,36 +159,18 @@ pub async fn read_pending_messages<C: Context>(
     session_id: Uuid,
 ) -> anyhow::Result<Vec<StreamReadMessage>> {
     let mut redis = ctx.redis().await?;
-    let mut offsets = get_offsets(&mut redis, session_id).await?;
-    if offsets.is_empty() {
-        return Ok(vec![]);
-    }
-
-    let streams: Vec<&String> = offsets.keys().collect();
-    let ids: Vec<&String> = offsets.values().collect();
-    let reply: Option<StreamReadReply> = redis.xread(&streams, &ids).await?;
-    match reply {
-        None => Ok(vec![]),
-        Some(reply) => {
-            let messages = reply
-                .streams
-                .into_iter()
-                .flat_map(|stream| {
-                    if let Some(last_id) = stream.messages.last() {
-                        offsets.insert(stream.stream_name, last_id.message_id.clone());
-                    } else {
-                        offsets.remove(&stream.stream_name);
-                    }
-
-                    stream.messages
-                })
-                .collect::<Vec<_>>();
+    read_messages(redis.deref_mut(), session_id, None).await
+}
 
-            // Update the users' streams offsets after reading messages from stream
-            set_offsets(&mut redis, session_id, offsets).await?;
-            Ok(messages)
-        }
-    }
+/// Blocks on the given connection until a message arrives on any of the
+/// session's streams or the timeout elapses.
+/// Use a dedicated connection, a blocked connection can't serve other commands.
+pub async fn wait_for_pending_messages(
+    redis: &mut MultiplexedConnection,
+    session_id: Uuid,
+    timeout: Duration,
+) -> anyhow::Result<Vec<StreamReadMessage>> {
+    read_messages(redis, session_id, Some(timeout)).await
 }
 
 pub async fn is_joined<C: Context>(
@@ -206,12 +213,43 @@ pub async fn remove_offsets<C: Context>(ctx: &C, session_id: Uuid) -> anyhow::Re
     Ok(redis.del(offsets_key).await?)
 }
 
+/// Marks the session as having a blocking read on its streams for `ttl_seconds`.
+pub async fn set_push_active<C: Context>(
+    ctx: &C,
+    session_id: Uuid,
+    ttl_seconds: u64,
+) -> anyhow::Result<()> {
+    let mut redis = ctx.redis().await?;
+    Ok(redis
+        .set_ex(make_push_key(session_id), true, ttl_seconds)
+        .await?)
+}
+
+pub async fn remove_push_active<C: Context>(ctx: &C, session_id: Uuid) -> anyhow::Result<()> {
+    let mut redis = ctx.redis().await?;
+    Ok(redis.del(make_push_key(session_id)).await?)
+}
+
+pub async fn is_push_active<C: Context>(ctx: &C, session_id: Uuid) -> anyhow::Result<bool> {
+    let mut redis = ctx.redis().await?;
+    Ok(redis.exists(make_push_key(session_id)).await?)
+}
+
 pub async fn clear_stream<C: Context>(ctx: &C, stream_name: StreamName<'_>) -> anyhow::Result<()> {
     let mut redis = ctx.redis().await?;
     let key = make_key(stream_name);
     Ok(redis.del(key).await?)
 }
 
+pub async fn fetch_length<C: Context>(
+    ctx: &C,
+    stream_name: StreamName<'_>,
+) -> anyhow::Result<usize> {
+    let mut redis = ctx.redis().await?;
+    let key = make_key(stream_name);
+    Ok(redis.xlen(key).await?)
+}
+
 pub async fn get_latest_message_id<C: Context>(
     ctx: &C,
     stream_name: StreamName<'_>,
@@ -242,6 +280,52 @@ pub async fn trim_messages<C: Context>(
 }
 
 // utility
+async fn read_messages(
+    redis: &mut MultiplexedConnection,
+    session_id: Uuid,
+    block: Option<Duration>,
+) -> anyhow::Result<Vec<StreamReadMessage>> {
+    let offsets = get_offsets(redis, session_id).await?;
+    if offsets.is_empty() {
+        // nothing to wait on, but blocking callers still expect to be throttled
+        if let Some(block) = block {
+            tokio::time::sleep(block).await;
+        }
+        return Ok(vec![]);
+    }
+
+    let streams: Vec<&String> = offsets.keys().collect();
+    let ids: Vec<&String> = offsets.values().collect();
+    let reply: Option<StreamReadReply> = match block {
+        Some(block) => {
+            let options = StreamReadOptions::default().block(block.as_millis() as _);
+            redis.xread_options(&streams, &ids, &options).await?
+        }
+        None => redis.xread(&streams, &ids).await?,
+    };
+    match reply {
+        None => Ok(vec![]),
+        Some(reply) => {
+            let mut read_offsets = vec![];
+            let messages = reply
+                .streams
+                .into_iter()
+                .flat_map(|stream| {
+                    if let Some(last_id) = stream.messages.last() {
+                        read_offsets.push((stream.stream_name, last_id.message_id.clone()));
+                    }
+                    stream.messages
+                })
+                .collect::<Vec<_>>();
+
+            // Only advance the streams which were read from,
+            // the session may have left others while the read was blocking
+            set_offsets(redis, session_id, read_offsets).await?;
+            Ok(messages)
+        }
+    }
+}
+
 async fn get_offsets(
     redis: &mut MultiplexedConnection,
     session_id: Uuid,
@@ -250,15 +334,34 @@ async fn get_offsets(
     Ok(redis.hgetall(offsets_key).await?)
 }
 
+/// Only updates offsets which still exist, so that streams left in the meantime
+/// aren't rejoined and the offsets of deleted sessions aren't recreated.
+static SET_OFFSETS_SCRIPT: LazyLock<Script> = LazyLock::new(|| {
+    Script::new(
+        r"
+        for i = 1, #ARGV, 2 do
+            if redis.call('HEXISTS', KEYS[1], ARGV[i]) == 1 then
+                redis.call('HSET', KEYS[1], ARGV[i], ARGV[i + 1])
+            end
+        end
+        return 0
+        ",
+    )
+});
+
 async fn set_offsets(
     redis: &mut MultiplexedConnection,
     session_id: Uuid,
-    offsets: HashMap<String, String>,
+    offsets: Vec<(String, String)>,
 ) -> anyhow::Result<()> {
-    let offsets_key = make_offsets_key(&session_id);
-    let items: Vec<(&str, &str)> = offsets
-        .iter()
-        .map(|(k, v)| (k.as_str(), v.as_str()))
-        .collect();
-    Ok(redis.hset_multiple(offsets_key, items.as_slice()).await?)
+    if offsets.is_empty() {
+        return Ok(());
+    }
+
+    let mut invocation = SET_OFFSETS_SCRIPT.key(make_offsets_key(&session_id));
+    for (stream, offset) in offsets.iter() {
+        invocation.arg(stream).arg(offset);
+    }
+    let _: i32 = invocation.invoke_async(redis).await?;
+    Ok(())
 }
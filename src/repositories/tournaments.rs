@@ -0,0 +1,69 @@
+use crate::common::context::Context;
+use crate::common::redis_json::Json;
+use crate::entities::tournaments::Tournament;
+use redis::{AsyncCommands, Script};
+use std::ops::DerefMut;
+use std::sync::LazyLock;
+
+const KEY: &str = "akatsuki:bancho:multiplayer:tournaments";
+
+/// Only stores the tournament if it is still in the state the update was based on,
+/// an empty previous state means the tournament must not exist yet.
+static REPLACE_SCRIPT: LazyLock<Script> = LazyLock::new(|| {
+    Script::new(
+        r"
+        local current = redis.call('HGET', KEYS[1], ARGV[1])
+        if ARGV[2] == '' then
+            if current then
+                return 0
+            end
+        elseif current ~= ARGV[2] then
+            return 0
+        end
+        redis.call('HSET', KEYS[1], ARGV[1], ARGV[3])
+        return 1
+        ",
+    )
+});
+
+pub async fn fetch_one<C: Context>(ctx: &C, match_id: i64) -> anyhow::Result<Option<Tournament>> {
+    let mut redis = ctx.redis().await?;
+    let tournament: Option<Json<Tournament>> = redis.hget(KEY, match_id).await?;
+    Ok(tournament.map(Json::into_inner))
+}
+
+/// Returns the tournament along with its stored form, to be passed to [`replace`].
+pub async fn fetch_for_update<C: Context>(
+    ctx: &C,
+    match_id: i64,
+) -> anyhow::Result<Option<(Tournament, String)>> {
+    let mut redis = ctx.redis().await?;
+    let stored: Option<String> = redis.hget(KEY, match_id).await?;
+    match stored {
+        Some(stored) => Ok(Some((serde_json::from_str(&stored)?, stored))),
+        None => Ok(None),
+    }
+}
+
+/// Stores the tournament, returning false if it has been changed since `previous` was fetched.
+pub async fn replace<C: Context>(
+    ctx: &C,
+    previous: Option<&str>,
+    tournament: &Tournament,
+) -> anyhow::Result<bool> {
+    let mut redis = ctx.redis().await?;
+    let replaced: i32 = REPLACE_SCRIPT
+        .key(KEY)
+        .arg(tournament.match_id)
+        .arg(previous.unwrap_or_default())
+        .arg(Json(tournament))
+        .invoke_async(redis.deref_mut())
+        .await?;
+    Ok(replaced == 1)
+}
+
+pub async fn delete<C: Context>(ctx: &C, match_id: i64) -> anyhow::Result<()> {
+    let mut redis = ctx.redis().await?;
+    let _: () = redis.hdel(KEY, match_id).await?;
+    Ok(())
+}
@@ -123,6 +123,21 @@ pub async fn unban<C: Context>(ctx: &C, user_id: i64) -> sqlx::Result<()> {
     Ok(())
 }
 
+/// Restores the given privileges, keeping all others as they are.
+pub async fn grant_privileges<C: Context>(
+    ctx: &C,
+    user_id: i64,
+    privileges: Privileges,
+) -> sqlx::Result<()> {
+    const QUERY: &str = "UPDATE users SET privileges = (privileges | (?)) WHERE id = ?";
+    sqlx::query(QUERY)
+        .bind(privileges.bits())
+        .bind(user_id)
+        .execute(ctx.db())
+        .await?;
+    Ok(())
+}
+
 pub async fn unrestrict<C: Context>(ctx: &C, user_id: i64) -> sqlx::Result<()> {
     const QUERY: &str = "UPDATE users SET privileges = (privileges | (?)) WHERE id = ?";
     let privileges = Privileges::PubliclyVisible;
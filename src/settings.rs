@@ -1,7 +1,9 @@
+use crate::adapters::mmdb::MmdbProvider;
 use crate::common::env::FromEnv;
 use std::env;
 use std::net::IpAddr;
 use std::ops::Deref;
+use std::str::FromStr;
 use std::sync::LazyLock;
 use std::time::Duration;
 use tracing::Level;
@@ -23,7 +25,6 @@ pub struct AppSettings {
     pub redis_response_timeout: Duration,
     pub redis_wait_timeout: Duration,
 
-    pub app_ci_key: String,
     pub beatmaps_service_base_url: String,
     pub performance_service_base_url: String,
 
@@ -31,6 +32,149 @@ pub struct AppSettings {
     pub discord_logs_webhook_url: Option<String>,
     pub discord_hw_webhook_url: Option<String>,
     pub discord_ranked_maps_webhook_url: Option<String>,
+
+    pub rate_limits: RateLimitSettings,
+    pub login_lockouts: LoginLockoutSettings,
+    pub geolocation: GeolocationSettings,
+    pub metrics: MetricsSettings,
+}
+
+pub struct RateLimitSettings {
+    /// Silence duration for the first offence, doubled for every repeated offence.
+    pub silence_base: Duration,
+    pub silence_max: Duration,
+}
+
+impl RateLimitSettings {
+    pub fn load_from_env() -> anyhow::Result<Self> {
+        let silence_base_secs = optional_from_env("RATE_LIMIT_SILENCE_BASE_SECS", 5 * 60)?;
+        let silence_max_secs = optional_from_env("RATE_LIMIT_SILENCE_MAX_SECS", 24 * 60 * 60)?;
+
+        Ok(RateLimitSettings {
+            silence_base: Duration::from_secs(silence_base_secs),
+            silence_max: Duration::from_secs(silence_max_secs),
+        })
+    }
+}
+
+pub struct LoginLockoutSettings {
+    /// Failed attempts from a single network before it is locked out
+    pub ip_attempts: u32,
+    /// Failed attempts against a single username before it is locked out
+    pub username_attempts: u32,
+    /// Failed attempts are forgotten after this long without a new one
+    pub attempt_window: Duration,
+    /// Lockout duration for the first lockout, doubled for every repeated lockout.
+    pub lockout_base: Duration,
+    pub lockout_max: Duration,
+    /// Repeated lockouts of an account within a day are reported to discord from this count on
+    pub notify_after: u32,
+}
+
+impl LoginLockoutSettings {
+    pub fn load_from_env() -> anyhow::Result<Self> {
+        let attempt_window_secs = optional_from_env("LOGIN_LOCKOUT_ATTEMPT_WINDOW_SECS", 15 * 60)?;
+        let lockout_base_secs = optional_from_env("LOGIN_LOCKOUT_BASE_SECS", 5 * 60)?;
+        let lockout_max_secs = optional_from_env("LOGIN_LOCKOUT_MAX_SECS", 24 * 60 * 60)?;
+
+        Ok(LoginLockoutSettings {
+            ip_attempts: optional_from_env("LOGIN_LOCKOUT_IP_ATTEMPTS", 20)?,
+            username_attempts: optional_from_env("LOGIN_LOCKOUT_USERNAME_ATTEMPTS", 5)?,
+            attempt_window: Duration::from_secs(attempt_window_secs),
+            lockout_base: Duration::from_secs(lockout_base_secs),
+            lockout_max: Duration::from_secs(lockout_max_secs),
+            notify_after: optional_from_env("LOGIN_LOCKOUT_NOTIFY_AFTER", 3)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeolocationBackend {
+    IpApi,
+    /// Local GeoLite2-style database, falling back to ip-api for unknown addresses
+    Mmdb,
+}
+
+impl FromStr for GeolocationBackend {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.trim() {
+            "ip_api" => Ok(GeolocationBackend::IpApi),
+            "mmdb" => Ok(GeolocationBackend::Mmdb),
+            _ => anyhow::bail!("invalid geolocation provider {s:?}, expected ip_api or mmdb"),
+        }
+    }
+}
+
+pub struct GeolocationSettings {
+    /// Set for the mmdb provider, opened along with the settings,
+    /// so that a missing or corrupt database fails startup
+    pub mmdb: Option<MmdbProvider>,
+    /// How long resolved locations are cached in redis
+    pub cache_ttl: Duration,
+}
+
+impl GeolocationSettings {
+    pub fn load_from_env() -> anyhow::Result<Self> {
+        let provider = optional_from_env("GEOLOCATION_PROVIDER", GeolocationBackend::IpApi)?;
+        let mmdb_path = env::var("GEOLOCATION_MMDB_PATH")
+            .ok()
+            .filter(|path| !path.trim().is_empty());
+        let mmdb = match (provider, mmdb_path) {
+            (GeolocationBackend::IpApi, _) => None,
+            (GeolocationBackend::Mmdb, Some(mmdb_path)) => match MmdbProvider::open(&mmdb_path) {
+                Ok(mmdb) => Some(mmdb),
+                Err(e) => anyhow::bail!("failed to open geolocation database {mmdb_path:?}: {e}"),
+            },
+            (GeolocationBackend::Mmdb, None) => {
+                anyhow::bail!("GEOLOCATION_MMDB_PATH is required for the mmdb geolocation provider")
+            }
+        };
+        let cache_ttl_secs = optional_from_env("GEOLOCATION_CACHE_TTL_SECS", 7 * 24 * 60 * 60)?;
+
+        Ok(GeolocationSettings {
+            mmdb,
+            cache_ttl: Duration::from_secs(cache_ttl_secs),
+        })
+    }
+}
+
+pub struct MetricsSettings {
+    /// Port of the metrics listener for components without an HTTP server
+    pub port: Option<u16>,
+    pub push_gateway_url: Option<String>,
+    pub push_interval: Duration,
+}
+
+impl MetricsSettings {
+    pub fn load_from_env() -> anyhow::Result<Self> {
+        let port = match env::var("METRICS_PORT") {
+            Ok(port) if !port.trim().is_empty() => Some(u16::from_str(port.trim())?),
+            _ => None,
+        };
+        let push_gateway_url = env::var("METRICS_PUSH_GATEWAY_URL")
+            .ok()
+            .filter(|url| !url.trim().is_empty());
+        let push_interval_secs = optional_from_env("METRICS_PUSH_INTERVAL_SECS", 15)?;
+
+        Ok(MetricsSettings {
+            port,
+            push_gateway_url,
+            push_interval: Duration::from_secs(push_interval_secs),
+        })
+    }
+}
+
+fn optional_from_env<T>(env_var: &str, default: T) -> anyhow::Result<T>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Into<anyhow::Error>,
+{
+    match env::var(env_var) {
+        Ok(value) if !value.trim().is_empty() => T::from_str(&value).map_err(Into::into),
+        _ => Ok(default),
+    }
 }
 
 impl AppSettings {
@@ -57,7 +201,6 @@ impl AppSettings {
         let redis_wait_timeout_secs = u64::from_env("REDIS_WAIT_TIMEOUT_SECS")?;
         let redis_wait_timeout = Duration::from_secs(redis_wait_timeout_secs);
 
-        let app_ci_key = env::var("APP_CI_KEY")?;
         let beatmaps_service_base_url = env::var("BEATMAPS_SERVICE_BASE_URL")?;
         let performance_service_base_url = env::var("PERFORMANCE_SERVICE_BASE_URL")?;
         let frontend_base_url = env::var("FRONTEND_URL")?;
@@ -71,6 +214,11 @@ impl AppSettings {
             .ok()
             .filter(|url| !url.trim().is_empty());
 
+        let rate_limits = RateLimitSettings::load_from_env()?;
+        let login_lockouts = LoginLockoutSettings::load_from_env()?;
+        let geolocation = GeolocationSettings::load_from_env()?;
+        let metrics = MetricsSettings::load_from_env()?;
+
         Ok(AppSettings {
             app_env,
             app_component,
@@ -88,13 +236,17 @@ impl AppSettings {
             redis_response_timeout,
             redis_wait_timeout,
 
-            app_ci_key,
             beatmaps_service_base_url,
             performance_service_base_url,
             frontend_base_url,
             discord_logs_webhook_url,
             discord_hw_webhook_url,
             discord_ranked_maps_webhook_url,
+
+            rate_limits,
+            login_lockouts,
+            geolocation,
+            metrics,
         })
     }
 
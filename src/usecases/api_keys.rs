@@ -0,0 +1,72 @@
+use crate::common::context::Context;
+use crate::common::error::{AppError, ServiceResult, unexpected};
+use crate::models::api_keys::{ApiKey, ApiKeyScope};
+use crate::repositories::api_keys;
+use rand::Rng;
+use rand::distr::Alphanumeric;
+use sha2::{Digest, Sha256};
+use tracing::info;
+
+const KEY_PREFIX: &str = "bsk_";
+const KEY_LENGTH: usize = 40;
+
+/// Creates a new API key, the plain key is only ever returned here.
+pub async fn create<C: Context>(
+    ctx: &C,
+    name: &str,
+    scopes: &[ApiKeyScope],
+    created_by: i64,
+) -> ServiceResult<(i64, String)> {
+    if scopes.is_empty() {
+        return Err(AppError::ApiKeysInvalidScope);
+    }
+
+    let secret: String = rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(KEY_LENGTH)
+        .map(char::from)
+        .collect();
+    let key = format!("{KEY_PREFIX}{secret}");
+    let scopes = ApiKeyScope::format_list(scopes);
+    match api_keys::create(ctx, name, &hash_key(&key), &scopes, created_by).await {
+        Ok(key_id) => {
+            info!(key_id, name, scopes, created_by, "API key created.");
+            Ok((key_id as _, key))
+        }
+        Err(e) => unexpected(e),
+    }
+}
+
+pub async fn fetch_active<C: Context>(ctx: &C) -> ServiceResult<Vec<ApiKey>> {
+    match api_keys::fetch_active(ctx).await {
+        Ok(keys) => keys.into_iter().map(ApiKey::try_from).collect(),
+        Err(e) => unexpected(e),
+    }
+}
+
+pub async fn revoke<C: Context>(ctx: &C, key_id: i64) -> ServiceResult<()> {
+    match api_keys::revoke(ctx, key_id).await {
+        Ok(true) => {
+            info!(key_id, "API key revoked.");
+            Ok(())
+        }
+        Ok(false) => Err(AppError::ApiKeysNotFound),
+        Err(e) => unexpected(e),
+    }
+}
+
+pub async fn authenticate<C: Context>(ctx: &C, key: &str) -> ServiceResult<ApiKey> {
+    if !key.starts_with(KEY_PREFIX) {
+        return Err(AppError::Unauthorized);
+    }
+    match api_keys::fetch_active_by_hash(ctx, &hash_key(key)).await {
+        Ok(Some(api_key)) => ApiKey::try_from(api_key),
+        Ok(None) => Err(AppError::Unauthorized),
+        Err(e) => unexpected(e),
+    }
+}
+
+/// Keys are random, so a plain hash is enough to avoid storing them in clear text.
+fn hash_key(key: &str) -> String {
+    format!("{:x}", Sha256::digest(key.as_bytes()))
+}
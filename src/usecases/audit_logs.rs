@@ -0,0 +1,91 @@
+use crate::common::chat::safe_username;
+use crate::common::context::Context;
+use crate::common::error::{ServiceResult, unexpected};
+use crate::entities::audit_logs::CreateAuditLogArgs;
+use crate::models::api_keys::ApiKey;
+use crate::models::audit_logs::AuditLog;
+use crate::models::sessions::Session;
+use crate::repositories::audit_logs;
+use crate::usecases::users;
+use tracing::error;
+
+/// Records a privileged command invocation.
+/// Failing to write the log must never fail the command itself, so errors are only logged.
+pub async fn record_command<C: Context, T>(
+    ctx: &C,
+    session: &Session,
+    command_name: &str,
+    target: Option<&str>,
+    arguments: Option<&str>,
+    result: &ServiceResult<T>,
+) {
+    let target_user_id = match target {
+        Some(target) => users::fetch_one_by_username_safe(ctx, &safe_username(target))
+            .await
+            .ok()
+            .map(|user| user.user_id),
+        None => None,
+    };
+    let args = CreateAuditLogArgs {
+        user_id: session.user_id,
+        username: &session.username,
+        api_key_id: None,
+        action: command_name,
+        target_user_id,
+        target,
+        arguments,
+        error: result.as_ref().err().map(|e| e.code()),
+    };
+    if let Err(e) = audit_logs::create(ctx, args).await {
+        error!(
+            user_id = session.user_id,
+            command_name, "Failed to record command in audit log: {e:?}"
+        );
+    }
+}
+
+/// Records a moderation action performed through the admin API,
+/// including calls that were rejected or failed.
+pub async fn record_api_action<C: Context, T>(
+    ctx: &C,
+    api_key: &ApiKey,
+    action: &str,
+    target_user_id: Option<i64>,
+    arguments: Option<&str>,
+    result: &ServiceResult<T>,
+) {
+    let args = CreateAuditLogArgs {
+        user_id: api_key.created_by,
+        username: &api_key.name,
+        api_key_id: Some(api_key.id),
+        action,
+        target_user_id,
+        target: None,
+        arguments,
+        error: result.as_ref().err().map(|e| e.code()),
+    };
+    if let Err(e) = audit_logs::create(ctx, args).await {
+        error!(
+            api_key_id = api_key.id,
+            action, "Failed to record API action in audit log: {e:?}"
+        );
+    }
+}
+
+pub async fn fetch_by_user_id<C: Context>(
+    ctx: &C,
+    user_id: i64,
+    limit: u32,
+) -> ServiceResult<Vec<AuditLog>> {
+    match audit_logs::fetch_by_user_id(ctx, user_id, limit).await {
+        Ok(logs) => Ok(logs.into_iter().map(AuditLog::from).collect()),
+        Err(e) => unexpected(e),
+    }
+}
+
+pub async fn fetch_recent<C: Context>(ctx: &C, limit: u32) -> ServiceResult<Vec<AuditLog>> {
+    match audit_logs::fetch_recent(ctx, limit).await {
+        Ok(logs) => Ok(logs.into_iter().map(AuditLog::from).collect()),
+        Err(e) => unexpected(e),
+    }
+}
@@ -1,8 +1,61 @@
 use crate::common::context::Context;
-use crate::common::error::ServiceResult;
+use crate::common::error::{AppError, ServiceResult, unexpected};
+use crate::common::versioned_cache::VersionedCache;
+use crate::models::bancho_settings::{BanchoSettingKey, BanchoSettings};
+use crate::models::maintenance::MaintenanceWindow;
 use crate::repositories::bancho_settings;
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::{error, info};
 
 const MAINTENANCE_KEY: &str = "bancho_maintenance";
+/// `value_string` holds the scheduled window as JSON, empty if none is scheduled.
+const MAINTENANCE_WINDOW_KEY: &str = "bancho_maintenance_window";
+
+static CACHE: VersionedCache<BanchoSettings> =
+    VersionedCache::new("akatsuki:bancho:settings:version", "peppy:reload_settings");
+
+/// Returns the runtime settings, reloading them once they have been changed on any replica.
+pub async fn fetch_current<C: Context>(ctx: &C) -> ServiceResult<Arc<BanchoSettings>> {
+    CACHE.fetch(ctx, |version| load(ctx, version)).await
+}
+
+/// Returns the last loaded settings without checking for changes,
+/// for code which can't wait on the database.
+/// The settings are loaded when the state is initialized.
+pub fn cached() -> Arc<BanchoSettings> {
+    CACHE
+        .cached()
+        .expect("bancho settings are loaded on startup")
+}
+
+/// Validates and stores the setting, then makes every replica reload its settings.
+pub async fn set<C: Context>(
+    ctx: &C,
+    key: BanchoSettingKey,
+    value: &str,
+) -> ServiceResult<Arc<BanchoSettings>> {
+    let mut settings = BanchoSettings::clone(&fetch_current(ctx).await?);
+    settings.set(key, value)?;
+    let value = settings.get(key);
+    match key.is_int() {
+        true => {
+            let value_int = i32::from_str(&value).map_err(|_| AppError::SettingsInvalidValue)?;
+            upsert(ctx, key.as_str(), value_int, "").await?
+        }
+        false => upsert(ctx, key.as_str(), 0, &value).await?,
+    }
+    CACHE.reload(ctx).await?;
+    fetch_current(ctx).await
+}
+
+/// Called by the pubsub daemon, returns the new version if it had to be bumped.
+pub async fn handle_reload_request<C: Context>(
+    ctx: &C,
+    payload: &str,
+) -> ServiceResult<Option<u64>> {
+    CACHE.handle_reload_request(ctx, payload).await
+}
 
 pub async fn in_maintenance_mode<C: Context>(ctx: &C) -> ServiceResult<bool> {
     let current = bancho_settings::fetch(ctx, MAINTENANCE_KEY).await?;
@@ -10,6 +63,11 @@ pub async fn in_maintenance_mode<C: Context>(ctx: &C) -> ServiceResult<bool> {
     Ok(is_active)
 }
 
+pub async fn set_maintenance<C: Context>(ctx: &C, enabled: bool) -> ServiceResult<()> {
+    bancho_settings::update_int(ctx, MAINTENANCE_KEY, enabled as i32).await?;
+    Ok(())
+}
+
 pub async fn toggle_maintenance<C: Context>(ctx: &C) -> ServiceResult<bool> {
     let current = bancho_settings::fetch(ctx, MAINTENANCE_KEY).await?;
     let is_active = current.value_int != 0;
@@ -20,3 +78,69 @@ pub async fn toggle_maintenance<C: Context>(ctx: &C) -> ServiceResult<bool> {
     bancho_settings::update_int(ctx, MAINTENANCE_KEY, new_value).await?;
     Ok(!is_active)
 }
+
+pub async fn fetch_maintenance_window<C: Context>(
+    ctx: &C,
+) -> ServiceResult<Option<MaintenanceWindow>> {
+    let setting = match bancho_settings::fetch(ctx, MAINTENANCE_WINDOW_KEY).await {
+        Ok(setting) if !setting.value_string.is_empty() => setting,
+        Ok(_) | Err(sqlx::Error::RowNotFound) => return Ok(None),
+        Err(e) => return unexpected(e),
+    };
+    match serde_json::from_str(&setting.value_string) {
+        Ok(window) => Ok(Some(window)),
+        Err(e) => unexpected(e),
+    }
+}
+
+pub async fn set_maintenance_window<C: Context>(
+    ctx: &C,
+    window: &MaintenanceWindow,
+) -> ServiceResult<()> {
+    let value = serde_json::to_string(window)?;
+    upsert(ctx, MAINTENANCE_WINDOW_KEY, 0, &value).await
+}
+
+pub async fn clear_maintenance_window<C: Context>(ctx: &C) -> ServiceResult<()> {
+    bancho_settings::update_str(ctx, MAINTENANCE_WINDOW_KEY, "").await?;
+    Ok(())
+}
+
+async fn upsert<C: Context>(
+    ctx: &C,
+    key: &str,
+    value_int: i32,
+    value_str: &str,
+) -> ServiceResult<()> {
+    match bancho_settings::fetch(ctx, key).await {
+        Ok(_) => bancho_settings::update(ctx, key, value_int, value_str).await?,
+        Err(sqlx::Error::RowNotFound) => {
+            bancho_settings::create(ctx, key, value_int, value_str).await?
+        }
+        Err(e) => return unexpected(e),
+    }
+    Ok(())
+}
+
+async fn load<C: Context>(ctx: &C, version: u64) -> ServiceResult<BanchoSettings> {
+    let rows = match bancho_settings::fetch_all(ctx).await {
+        Ok(rows) => rows,
+        Err(e) => return unexpected(e),
+    };
+    let mut settings = BanchoSettings::default();
+    for row in rows {
+        // the table also holds state like the maintenance flag
+        let Ok(key) = BanchoSettingKey::from_str(&row.name) else {
+            continue;
+        };
+        let value = match key.is_int() {
+            true => row.value_int.to_string(),
+            false => row.value_string,
+        };
+        if let Err(e) = settings.set(key, &value) {
+            error!(key = key.as_str(), value, "Skipping invalid setting: {e:?}");
+        }
+    }
+    info!(version, "Loaded bancho settings.");
+    Ok(settings)
+}
@@ -1,15 +1,21 @@
 use crate::common::context::Context;
 use crate::common::error::{AppError, ServiceResult, unexpected};
-use crate::entities::channels::ChannelName;
-use crate::models::channels::Channel;
+use crate::entities::channels::{Channel as Entity, ChannelName};
+use crate::models::channels::{Channel, ChannelRole};
 use crate::models::sessions::Session;
 use crate::repositories::channels;
 use crate::repositories::streams::StreamName;
-use crate::usecases::{multiplayer, spectators, streams};
-use bancho_protocol::messages::server::ChannelInfo;
+use crate::usecases::{messages, multiplayer, sessions, spectators, streams};
+use bancho_protocol::messages::MessageArgs;
+use bancho_protocol::messages::server::{ChannelInfo, ChannelKick, ChatMessage};
+use bancho_protocol::serde::BinarySerialize;
+use bancho_protocol::structures::IrcMessage;
 use tracing::{error, info};
 use uuid::Uuid;
 
+const CHANNEL_NAME_MAX_LENGTH: usize = 32;
+const MAX_OWNED_CHANNELS: usize = 3;
+
 pub async fn get_channel_name<'a, C: Context>(
     ctx: &C,
     session: &Session,
@@ -68,6 +74,18 @@ pub async fn join<C: Context>(
     if !channel.can_read(session.privileges) {
         return Err(AppError::ChannelsUnauthorized);
     }
+    match fetch_role(ctx, &channel, session.user_id).await? {
+        Some(ChannelRole::Banned) if !session.privileges.is_staff() => {
+            return Err(AppError::ChannelsBanned);
+        }
+        None if channel.invite_only
+            && !channel.is_owner(session.user_id)
+            && !session.privileges.is_staff() =>
+        {
+            return Err(AppError::ChannelsUnauthorized);
+        }
+        _ => {}
+    }
 
     let stream_name = channel_name.get_message_stream();
     streams::join(ctx, session.session_id, stream_name).await?;
@@ -78,6 +96,12 @@ pub async fn join<C: Context>(
     );
 
     broadcast_channel_info_update(ctx, channel_name, &channel, member_count).await?;
+    if let Err(e) = replay_history(ctx, session, channel_name, &channel).await {
+        error!(
+            channel_name = channel_name.to_string(),
+            "Failed to replay channel history: {e:?}"
+        );
+    }
 
     Ok((channel, member_count))
 }
@@ -131,6 +155,272 @@ pub async fn member_count<C: Context>(
     }
 }
 
+pub async fn fetch_members<C: Context>(
+    ctx: &C,
+    channel_name: ChannelName<'_>,
+) -> ServiceResult<Vec<Uuid>> {
+    match channels::fetch_channel_members(ctx, channel_name).await {
+        Ok(member_ids) => Ok(member_ids),
+        Err(e) => unexpected(e),
+    }
+}
+
+/// Returns the channels the session may see in its channel listing.
+pub async fn fetch_listed<C: Context>(ctx: &C, session: &Session) -> ServiceResult<Vec<Channel>> {
+    let memberships = match channels::fetch_user_memberships(ctx, session.user_id).await {
+        Ok(memberships) => memberships,
+        Err(e) => return unexpected(e),
+    };
+    let is_invited = |channel: &Channel| {
+        memberships.iter().any(|member| {
+            member.channel_id == channel.id && member.role != ChannelRole::Banned as i8
+        })
+    };
+
+    let channels = fetch_all(ctx).await?;
+    Ok(channels
+        .into_iter()
+        .filter(|channel| channel.can_read(session.privileges))
+        .filter(|channel| {
+            !channel.invite_only
+                || channel.is_owner(session.user_id)
+                || session.privileges.is_staff()
+                || is_invited(channel)
+        })
+        .collect())
+}
+
+pub async fn fetch_role<C: Context>(
+    ctx: &C,
+    channel: &Channel,
+    user_id: i64,
+) -> ServiceResult<Option<ChannelRole>> {
+    // spectator and multiplayer channels are not persisted
+    if channel.id == 0 {
+        return Ok(None);
+    }
+
+    match channels::fetch_member(ctx, channel.id, user_id).await {
+        Ok(Some(member)) => Ok(Some(ChannelRole::try_from(member.role)?)),
+        Ok(None) => Ok(None),
+        Err(e) => unexpected(e),
+    }
+}
+
+pub async fn is_banned<C: Context>(
+    ctx: &C,
+    channel: &Channel,
+    user_id: i64,
+) -> ServiceResult<bool> {
+    let role = fetch_role(ctx, channel, user_id).await?;
+    Ok(role == Some(ChannelRole::Banned))
+}
+
+/// Staff, channel owners and channel moderators may moderate a channel.
+pub async fn can_moderate<C: Context>(
+    ctx: &C,
+    session: &Session,
+    channel: &Channel,
+) -> ServiceResult<bool> {
+    if session.privileges.is_staff() || channel.is_owner(session.user_id) {
+        return Ok(true);
+    }
+    let role = fetch_role(ctx, channel, session.user_id).await?;
+    Ok(role == Some(ChannelRole::Moderator))
+}
+
+/// Only channel owners and admins may change the settings of a channel.
+pub fn can_manage(session: &Session, channel: &Channel) -> bool {
+    channel.is_owner(session.user_id) || session.privileges.is_admin()
+}
+
+pub async fn create<C: Context>(
+    ctx: &C,
+    session: &Session,
+    channel_name: &str,
+    description: &str,
+) -> ServiceResult<Channel> {
+    let channel_name = channel_name.to_lowercase();
+    if !is_valid_channel_name(&channel_name) {
+        return Err(AppError::ChannelsInvalidName);
+    }
+    match channels::fetch_one(ctx, &channel_name).await {
+        Ok(_) => return Err(AppError::ChannelsAlreadyExists),
+        Err(sqlx::Error::RowNotFound) => {}
+        Err(e) => return unexpected(e),
+    }
+
+    let owned_channels = fetch_all(ctx)
+        .await?
+        .into_iter()
+        .filter(|channel| channel.is_owner(session.user_id))
+        .count();
+    if owned_channels >= MAX_OWNED_CHANNELS {
+        return Err(AppError::ChannelsLimitReached);
+    }
+
+    let channel = match channels::create(ctx, &channel_name, description, session.user_id).await {
+        Ok(channel) => Channel::from(channel),
+        Err(e) => return unexpected(e),
+    };
+    info!(
+        channel_name = channel.name,
+        owner_user_id = session.user_id,
+        "User created channel."
+    );
+    broadcast_channel_info_update(ctx, ChannelName::Chat(&channel.name), &channel, 0).await?;
+    Ok(channel)
+}
+
+pub async fn update<C: Context>(ctx: &C, channel: &Channel) -> ServiceResult<()> {
+    let entity = Entity {
+        id: channel.id,
+        name: channel.name.clone(),
+        description: channel.description.clone(),
+        public_read: channel.read_privileges.is_empty(),
+        public_write: channel.write_privileges.is_empty(),
+        read_privileges: channel.read_privileges.bits(),
+        write_privileges: channel.write_privileges.bits(),
+        owner_user_id: channel.owner_user_id,
+        invite_only: channel.invite_only,
+        history_size: channel.history_size as _,
+        slow_mode_seconds: channel.slow_mode_seconds as _,
+        status: channel.status,
+    };
+    if let Err(e) = channels::update(ctx, &entity).await {
+        return unexpected(e);
+    }
+
+    let channel_name = ChannelName::Chat(&channel.name);
+    let member_count = member_count(ctx, channel_name).await?;
+    broadcast_channel_info_update(ctx, channel_name, channel, member_count).await
+}
+
+pub async fn delete<C: Context>(ctx: &C, channel: &Channel) -> ServiceResult<()> {
+    if channel.is_system() {
+        return Err(AppError::ChannelsSystemChannel);
+    }
+
+    let channel_name = ChannelName::Chat(&channel.name);
+    for session_id in fetch_members(ctx, channel_name).await? {
+        kick_session(ctx, session_id, channel_name).await?;
+    }
+    match channels::delete(ctx, channel.id).await {
+        Ok(_) => Ok(()),
+        Err(e) => unexpected(e),
+    }
+}
+
+pub async fn set_role<C: Context>(
+    ctx: &C,
+    channel: &Channel,
+    user_id: i64,
+    role: Option<ChannelRole>,
+) -> ServiceResult<()> {
+    let result = match role {
+        Some(role) => channels::set_member_role(ctx, channel.id, user_id, role as i8).await,
+        None => channels::remove_member(ctx, channel.id, user_id).await,
+    };
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => unexpected(e),
+    }
+}
+
+pub async fn fetch_moderator_ids<C: Context>(
+    ctx: &C,
+    channel: &Channel,
+) -> ServiceResult<Vec<i64>> {
+    match channels::fetch_members(ctx, channel.id).await {
+        Ok(members) => Ok(members
+            .into_iter()
+            .filter(|member| member.role == ChannelRole::Moderator as i8)
+            .map(|member| member.user_id)
+            .collect()),
+        Err(e) => unexpected(e),
+    }
+}
+
+/// Removes all sessions of the user from the channel.
+/// Returns whether the user was in the channel.
+pub async fn kick<C: Context>(ctx: &C, channel: &Channel, user_id: i64) -> ServiceResult<bool> {
+    let channel_name = ChannelName::Chat(&channel.name);
+    let member_ids = fetch_members(ctx, channel_name).await?;
+    let mut kicked = false;
+    for session in sessions::fetch_by_user_id(ctx, user_id).await? {
+        if member_ids.contains(&session.session_id) {
+            kick_session(ctx, session.session_id, channel_name).await?;
+            kicked = true;
+        }
+    }
+    Ok(kicked)
+}
+
+pub async fn mute<C: Context>(
+    ctx: &C,
+    channel: &Channel,
+    user_id: i64,
+    mute_seconds: u64,
+) -> ServiceResult<()> {
+    match channels::mute(ctx, &channel.name, user_id, mute_seconds).await {
+        Ok(_) => Ok(()),
+        Err(e) => unexpected(e),
+    }
+}
+
+/// Returns whether the user was muted.
+pub async fn unmute<C: Context>(ctx: &C, channel: &Channel, user_id: i64) -> ServiceResult<bool> {
+    match channels::unmute(ctx, &channel.name, user_id).await {
+        Ok(was_muted) => Ok(was_muted),
+        Err(e) => unexpected(e),
+    }
+}
+
+/// Returns the remaining seconds of the user's mute in the channel, if they are muted.
+pub async fn fetch_mute_remaining<C: Context>(
+    ctx: &C,
+    channel_name: ChannelName<'_>,
+    user_id: i64,
+) -> ServiceResult<Option<u64>> {
+    match channels::fetch_mute_ttl(ctx, &channel_name.to_string(), user_id).await {
+        Ok(mute_remaining) => Ok(mute_remaining),
+        Err(e) => unexpected(e),
+    }
+}
+
+/// Enforces channel mutes and slow mode, moderators are exempt from slow mode.
+pub async fn check_can_send<C: Context>(
+    ctx: &C,
+    session: &Session,
+    channel_name: ChannelName<'_>,
+    channel: &Channel,
+) -> ServiceResult<()> {
+    if fetch_mute_remaining(ctx, channel_name, session.user_id)
+        .await?
+        .is_some()
+    {
+        return Err(AppError::ChannelsMuted);
+    }
+
+    if channel.slow_mode_seconds == 0 {
+        return Ok(());
+    }
+    // the role is only looked up once the sender is actually slowed down
+    match channels::try_consume_slow_mode(
+        ctx,
+        &channel_name.to_string(),
+        session.user_id,
+        channel.slow_mode_seconds as _,
+    )
+    .await
+    {
+        Ok(true) => Ok(()),
+        Ok(false) if can_moderate(ctx, session, channel).await? => Ok(()),
+        Ok(false) => Err(AppError::ChannelsSlowMode),
+        Err(e) => unexpected(e),
+    }
+}
+
 pub async fn close<C: Context>(ctx: &C, channel_name: ChannelName<'_>) -> ServiceResult<()> {
     let member_ids = channels::fetch_channel_members(ctx, channel_name).await?;
     for session_id in member_ids {
@@ -145,12 +435,79 @@ pub async fn close<C: Context>(ctx: &C, channel_name: ChannelName<'_>) -> Servic
 
 // utility
 
+fn is_valid_channel_name(channel_name: &str) -> bool {
+    let Some(name) = channel_name.strip_prefix('#') else {
+        return false;
+    };
+    // these prefixes are reserved for spectator and multiplayer channels
+    if name.starts_with("spectator") || name.starts_with("multiplayer") {
+        return false;
+    }
+    (2..=CHANNEL_NAME_MAX_LENGTH).contains(&name.len())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Sends the recent messages of the channel to the session,
+/// after the response to the join itself has been delivered.
+async fn replay_history<C: Context>(
+    ctx: &C,
+    session: &Session,
+    channel_name: ChannelName<'_>,
+    channel: &Channel,
+) -> ServiceResult<()> {
+    if channel.history_size == 0 {
+        return Ok(());
+    }
+
+    let history = messages::fetch_channel_history(ctx, channel_name, channel.history_size).await?;
+    let mut data = vec![];
+    for message in &history {
+        let irc_message = IrcMessage {
+            sender: &message.sender_name,
+            sender_id: message.sender_id as _,
+            text: &message.content,
+            recipient: channel_name.to_bancho(),
+        };
+        data.extend(ChatMessage(&irc_message).as_message().serialize());
+    }
+    if data.is_empty() {
+        return Ok(());
+    }
+
+    streams::broadcast_data(ctx, StreamName::User(session.session_id), &data, None, None).await
+}
+
+async fn kick_session<C: Context>(
+    ctx: &C,
+    session_id: Uuid,
+    channel_name: ChannelName<'_>,
+) -> ServiceResult<()> {
+    leave(ctx, session_id, channel_name).await?;
+    streams::broadcast_message(
+        ctx,
+        StreamName::User(session_id),
+        ChannelKick {
+            name: channel_name.to_bancho(),
+        },
+        None,
+        None,
+    )
+    .await
+}
+
 async fn broadcast_channel_info_update<C: Context>(
     ctx: &C,
     channel_name: ChannelName<'_>,
     channel: &Channel,
     member_count: usize,
 ) -> ServiceResult<()> {
+    // invite-only channels are not advertised
+    if channel.invite_only {
+        return Ok(());
+    }
+
     let update_stream = channel_name.get_update_stream();
     let priv_rule = match update_stream {
         StreamName::Main => Some(channel.read_privileges),
@@ -0,0 +1,179 @@
+use crate::common::context::Context;
+use crate::common::error::{AppError, ServiceResult, unexpected};
+use crate::common::versioned_cache::VersionedCache;
+use crate::entities::bot;
+use crate::entities::channels::ChannelName;
+use crate::models::chat_filters::{ChatFilter, FilterAction, FilterRuleType};
+use crate::models::sessions::Session;
+use crate::repositories::chat_filters;
+use crate::usecases::{streams, users};
+use bancho_protocol::messages::server::ChatMessage;
+use bancho_protocol::structures::IrcMessage;
+use chrono::{TimeDelta, Utc};
+use std::sync::Arc;
+use tracing::{error, info};
+
+const FILTER_SILENCE_REASON: &str = "Inappropriate message (Auto-Silence)";
+
+static CACHE: VersionedCache<Vec<ChatFilter>> = VersionedCache::new(
+    "akatsuki:bancho:chat_filters:version",
+    "peppy:reload_chat_filters",
+);
+
+pub struct FilterResult {
+    /// The content of the message after censoring
+    pub content: String,
+    /// The ids of the filters that flagged the message
+    pub flagged_by: Vec<i64>,
+}
+
+pub async fn fetch_all<C: Context>(ctx: &C) -> ServiceResult<Arc<Vec<ChatFilter>>> {
+    CACHE.fetch(ctx, |version| load(ctx, version)).await
+}
+
+pub async fn create<C: Context>(
+    ctx: &C,
+    rule_type: FilterRuleType,
+    pattern: &str,
+    action: FilterAction,
+    created_by: i64,
+) -> ServiceResult<i64> {
+    // make sure the rule compiles before storing it
+    ChatFilter::new(0, rule_type, pattern.to_string(), action.clone())?;
+
+    let (replacement, silence_seconds) = match &action {
+        FilterAction::Censor(replacement) => (Some(replacement.as_str()), None),
+        FilterAction::Silence(seconds) => (None, Some(*seconds)),
+        FilterAction::Drop | FilterAction::Flag => (None, None),
+    };
+    let filter_id = match chat_filters::create(
+        ctx,
+        rule_type.as_str(),
+        pattern,
+        action.as_str(),
+        replacement,
+        silence_seconds,
+        created_by,
+    )
+    .await
+    {
+        Ok(filter_id) => filter_id as i64,
+        Err(e) => return unexpected(e),
+    };
+
+    CACHE.reload(ctx).await?;
+    Ok(filter_id)
+}
+
+pub async fn delete<C: Context>(ctx: &C, filter_id: i64) -> ServiceResult<()> {
+    match chat_filters::delete(ctx, filter_id).await {
+        Ok(true) => CACHE.reload(ctx).await,
+        Ok(false) => Err(AppError::ChatFiltersNotFound),
+        Err(e) => unexpected(e),
+    }
+}
+
+/// Called by the pubsub daemon, returns the new version if it had to be bumped.
+pub async fn handle_reload_request<C: Context>(
+    ctx: &C,
+    payload: &str,
+) -> ServiceResult<Option<u64>> {
+    CACHE.handle_reload_request(ctx, payload).await
+}
+
+/// Runs the message through the chat filters.
+/// Messages that are dropped or get their sender silenced result in an error.
+pub async fn apply<C: Context>(
+    ctx: &C,
+    session: &mut Session,
+    content: &str,
+) -> ServiceResult<FilterResult> {
+    let mut result = FilterResult {
+        content: content.to_string(),
+        flagged_by: vec![],
+    };
+    for filter in fetch_all(ctx).await?.iter() {
+        let matches = filter.find_matches(&result.content);
+        if matches.is_empty() {
+            continue;
+        }
+
+        match &filter.action {
+            FilterAction::Censor(replacement) => {
+                for range in matches.into_iter().rev() {
+                    result.content.replace_range(range, replacement);
+                }
+            }
+            FilterAction::Drop => return Err(AppError::MessagesFiltered),
+            FilterAction::Silence(seconds) => {
+                session.silence_end = Some(Utc::now() + TimeDelta::seconds(*seconds));
+                users::silence_user(ctx, session.user_id, FILTER_SILENCE_REASON, *seconds).await?;
+                return Err(AppError::MessagesUserSilenced);
+            }
+            FilterAction::Flag => result.flagged_by.push(filter.id),
+        }
+    }
+    Ok(result)
+}
+
+/// Reports a flagged message to #staff.
+pub async fn report_flagged<C: Context>(
+    ctx: &C,
+    session: &Session,
+    recipient: &str,
+    content: &str,
+    flagged_by: &[i64],
+) -> ServiceResult<()> {
+    let filter_ids = flagged_by
+        .iter()
+        .map(|filter_id| format!("#{filter_id}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let text = format!(
+        "Flagged message from {} ({}) to {recipient} by filter {filter_ids}: {content}",
+        session.username, session.user_id,
+    );
+    let channel_name = ChannelName::Chat("#staff");
+    let message = IrcMessage {
+        sender: bot::BOT_NAME,
+        sender_id: bot::BOT_ID as _,
+        text: &text,
+        recipient: channel_name.to_bancho(),
+    };
+    streams::broadcast_message(
+        ctx,
+        channel_name.get_message_stream(),
+        ChatMessage(&message),
+        None,
+        None,
+    )
+    .await
+}
+
+// utility
+
+async fn load<C: Context>(ctx: &C, version: u64) -> ServiceResult<Vec<ChatFilter>> {
+    let filters = match chat_filters::fetch_all(ctx).await {
+        Ok(filters) => filters,
+        Err(e) => return unexpected(e),
+    };
+    let filters: Vec<_> = filters
+        .into_iter()
+        .filter_map(|filter| {
+            let filter_id = filter.id;
+            match ChatFilter::try_from(filter) {
+                Ok(filter) => Some(filter),
+                Err(e) => {
+                    error!(filter_id, "Skipping invalid chat filter: {e:?}");
+                    None
+                }
+            }
+        })
+        .collect();
+    info!(
+        version,
+        filter_count = filters.len(),
+        "Loaded chat filters."
+    );
+    Ok(filters)
+}
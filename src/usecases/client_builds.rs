@@ -0,0 +1,218 @@
+use crate::adapters::discord;
+use crate::common::context::Context;
+use crate::common::error::{AppError, ServiceResult, unexpected};
+use crate::common::website;
+use crate::entities::bot;
+use crate::entities::channels::ChannelName;
+use crate::entities::client_builds::ClientBuild as Entity;
+use crate::models::bancho::OsuVersion;
+use crate::models::bancho_settings::BanchoSettings;
+use crate::models::client_builds::{BuildPolicy, BuildVersion, ClientBuild};
+use crate::models::users::User;
+use crate::repositories::client_builds;
+use crate::usecases::{sessions, streams};
+use bancho_protocol::messages::server::ChatMessage;
+use bancho_protocol::structures::IrcMessage;
+use std::collections::HashMap;
+use tracing::{error, info, warn};
+
+pub async fn fetch_all<C: Context>(ctx: &C) -> ServiceResult<Vec<ClientBuild>> {
+    match client_builds::fetch_all(ctx).await {
+        Ok(builds) => Ok(parse_builds(builds)),
+        Err(e) => unexpected(e),
+    }
+}
+
+pub async fn create<C: Context>(
+    ctx: &C,
+    version: BuildVersion,
+    policy: BuildPolicy,
+    reason: &str,
+    created_by: i64,
+) -> ServiceResult<i64> {
+    match client_builds::create(
+        ctx,
+        version.date,
+        version.minor,
+        policy.as_str(),
+        reason,
+        created_by,
+    )
+    .await
+    {
+        Ok(build_id) => Ok(build_id as i64),
+        Err(e) => unexpected(e),
+    }
+}
+
+pub async fn delete<C: Context>(ctx: &C, build_id: i64) -> ServiceResult<()> {
+    match client_builds::delete(ctx, build_id).await {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(AppError::ClientBuildsNotFound),
+        Err(e) => unexpected(e),
+    }
+}
+
+/// Rejects builds below the minimum of their release stream or past their expiration,
+/// unless they have been explicitly allowed. Doesn't need the user,
+/// so it can run before their password is checked.
+pub async fn check_supported<C: Context>(
+    ctx: &C,
+    osu_version: &OsuVersion,
+    settings: &BanchoSettings,
+) -> ServiceResult<()> {
+    let below_minimum = settings
+        .min_build(&osu_version.release_stream)
+        .is_some_and(|min_build| osu_version.build() < min_build);
+    let is_outdated = osu_version.is_outdated(settings);
+    if !below_minimum && !is_outdated {
+        return Ok(());
+    }
+
+    match fetch_matching(ctx, osu_version.build()).await? {
+        Some(entry) if entry.policy == BuildPolicy::Allow => Ok(()),
+        _ if below_minimum => Err(AppError::UnsupportedClientVersion),
+        _ => Err(AppError::ClientTooOld),
+    }
+}
+
+/// Rejects denied builds, reporting the attempt to staff.
+pub async fn check_denied<C: Context>(
+    ctx: &C,
+    user: &User,
+    osu_version: &OsuVersion,
+) -> ServiceResult<()> {
+    match fetch_matching(ctx, osu_version.build()).await? {
+        Some(entry) if entry.policy == BuildPolicy::Deny => {
+            info!(
+                user_id = user.user_id,
+                build_id = entry.id,
+                "Rejected login with denied client build {osu_version}"
+            );
+            report_denied(ctx, user, osu_version, &entry).await;
+            Err(AppError::UnsupportedClientVersion)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Tells the user why their build is unsupported,
+/// including the deny reason or the minimum build of their release stream.
+pub async fn unsupported_message<C: Context>(
+    ctx: &C,
+    osu_version: &OsuVersion,
+    settings: &BanchoSettings,
+) -> ServiceResult<String> {
+    match fetch_matching(ctx, osu_version.build()).await? {
+        Some(entry) if entry.policy == BuildPolicy::Deny => {
+            return Ok(format!(
+                "osu! {osu_version} is not allowed on Akatsuki: {}\nPlease update your client.",
+                entry.reason,
+            ));
+        }
+        _ => {}
+    }
+    match settings.min_build(&osu_version.release_stream) {
+        Some(min_build) if osu_version.build() < min_build => Ok(format!(
+            "osu! {osu_version} is no longer supported, Akatsuki requires build {min_build} or newer.\nPlease update your client."
+        )),
+        _ => Ok(AppError::UnsupportedClientVersion.message().to_owned()),
+    }
+}
+
+/// Counts the online sessions per osu! version, most used first.
+pub async fn fetch_version_distribution<C: Context>(
+    ctx: &C,
+) -> ServiceResult<Vec<(String, usize)>> {
+    let mut versions = HashMap::new();
+    for session in sessions::fetch_all(ctx).await? {
+        let version = session.client_version.unwrap_or_else(|| "IRC".to_owned());
+        *versions.entry(version).or_insert(0) += 1;
+    }
+    let mut versions = versions.into_iter().collect::<Vec<_>>();
+    versions.sort_by(|(a_version, a_count), (b_version, b_count)| {
+        b_count.cmp(a_count).then_with(|| b_version.cmp(a_version))
+    });
+    Ok(versions)
+}
+
+// utility
+
+/// Entries for a specific minor version take precedence over the ones covering the whole date.
+async fn fetch_matching<C: Context>(
+    ctx: &C,
+    build: BuildVersion,
+) -> ServiceResult<Option<ClientBuild>> {
+    let builds = match client_builds::fetch_by_date(ctx, build.date).await {
+        Ok(builds) => parse_builds(builds),
+        Err(e) => return unexpected(e),
+    };
+    Ok(builds
+        .into_iter()
+        .filter(|entry| entry.matches(build))
+        .max_by_key(|entry| entry.version.minor.is_some()))
+}
+
+/// Notifies `#staff` and discord, at most once an hour per user and build.
+async fn report_denied<C: Context>(
+    ctx: &C,
+    user: &User,
+    osu_version: &OsuVersion,
+    entry: &ClientBuild,
+) {
+    let version = osu_version.to_string();
+    match client_builds::try_claim_report(ctx, user.user_id, &version).await {
+        Ok(true) => {}
+        Ok(false) => return,
+        Err(e) => {
+            error!("Failed to claim client build report: {e:?}");
+            return;
+        }
+    }
+
+    let text = format!(
+        "{} ({}) tried to log in with denied build {version} (#{}): {}",
+        user.username, user.user_id, entry.id, entry.reason,
+    );
+    let channel_name = ChannelName::Chat("#staff");
+    let message = IrcMessage {
+        sender: bot::BOT_NAME,
+        sender_id: bot::BOT_ID as _,
+        text: &text,
+        recipient: channel_name.to_bancho(),
+    };
+    if let Err(e) = streams::broadcast_message(
+        ctx,
+        channel_name.get_message_stream(),
+        ChatMessage(&message),
+        None,
+        None,
+    )
+    .await
+    {
+        warn!("Failed to report denied client build to staff: {e:?}");
+    }
+
+    let user_profile_link = website::get_profile_link(user.user_id);
+    let notification = format!(
+        "[{}]({user_profile_link}) tried to log in with denied build `{version}` (#{}): {}",
+        user.username, entry.id, entry.reason,
+    );
+    let _ = discord::send_logs_purple_embed("Denied client build", &notification, None).await;
+}
+
+fn parse_builds(builds: Vec<Entity>) -> Vec<ClientBuild> {
+    builds
+        .into_iter()
+        .filter_map(|build| {
+            let build_id = build.id;
+            match ClientBuild::try_from(build) {
+                Ok(build) => Some(build),
+                Err(e) => {
+                    error!(build_id, "Skipping invalid client build: {e:?}");
+                    None
+                }
+            }
+        })
+        .collect()
+}
@@ -1,16 +1,32 @@
-use crate::adapters::ip_api;
-use crate::models::location::LocationInformation;
+use crate::adapters::geolocation::GeolocationProvider;
+use crate::adapters::ip_api::IpApiProvider;
+use crate::common::context::Context;
+use crate::models::location::{IpLocation, LocationInformation};
+use crate::repositories::location;
+use crate::settings::AppSettings;
 use bancho_protocol::structures::Country;
 use std::net::IpAddr;
+use std::sync::LazyLock;
 use tracing::warn;
 
-pub async fn get_location(
+/// Providers in the order they are asked, the first one knowing the address wins.
+static PROVIDERS: LazyLock<Vec<&'static dyn GeolocationProvider>> = LazyLock::new(|| {
+    let mut providers: Vec<&'static dyn GeolocationProvider> = vec![];
+    if let Some(mmdb) = &AppSettings::get().geolocation.mmdb {
+        providers.push(mmdb);
+    }
+    providers.push(&IpApiProvider);
+    providers
+});
+
+pub async fn get_location<C: Context>(
+    ctx: &C,
     ip_address: IpAddr,
     user_country: Country,
     show_exact: bool,
 ) -> LocationInformation {
-    match ip_api::get_ip_info(ip_address).await {
-        Ok(location) => {
+    match resolve(ctx, ip_address).await {
+        Some(location) => {
             let country = location.country_code.map_or(user_country, |code| {
                 Country::try_from_iso3166_2(&code).unwrap_or(user_country)
             });
@@ -21,16 +37,42 @@ pub async fn get_location(
             };
             location.offset_randomly(show_exact)
         }
-        Err(e) => {
-            warn!(
-                ip_address = ip_address.to_string(),
-                "Failed getting location for IP address: {e:?}"
-            );
-            LocationInformation {
-                country: user_country,
-                latitude: 0.0,
-                longitude: 0.0,
+        None => LocationInformation {
+            country: user_country,
+            latitude: 0.0,
+            longitude: 0.0,
+        },
+    }
+}
+
+/// Resolves the location from the cache or the configured providers,
+/// caching the result of the first provider knowing the address.
+async fn resolve<C: Context>(ctx: &C, ip_address: IpAddr) -> Option<IpLocation> {
+    match location::fetch_one(ctx, ip_address).await {
+        Ok(Some(location)) => return Some(location),
+        Ok(None) => {}
+        Err(e) => warn!("Failed fetching cached location: {e:?}"),
+    }
+
+    for provider in PROVIDERS.iter() {
+        let location = match provider.lookup(ip_address).await {
+            Ok(Some(location)) if !location.is_empty() => location,
+            Ok(_) => continue,
+            Err(e) => {
+                warn!(
+                    ip_address = ip_address.to_string(),
+                    provider = provider.name(),
+                    "Failed getting location for IP address: {e:?}"
+                );
+                continue;
             }
+        };
+
+        let ttl = AppSettings::get().geolocation.cache_ttl.as_secs();
+        if let Err(e) = location::create(ctx, ip_address, &location, ttl).await {
+            warn!("Failed caching location: {e:?}");
         }
+        return Some(location);
     }
+    None
 }
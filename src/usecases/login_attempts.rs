@@ -0,0 +1,197 @@
+use crate::adapters::discord;
+use crate::common::chat::format_duration;
+use crate::common::context::Context;
+use crate::common::error::{AppError, ServiceResult, unexpected};
+use crate::common::website;
+use crate::models::login_attempts::LoginAttemptScope;
+use crate::repositories::login_attempts;
+use crate::settings::AppSettings;
+use std::net::IpAddr;
+use std::time::Duration;
+use tracing::{error, warn};
+
+/// Fails with [`AppError::SessionsLockedOut`] if the network or the username is locked out.
+/// A locked out username may still be tried from networks without any failed attempts,
+/// so that others can't keep the owner of the account from logging in.
+pub async fn check<C: Context>(ctx: &C, ip_address: IpAddr, username: &str) -> ServiceResult<()> {
+    let network = LoginAttemptScope::Ip(ip_address).to_string();
+    if fetch_lock_ttl(ctx, &network).await?.is_some() {
+        return Err(AppError::SessionsLockedOut);
+    }
+
+    let username = LoginAttemptScope::Username(username).to_string();
+    if fetch_lock_ttl(ctx, &username).await?.is_none() {
+        return Ok(());
+    }
+    match login_attempts::has_failures(ctx, &network).await {
+        Ok(true) => Err(AppError::SessionsLockedOut),
+        Ok(false) => Ok(()),
+        Err(e) => unexpected(e),
+    }
+}
+
+/// Returns the remaining lockout of the network or the username, whichever ends last.
+pub async fn fetch_lockout<C: Context>(
+    ctx: &C,
+    ip_address: IpAddr,
+    username: &str,
+) -> ServiceResult<Option<Duration>> {
+    let mut remaining = None;
+    for scope in [
+        LoginAttemptScope::Ip(ip_address),
+        LoginAttemptScope::Username(username),
+    ] {
+        let ttl = fetch_lock_ttl(ctx, &scope.to_string()).await?;
+        remaining = remaining.max(ttl);
+    }
+    Ok(remaining.map(Duration::from_secs))
+}
+
+/// Returns the message shown to users trying to log in while locked out.
+pub async fn lockout_message<C: Context>(ctx: &C, ip_address: IpAddr, username: &str) -> String {
+    match fetch_lockout(ctx, ip_address, username).await {
+        Ok(Some(remaining)) => format!(
+            "Too many failed login attempts, please try again in {}.\n\
+            If you forgot your password, you can reset it on the website.",
+            format_duration(remaining.as_secs().max(1)),
+        ),
+        _ => AppError::SessionsLockedOut.message().to_owned(),
+    }
+}
+
+/// Records a failed login attempt,
+/// locking out the network or the username once they reach their limit.
+pub async fn record_failure<C: Context>(
+    ctx: &C,
+    ip_address: IpAddr,
+    username: &str,
+    user_id: Option<i64>,
+) -> ServiceResult<()> {
+    let settings = &AppSettings::get().login_lockouts;
+    for (scope, limit) in [
+        (LoginAttemptScope::Ip(ip_address), settings.ip_attempts),
+        (
+            LoginAttemptScope::Username(username),
+            settings.username_attempts,
+        ),
+    ] {
+        let window = settings.attempt_window.as_secs() as i64;
+        let failures =
+            match login_attempts::increment_failures(ctx, &scope.to_string(), window).await {
+                Ok(failures) => failures,
+                Err(e) => return unexpected(e),
+            };
+        if failures >= limit {
+            lock_out(ctx, scope, user_id).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Forgets the failed attempts against the username after a successful login.
+/// Attempts from the network are kept, so logging into another account doesn't reset them.
+pub async fn clear_failures<C: Context>(ctx: &C, username: &str) -> ServiceResult<()> {
+    let scope = LoginAttemptScope::Username(username);
+    match login_attempts::clear_failures(ctx, &scope.to_string()).await {
+        Ok(_) => Ok(()),
+        Err(e) => unexpected(e),
+    }
+}
+
+/// Lifts the lockout of the network or username and forgets its failed attempts,
+/// returning whether there was anything to forget.
+pub async fn unlock<C: Context>(ctx: &C, scope: LoginAttemptScope<'_>) -> ServiceResult<bool> {
+    match login_attempts::unlock(ctx, &scope.to_string()).await {
+        Ok(unlocked) => Ok(unlocked),
+        Err(e) => unexpected(e),
+    }
+}
+
+// utility
+
+async fn fetch_lock_ttl<C: Context>(ctx: &C, scope: &str) -> ServiceResult<Option<u64>> {
+    match login_attempts::fetch_lock_ttl(ctx, scope).await {
+        Ok(ttl) => Ok(ttl),
+        Err(e) => unexpected(e),
+    }
+}
+
+async fn lock_out<C: Context>(
+    ctx: &C,
+    scope: LoginAttemptScope<'_>,
+    user_id: Option<i64>,
+) -> ServiceResult<()> {
+    let key = scope.to_string();
+    let lockouts = match login_attempts::increment_lockouts(ctx, &key).await {
+        Ok(lockouts) => lockouts,
+        Err(e) => return unexpected(e),
+    };
+    let settings = &AppSettings::get().login_lockouts;
+    let duration = lockout_duration(lockouts, settings.lockout_base, settings.lockout_max);
+    if let Err(e) = login_attempts::lock(ctx, &key, duration.as_secs()).await {
+        return unexpected(e);
+    }
+    warn!(
+        scope = scope.as_str(),
+        lockouts,
+        duration_secs = duration.as_secs(),
+        "Locked out {key} after too many failed login attempts"
+    );
+
+    if let LoginAttemptScope::Username(username) = scope {
+        let settings = &AppSettings::get().login_lockouts;
+        if lockouts >= settings.notify_after {
+            notify_repeated_lockouts(username, user_id, lockouts, duration).await;
+        }
+    }
+    Ok(())
+}
+
+async fn notify_repeated_lockouts(
+    username: &str,
+    user_id: Option<i64>,
+    lockouts: u32,
+    duration: Duration,
+) {
+    let account = match user_id {
+        Some(user_id) => format!("[{username}]({})", website::get_profile_link(user_id)),
+        None => format!("{username} (no such account)"),
+    };
+    let notification = format!(
+        "{account} has been locked out {lockouts} times within a day \
+        after repeated failed login attempts. Logins are blocked for {}.",
+        format_duration(duration.as_secs()),
+    );
+    if let Err(e) =
+        discord::send_logs_red_embed("Repeated login lockouts", &notification, None).await
+    {
+        error!("Failed to send login lockout notice: {e:?}");
+    }
+}
+
+/// Locks out for the base duration, doubled for every lockout within the last day.
+fn lockout_duration(lockouts: u32, base: Duration, max: Duration) -> Duration {
+    let multiplier = 2u32.saturating_pow(lockouts.saturating_sub(1));
+    base.saturating_mul(multiplier).min(max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: Duration = Duration::from_secs(900);
+    const MAX: Duration = Duration::from_secs(86400);
+
+    #[test]
+    fn lockout_doubles_with_every_lockout() {
+        assert_eq!(lockout_duration(1, BASE, MAX), BASE);
+        assert_eq!(lockout_duration(2, BASE, MAX), Duration::from_secs(1800));
+        assert_eq!(lockout_duration(3, BASE, MAX), Duration::from_secs(3600));
+    }
+
+    #[test]
+    fn lockout_is_capped() {
+        assert_eq!(lockout_duration(8, BASE, MAX), MAX);
+        assert_eq!(lockout_duration(u32::MAX, BASE, MAX), MAX);
+    }
+}
@@ -0,0 +1,130 @@
+use crate::common::context::Context;
+use crate::common::error::{AppError, ServiceResult, unexpected};
+use crate::entities::bot;
+use crate::entities::login_notices::LoginNotice as Entity;
+use crate::models::login_notices::{LoginNotice, NoticeDelivery, NoticeTarget};
+use crate::models::sessions::Session;
+use crate::models::users::User;
+use crate::repositories::login_notices;
+use crate::usecases::users;
+use bancho_protocol::messages::Message;
+use bancho_protocol::messages::server::{Alert, ChatMessage};
+use bancho_protocol::structures::IrcMessage;
+use chrono::{DateTime, Utc};
+use tracing::error;
+
+const RESTRICTED_NOTICE: &str = "Your account is currently restricted. \
+You are not visible to other players and your scores will not be submitted.\n\
+Please contact staff if you believe this is a mistake.";
+
+pub async fn fetch_all<C: Context>(ctx: &C) -> ServiceResult<Vec<LoginNotice>> {
+    match login_notices::fetch_all(ctx).await {
+        Ok(notices) => Ok(parse_notices(notices)),
+        Err(e) => unexpected(e),
+    }
+}
+
+pub async fn create<C: Context>(
+    ctx: &C,
+    message: &str,
+    target: NoticeTarget,
+    delivery: NoticeDelivery,
+    starts_at: Option<DateTime<Utc>>,
+    ends_at: Option<DateTime<Utc>>,
+    created_by: i64,
+) -> ServiceResult<i64> {
+    match login_notices::create(
+        ctx,
+        message,
+        target.as_str(),
+        delivery.as_str(),
+        starts_at,
+        ends_at,
+        created_by,
+    )
+    .await
+    {
+        Ok(notice_id) => Ok(notice_id as i64),
+        Err(e) => unexpected(e),
+    }
+}
+
+pub async fn delete<C: Context>(ctx: &C, notice_id: i64) -> ServiceResult<()> {
+    match login_notices::delete(ctx, notice_id).await {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(AppError::LoginNoticesNotFound),
+        Err(e) => unexpected(e),
+    }
+}
+
+/// Serializes the notices shown to the user on login,
+/// starting with the ones explaining the state of their account.
+pub async fn fetch_login_data<C: Context>(ctx: &C, session: &Session) -> ServiceResult<Vec<u8>> {
+    let user = users::fetch_one(ctx, session.user_id).await?;
+    let mut data = vec![];
+    for message in account_state_notices(&user) {
+        data.extend(Message::serialize(Alert { message: &message }));
+    }
+
+    let notices = match login_notices::fetch_active(ctx).await {
+        Ok(notices) => parse_notices(notices),
+        Err(e) => return unexpected(e),
+    };
+    for notice in notices {
+        if notice.target.matches(&user) {
+            data.extend(serialize_notice(&notice, session));
+        }
+    }
+    Ok(data)
+}
+
+fn account_state_notices(user: &User) -> Vec<String> {
+    let mut notices = vec![];
+    if !user.privileges.is_publicly_visible() {
+        notices.push(RESTRICTED_NOTICE.to_owned());
+    }
+    if user.frozen {
+        let reason = user
+            .freeze_reason
+            .as_deref()
+            .filter(|reason| !reason.is_empty())
+            .unwrap_or("No reason was given.");
+        notices.push(format!(
+            "Your account has been frozen: {reason}\nPlease contact staff to get it unfrozen."
+        ));
+    }
+    notices
+}
+
+fn serialize_notice(notice: &LoginNotice, session: &Session) -> Vec<u8> {
+    match notice.delivery {
+        NoticeDelivery::Alert => Message::serialize(Alert {
+            message: &notice.message,
+        }),
+        NoticeDelivery::BotMessage => {
+            let bot_message = IrcMessage {
+                sender_id: bot::BOT_ID as _,
+                sender: bot::BOT_NAME,
+                recipient: &session.username,
+                text: &notice.message,
+            };
+            Message::serialize(ChatMessage(&bot_message))
+        }
+    }
+}
+
+fn parse_notices(notices: Vec<Entity>) -> Vec<LoginNotice> {
+    notices
+        .into_iter()
+        .filter_map(|notice| {
+            let notice_id = notice.id;
+            match LoginNotice::try_from(notice) {
+                Ok(notice) => Some(notice),
+                Err(e) => {
+                    error!(notice_id, "Skipping invalid login notice: {e:?}");
+                    None
+                }
+            }
+        })
+        .collect()
+}
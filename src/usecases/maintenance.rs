@@ -0,0 +1,207 @@
+use crate::common::chat::format_duration;
+use crate::common::context::Context;
+use crate::common::error::{ServiceResult, unexpected};
+use crate::entities::bot;
+use crate::models::maintenance::MaintenanceWindow;
+use crate::models::sessions::Session;
+use crate::repositories::maintenance;
+use crate::repositories::streams::StreamName;
+use crate::usecases::{bancho_settings, multiplayer, sessions, streams};
+use bancho_protocol::concat_messages;
+use bancho_protocol::messages::server::{Alert, ChatMessage};
+use bancho_protocol::structures::IrcMessage;
+use chrono::{DateTime, TimeDelta, Utc};
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+/// Seconds before the window starts at which the countdown is announced.
+const COUNTDOWN_STEPS: [i64; 9] = [3600, 1800, 900, 600, 300, 120, 60, 30, 10];
+/// How late a countdown step may still be announced, in case a poll was skipped.
+const COUNTDOWN_TOLERANCE_SECONDS: i64 = 5;
+/// Sessions whose match was in progress are kicked on a later sweep once it's over.
+const SWEEP_INTERVAL_SECONDS: i64 = 30;
+/// Claims are kept for a while after the window ends so that no step is repeated.
+const CLAIM_TTL_MARGIN_SECONDS: u64 = 60 * 60;
+
+pub async fn schedule<C: Context>(
+    ctx: &C,
+    starts_in: Duration,
+    length: Duration,
+    reason: &str,
+) -> ServiceResult<MaintenanceWindow> {
+    let now = Utc::now();
+    let starts_at = now + TimeDelta::seconds(starts_in.as_secs() as _);
+    let window = MaintenanceWindow {
+        starts_at,
+        ends_at: starts_at + TimeDelta::seconds(length.as_secs() as _),
+        reason: reason.to_owned(),
+    };
+    bancho_settings::set_maintenance_window(ctx, &window).await?;
+
+    let remaining_seconds = (window.starts_at - now).num_seconds();
+    let step = match countdown_step(remaining_seconds) {
+        Some(step) => format!("countdown:{step}"),
+        None => "scheduled".to_owned(),
+    };
+    announce_countdown(ctx, &window, remaining_seconds, &step, now).await?;
+    Ok(window)
+}
+
+/// Clears the scheduled window and turns maintenance mode off,
+/// returning whether a window was scheduled.
+pub async fn cancel<C: Context>(ctx: &C) -> ServiceResult<bool> {
+    let window = bancho_settings::fetch_maintenance_window(ctx).await?;
+    bancho_settings::clear_maintenance_window(ctx).await?;
+    bancho_settings::set_maintenance(ctx, false).await?;
+    if window.is_some() {
+        announce(ctx, "The scheduled maintenance has been cancelled.").await?;
+    }
+    Ok(window.is_some())
+}
+
+/// Advances the scheduled maintenance window. Safe to run concurrently on multiple replicas:
+/// every step is claimed in redis before it is acted on.
+pub async fn process_window<C: Context>(ctx: &C) -> ServiceResult<()> {
+    let Some(window) = bancho_settings::fetch_maintenance_window(ctx).await? else {
+        return Ok(());
+    };
+
+    let now = Utc::now();
+    if window.has_ended(now) {
+        if claim_step(ctx, &window, "ended", now).await? {
+            bancho_settings::set_maintenance(ctx, false).await?;
+            bancho_settings::clear_maintenance_window(ctx).await?;
+            info!("Maintenance window has ended");
+            announce(ctx, "Maintenance is over, welcome back!").await?;
+        }
+    } else if window.has_started(now) {
+        if claim_step(ctx, &window, "started", now).await? {
+            bancho_settings::set_maintenance(ctx, true).await?;
+            info!(reason = window.reason, "Maintenance window has started");
+            let remaining = (window.ends_at - now).num_seconds();
+            let message = format!(
+                "Akatsuki is now down for maintenance: {}\nWe expect to be back in {}.",
+                window.reason,
+                format_duration(remaining as _),
+            );
+            announce(ctx, &message).await?;
+        }
+
+        let sweep = now.timestamp() / SWEEP_INTERVAL_SECONDS;
+        if claim_step(ctx, &window, &format!("sweep:{sweep}"), now).await? {
+            let kicked_sessions = kick_sessions(ctx).await?;
+            if kicked_sessions != 0 {
+                info!(kicked_sessions, "Kicked sessions for maintenance");
+            }
+        }
+    } else {
+        let remaining_seconds = (window.starts_at - now).num_seconds();
+        if let Some(step) = countdown_step(remaining_seconds) {
+            let step = format!("countdown:{step}");
+            announce_countdown(ctx, &window, remaining_seconds, &step, now).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the message shown to users trying to log in during maintenance.
+pub async fn login_message<C: Context>(ctx: &C) -> Option<String> {
+    let window = match bancho_settings::fetch_maintenance_window(ctx).await {
+        Ok(window) => window?,
+        Err(e) => {
+            error!("Failed to fetch maintenance window: {e:?}");
+            return None;
+        }
+    };
+
+    let now = Utc::now();
+    if !window.has_started(now) || window.has_ended(now) {
+        return None;
+    }
+    let remaining = (window.ends_at - now).num_seconds();
+    Some(format!(
+        "Akatsuki is currently down for maintenance: {}\nWe expect to be back in {}.",
+        window.reason,
+        format_duration(remaining as _),
+    ))
+}
+
+fn countdown_step(remaining_seconds: i64) -> Option<i64> {
+    COUNTDOWN_STEPS.into_iter().find(|step| {
+        remaining_seconds <= *step && remaining_seconds > step - COUNTDOWN_TOLERANCE_SECONDS
+    })
+}
+
+async fn announce_countdown<C: Context>(
+    ctx: &C,
+    window: &MaintenanceWindow,
+    remaining_seconds: i64,
+    step: &str,
+    now: DateTime<Utc>,
+) -> ServiceResult<()> {
+    if !claim_step(ctx, window, step, now).await? {
+        return Ok(());
+    }
+
+    let length = (window.ends_at - window.starts_at).num_seconds();
+    let message = format!(
+        "Akatsuki is going down for maintenance in {} for about {}: {}",
+        format_duration(remaining_seconds.max(0) as _),
+        format_duration(length as _),
+        window.reason,
+    );
+    announce(ctx, &message).await
+}
+
+async fn claim_step<C: Context>(
+    ctx: &C,
+    window: &MaintenanceWindow,
+    step: &str,
+    now: DateTime<Utc>,
+) -> ServiceResult<bool> {
+    let ttl = (window.ends_at - now).num_seconds().max(0) as u64 + CLAIM_TTL_MARGIN_SECONDS;
+    match maintenance::claim_step(ctx, window.starts_at.timestamp(), step, ttl).await {
+        Ok(claimed) => Ok(claimed),
+        Err(e) => unexpected(e),
+    }
+}
+
+/// Sends the message to `#announce` and as a notification to everyone online.
+async fn announce<C: Context>(ctx: &C, message: &str) -> ServiceResult<()> {
+    let bot_message = IrcMessage {
+        sender_id: bot::BOT_ID as _,
+        sender: bot::BOT_NAME,
+        recipient: "#announce",
+        text: message,
+    };
+    let data = concat_messages!(ChatMessage(&bot_message), Alert { message });
+    streams::broadcast_data(ctx, StreamName::Main, &data, None, None).await?;
+    Ok(())
+}
+
+/// Kicks every session of non-staff users, unless they are playing in a multiplayer match.
+async fn kick_sessions<C: Context>(ctx: &C) -> ServiceResult<usize> {
+    let mut kicked_sessions = 0;
+    for session in sessions::fetch_all(ctx).await? {
+        if session.privileges.is_staff() || is_playing_match(ctx, &session).await? {
+            continue;
+        }
+
+        match sessions::delete(ctx, &session).await {
+            Ok(_) => kicked_sessions += 1,
+            Err(e) => warn!(
+                user_id = session.user_id,
+                "Failed to kick session for maintenance: {e:?}"
+            ),
+        }
+    }
+    Ok(kicked_sessions)
+}
+
+async fn is_playing_match<C: Context>(ctx: &C, session: &Session) -> ServiceResult<bool> {
+    let Some(match_id) = multiplayer::fetch_session_match_id(ctx, session.session_id).await? else {
+        return Ok(false);
+    };
+    let mp_match = multiplayer::fetch_one(ctx, match_id).await?;
+    Ok(mp_match.in_progress)
+}
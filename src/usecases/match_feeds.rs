@@ -0,0 +1,47 @@
+use crate::common::context::Context;
+use crate::common::error::{ServiceResult, unexpected};
+use crate::models::match_feeds::MatchFeedEvent;
+use crate::repositories::match_feeds;
+use crate::repositories::match_feeds::MatchFeedEntry;
+use redis::aio::MultiplexedConnection;
+use std::time::Duration;
+
+pub async fn publish<C: Context>(
+    ctx: &C,
+    match_id: i64,
+    event: &MatchFeedEvent,
+) -> ServiceResult<()> {
+    let event = serde_json::to_string(event)?;
+    match match_feeds::publish(ctx, match_id, &event).await {
+        Ok(_) => Ok(()),
+        Err(e) => unexpected(e),
+    }
+}
+
+/// Publishes the final event of a match and lets its feed expire.
+pub async fn close<C: Context>(ctx: &C, match_id: i64) -> ServiceResult<()> {
+    publish(ctx, match_id, &MatchFeedEvent::Disband).await?;
+    match match_feeds::expire(ctx, match_id).await {
+        Ok(_) => Ok(()),
+        Err(e) => unexpected(e),
+    }
+}
+
+pub async fn fetch_latest_event_id<C: Context>(ctx: &C, match_id: i64) -> ServiceResult<String> {
+    match match_feeds::fetch_latest_event_id(ctx, match_id).await {
+        Ok(event_id) => Ok(event_id),
+        Err(e) => unexpected(e),
+    }
+}
+
+pub async fn wait_for_events(
+    redis: &mut MultiplexedConnection,
+    match_id: i64,
+    last_event_id: &str,
+    timeout: Duration,
+) -> ServiceResult<Vec<MatchFeedEntry>> {
+    match match_feeds::wait_for_events(redis, match_id, last_event_id, timeout).await {
+        Ok(entries) => Ok(entries),
+        Err(e) => unexpected(e),
+    }
+}
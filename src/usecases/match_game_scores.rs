@@ -0,0 +1,100 @@
+use crate::common::context::Context;
+use crate::common::error::{ServiceResult, unexpected};
+use crate::entities::gamemodes::Gamemode;
+use crate::entities::match_game_scores::MatchGameScore;
+use crate::repositories::{match_game_scores, match_games};
+use bancho_protocol::structures::{MatchTeamType, WinCondition};
+use hashbrown::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameWinner {
+    /// Attached is the team as stored on the slot
+    Team(u8),
+    User(i64),
+}
+
+pub async fn save<C: Context>(ctx: &C, score: MatchGameScore) -> ServiceResult<()> {
+    match match_game_scores::save(ctx, &score).await {
+        Ok(_) => Ok(()),
+        Err(e) => unexpected(e),
+    }
+}
+
+pub async fn fetch_all<C: Context>(ctx: &C, game_id: i64) -> ServiceResult<Vec<MatchGameScore>> {
+    match match_game_scores::fetch_all(ctx, game_id).await {
+        Ok(scores) => Ok(scores),
+        Err(e) => unexpected(e),
+    }
+}
+
+/// Determines and stores the winner of a game from its recorded scores.
+pub async fn finish_game<C: Context>(
+    ctx: &C,
+    game_id: i64,
+    mode: Gamemode,
+    win_condition: WinCondition,
+    team_type: MatchTeamType,
+) -> ServiceResult<Option<GameWinner>> {
+    let scores = fetch_all(ctx, game_id).await?;
+    let winner = determine_winner(&scores, mode, win_condition, team_type);
+    let (winning_team, winning_user_id) = match winner {
+        Some(GameWinner::Team(team)) => (Some(team), None),
+        Some(GameWinner::User(user_id)) => (None, Some(user_id)),
+        None => (None, None),
+    };
+    match match_games::set_winner(ctx, game_id, winning_team, winning_user_id).await {
+        Ok(_) => Ok(winner),
+        Err(e) => unexpected(e),
+    }
+}
+
+/// Ties have no winner.
+pub fn determine_winner(
+    scores: &[MatchGameScore],
+    mode: Gamemode,
+    win_condition: WinCondition,
+    team_type: MatchTeamType,
+) -> Option<GameWinner> {
+    let value_of = |score: &MatchGameScore| match win_condition {
+        WinCondition::Accuracy => score.accuracy(mode),
+        WinCondition::Combo => score.max_combo as f64,
+        _ => score.score as f64,
+    };
+
+    let results: Vec<(GameWinner, f64)> = match team_type {
+        MatchTeamType::Vs | MatchTeamType::TagVs => {
+            let mut teams: HashMap<u8, (f64, usize)> = HashMap::new();
+            for score in scores {
+                let (total, count) = teams.entry(score.team).or_default();
+                *total += value_of(score);
+                *count += 1;
+            }
+            teams
+                .into_iter()
+                .map(|(team, (total, count))| {
+                    let value = match win_condition {
+                        WinCondition::Accuracy => total / count as f64,
+                        _ => total,
+                    };
+                    (GameWinner::Team(team), value)
+                })
+                .collect()
+        }
+        _ => scores
+            .iter()
+            .map(|score| (GameWinner::User(score.user_id), value_of(score)))
+            .collect(),
+    };
+
+    let (winner, best) = results
+        .iter()
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .copied()?;
+    let tied = results
+        .iter()
+        .any(|(other, value)| *other != winner && *value == best);
+    match tied {
+        true => None,
+        false => Some(winner),
+    }
+}
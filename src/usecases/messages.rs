@@ -5,15 +5,12 @@ use crate::entities::bot;
 use crate::entities::channels::ChannelName;
 use crate::models::messages::{Message, MessageSendResult, Recipient};
 use crate::models::privileges::Privileges;
+use crate::models::rate_limits::{RateLimitAction, RateLimitScope};
 use crate::models::sessions::Session;
 use crate::repositories::messages;
-use crate::usecases::{channels, relationships, users};
-use chrono::{TimeDelta, Utc};
+use crate::usecases::{channels, chat_filters, rate_limits, relationships, users};
 use tracing::error;
 
-const CHAT_SPAM_RATE_INTERVAL: u64 = 10;
-const CHAT_SPAM_RATE: i64 = 10;
-const CHAT_TIMEOUT_SECONDS: i64 = 5 * 60;
 const CHAT_TIMEOUT_REASON: &str = "Spamming (Auto-Silence)";
 
 pub async fn fetch_unread_messages<C: Context>(
@@ -26,6 +23,20 @@ pub async fn fetch_unread_messages<C: Context>(
     }
 }
 
+/// Returns the most recent messages sent to the channel by visible users, oldest first.
+/// Commands are left out, they may contain secrets (e.g. match passwords).
+pub async fn fetch_channel_history<C: Context>(
+    ctx: &C,
+    channel_name: ChannelName<'_>,
+    limit: usize,
+) -> ServiceResult<Vec<Message>> {
+    match messages::fetch_channel_history(ctx, channel_name, commands::COMMAND_PREFIX, limit).await
+    {
+        Ok(messages) => Ok(messages.into_iter().rev().map(Message::from).collect()),
+        Err(e) => unexpected(e),
+    }
+}
+
 pub async fn mark_all_read<C: Context>(ctx: &C, recipient_id: i64) -> ServiceResult<()> {
     match messages::mark_all_read(ctx, recipient_id).await {
         Ok(()) => Ok(()),
@@ -60,21 +71,26 @@ impl Default for RecipientInfo<'_> {
     }
 }
 
-pub async fn check_spam<C: Context>(ctx: &C, session: &mut Session) -> ServiceResult<()> {
-    let message_count =
-        messages::message_count(ctx, session.user_id, CHAT_SPAM_RATE_INTERVAL).await?;
-    if message_count < CHAT_SPAM_RATE {
-        return Ok(());
-    }
-
-    session.silence_end = Some(Utc::now() + TimeDelta::seconds(CHAT_TIMEOUT_SECONDS));
-    users::silence_user(
+pub async fn check_spam<C: Context>(
+    ctx: &C,
+    session: &mut Session,
+    recipient: &Recipient<'_>,
+) -> ServiceResult<()> {
+    let mut allowed = rate_limits::hit(
         ctx,
-        session.user_id,
-        CHAT_TIMEOUT_REASON,
-        CHAT_TIMEOUT_SECONDS,
+        RateLimitAction::Chat,
+        RateLimitScope::User(session.user_id),
     )
     .await?;
+    if allowed && let Recipient::Channel(channel_name) = recipient {
+        let scope = RateLimitScope::Channel(session.user_id, *channel_name);
+        allowed = rate_limits::hit(ctx, RateLimitAction::ChannelChat, scope).await?;
+    }
+    if allowed {
+        return Ok(());
+    }
+
+    rate_limits::silence_offender(ctx, session, CHAT_TIMEOUT_REASON).await?;
     Err(AppError::MessagesUserAutoSilenced)
 }
 
@@ -97,8 +113,22 @@ pub async fn send<C: Context>(
         return Err(AppError::MessagesInvalidLength);
     }
 
-    check_spam(ctx, session).await?;
+    // rejected messages don't count towards the rate limits
     let recipient_info = get_recipient_info(ctx, session, &recipient).await?;
+    check_spam(ctx, session, recipient).await?;
+
+    // staff, commands and messages sent to the bot are not filtered
+    let unfiltered = session.privileges.is_staff()
+        || recipient.is_bot()
+        || commands::is_command_message(message_content);
+    let filter_result = match unfiltered {
+        true => None,
+        false => Some(chat_filters::apply(ctx, session, message_content).await?),
+    };
+    let message_content = match &filter_result {
+        Some(filter_result) => filter_result.content.as_str(),
+        None => message_content,
+    };
     let message = messages::send(
         ctx,
         session.user_id,
@@ -111,6 +141,30 @@ pub async fn send<C: Context>(
     .await
     .map(Message::from)?;
 
+    if let Some(filter_result) = &filter_result
+        && !filter_result.flagged_by.is_empty()
+    {
+        let recipient_name = match (
+            &recipient_info.recipient_channel,
+            recipient_info.recipient_id,
+        ) {
+            (Some(channel_name), _) => channel_name.to_string(),
+            (None, Some(recipient_id)) => format!("user {recipient_id}"),
+            (None, None) => "unknown".to_string(),
+        };
+        if let Err(e) = chat_filters::report_flagged(
+            ctx,
+            session,
+            &recipient_name,
+            message_content,
+            &filter_result.flagged_by,
+        )
+        .await
+        {
+            error!("Failed to report flagged message: {e:?}");
+        }
+    }
+
     let response = commands::try_handle_command(ctx, session, message_content, &recipient).await?;
     Ok(MessageSendResult { message, response })
 }
@@ -126,6 +180,10 @@ async fn get_recipient_info<'a, C: Context>(
             if !channel.can_write(sender.privileges) {
                 return Err(AppError::ChannelsUnauthorized);
             }
+            if channels::is_banned(ctx, &channel, sender.user_id).await? {
+                return Err(AppError::ChannelsBanned);
+            }
+            channels::check_can_send(ctx, sender, *channel_name, &channel).await?;
 
             Ok(RecipientInfo {
                 recipient_channel: Some(*channel_name),
@@ -0,0 +1,40 @@
+use crate::common::context::Context;
+use crate::common::error::ServiceResult;
+use crate::common::metrics::{
+    ACTIVE_MATCHES, ONLINE_PRESENCES, ONLINE_SESSIONS, SPECTATOR_HOSTS, STREAM_LENGTH,
+};
+use crate::repositories::streams::StreamName;
+use crate::repositories::{multiplayer, presences, sessions, spectators, streams};
+use hashbrown::HashMap;
+use metrics::gauge;
+use tracing::warn;
+
+/// Records gauges for the current online state, stored in redis.
+pub async fn record_online_state<C: Context>(ctx: &C) -> ServiceResult<()> {
+    gauge!(ONLINE_SESSIONS).set(sessions::fetch_count(ctx).await? as f64);
+    gauge!(ONLINE_PRESENCES).set(presences::fetch_count(ctx).await? as f64);
+    gauge!(ACTIVE_MATCHES).set(multiplayer::fetch_count(ctx).await? as f64);
+    gauge!(SPECTATOR_HOSTS).set(spectators::fetch_host_count(ctx).await? as f64);
+    Ok(())
+}
+
+/// Records the total length of all streams, grouped by stream kind.
+pub async fn record_stream_lengths<C: Context>(ctx: &C) -> ServiceResult<()> {
+    let mut lengths: HashMap<&'static str, usize> = HashMap::new();
+    for key in streams::fetch_all(ctx).await? {
+        let stream_name = match StreamName::from_key(&key) {
+            Ok(stream_name) => stream_name,
+            Err(_) => {
+                warn!(key, "Skipping stream with an invalid key");
+                continue;
+            }
+        };
+        let length = streams::fetch_length(ctx, stream_name).await?;
+        *lengths.entry(stream_name.kind()).or_default() += length;
+    }
+
+    for (kind, length) in lengths {
+        gauge!(STREAM_LENGTH, "stream" => kind).set(length as f64);
+    }
+    Ok(())
+}
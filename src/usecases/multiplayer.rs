@@ -1,20 +1,29 @@
+use crate::adapters::performance_service::PerformanceRequest;
+use crate::adapters::{beatmaps_service, performance_service};
 use crate::common::context::Context;
 use crate::common::error::{AppError, ServiceResult, unexpected};
-use crate::common::state::AppState;
 use crate::entities::bot;
 use crate::entities::channels::ChannelName;
 use crate::entities::gamemodes::Gamemode;
 use crate::entities::match_events::MatchEventType;
+use crate::entities::match_game_scores::{MatchGameScore, MatchScoreFrame};
+use crate::entities::multiplayer::MapConstraints;
 use crate::entities::multiplayer::MultiplayerMatchSlot as SlotEntity;
 use crate::entities::sessions::SessionIdentity;
+use crate::models::beatmaps::RankedStatus;
+use crate::models::match_feeds::MatchFeedEvent;
 use crate::models::multiplayer::MatchSlotExt;
 use crate::models::multiplayer::{MultiplayerMatch, MultiplayerMatchSlot, MultiplayerMatchSlots};
 use crate::models::presences::PresenceStats;
+use crate::models::rate_limits::{RateLimitAction, RateLimitScope};
 use crate::models::sessions::Session;
-use crate::repositories::multiplayer::TimerType;
+use crate::repositories::multiplayer::{MultiplayerTimer, TimerType};
 use crate::repositories::streams::StreamName;
 use crate::repositories::{match_games, multiplayer};
-use crate::usecases::{channels, match_events, presences, sessions, stats, streams};
+use crate::usecases::{
+    beatmaps, channels, match_events, match_feeds, match_game_scores, presences, rate_limits,
+    sessions, stats, streams, tournaments,
+};
 use bancho_protocol::concat_messages;
 use bancho_protocol::messages::MessageArgs;
 use bancho_protocol::messages::server::{
@@ -23,8 +32,8 @@ use bancho_protocol::messages::server::{
     MatchUpdate,
 };
 use bancho_protocol::serde::BinarySerialize;
-use bancho_protocol::structures::{IrcMessage, Match, MatchTeam, Mods, SlotStatus};
-use std::time::Duration;
+use bancho_protocol::structures::{Action, IrcMessage, Match, MatchTeam, Mode, Mods, SlotStatus};
+use chrono::{DateTime, TimeDelta, Utc};
 use tracing::error;
 use uuid::Uuid;
 
@@ -39,6 +48,9 @@ pub async fn create<C: Context>(
     mode: Gamemode,
     max_player_count: usize,
 ) -> ServiceResult<MultiplayerMatch> {
+    let scope = RateLimitScope::User(host_session.user_id);
+    rate_limits::check(ctx, RateLimitAction::MatchCreate, scope).await?;
+
     if let Some(match_id) =
         multiplayer::fetch_session_match_id(ctx, host_session.session_id).await?
     {
@@ -179,6 +191,8 @@ pub async fn delete<C: Context>(ctx: &C, match_id: i64) -> ServiceResult<()> {
     channels::close(ctx, ChannelName::Multiplayer(match_id)).await?;
     streams::clear_stream(ctx, StreamName::Multiplayer(match_id)).await?;
     streams::clear_stream(ctx, StreamName::Multiplaying(match_id)).await?;
+    match_feeds::close(ctx, match_id).await?;
+    tournaments::delete(ctx, match_id).await?;
     match_events::create(ctx, match_id, MatchEventType::MatchDisbanded, None, None).await?;
     streams::broadcast_message(
         ctx,
@@ -267,6 +281,7 @@ pub async fn join<C: Context>(
         None,
     )
     .await;
+    multiplayer::enqueue_host(ctx, match_id, session.user_id).await?;
 
     streams::join(
         ctx,
@@ -312,6 +327,7 @@ pub async fn leave<C: Context>(
         None,
     )
     .await;
+    multiplayer::dequeue_host(ctx, match_id, session.user_id).await?;
 
     streams::leave(
         ctx,
@@ -336,15 +352,35 @@ pub async fn leave<C: Context>(
         delete(ctx, match_id).await?;
     } else {
         if mp_match.host_user_id == session.user_id {
-            match slots.iter().filter_map(|slot| slot.user).next() {
-                Some(next_host) => {
-                    mp_match.host_user_id = next_host.user_id;
+            // with auto host enabled, the next user in the queue takes over
+            let next_host_user_id = match multiplayer::is_auto_host(ctx, match_id).await? {
+                true => multiplayer::fetch_host_queue(ctx, match_id)
+                    .await?
+                    .into_iter()
+                    .find(|user_id| {
+                        slots.iter().any(|slot| {
+                            slot.user
+                                .is_some_and(|slot_user| slot_user.user_id == *user_id)
+                        })
+                    }),
+                false => None,
+            };
+            let next_host_user_id = next_host_user_id.or_else(|| {
+                slots
+                    .iter()
+                    .filter_map(|slot| slot.user)
+                    .next()
+                    .map(|slot_user| slot_user.user_id)
+            });
+            match next_host_user_id {
+                Some(next_host_user_id) => {
+                    mp_match.host_user_id = next_host_user_id;
                     multiplayer::update(ctx, mp_match.as_entity(), false).await?;
                     let _ = match_events::create(
                         ctx,
                         mp_match.match_id,
                         MatchEventType::MatchHostAssignment,
-                        Some(next_host.user_id),
+                        Some(next_host_user_id),
                         None,
                     )
                     .await;
@@ -378,6 +414,15 @@ pub async fn update_bancho<C: Context>(
         return Err(AppError::MultiplayerUnauthorized);
     }
 
+    // referees are not bound to the map constraints of their match
+    let beatmap_changed = args.beatmap_id > 0 && args.beatmap_id != mp_match.beatmap_id;
+    let rejection = match check_host {
+        Some(user_id) if beatmap_changed && !is_referee(ctx, match_id, user_id).await? => {
+            check_map_constraints(ctx, match_id, args.beatmap_id).await?
+        }
+        _ => None,
+    };
+
     let update_name = mp_match.name != args.name;
     let update_private = mp_match.password.is_empty() != args.password.is_empty();
     if mp_match.password != args.password {
@@ -386,11 +431,13 @@ pub async fn update_bancho<C: Context>(
     if update_name {
         mp_match.name = args.name.to_string();
     }
-    if mp_match.beatmap_name != args.beatmap_name {
-        mp_match.beatmap_name = args.beatmap_name.to_string();
-        mp_match.beatmap_md5 = args.beatmap_md5.to_string();
+    if rejection.is_none() {
+        if mp_match.beatmap_name != args.beatmap_name {
+            mp_match.beatmap_name = args.beatmap_name.to_string();
+            mp_match.beatmap_md5 = args.beatmap_md5.to_string();
+        }
+        mp_match.beatmap_id = args.beatmap_id;
     }
-    mp_match.beatmap_id = args.beatmap_id;
 
     let match_mods = mp_match.mods;
     let mut slots = multiplayer::fetch_all_slots(ctx, mp_match.match_id).await?;
@@ -425,9 +472,137 @@ pub async fn update_bancho<C: Context>(
 
     broadcast_update(ctx, &mp_match, MultiplayerMatchSlot::from(slots)).await?;
     let mp_match = multiplayer::update(ctx, mp_match.into(), update_name || update_private).await?;
+    if let Some(rejection) = rejection {
+        let message = format!("This map can't be picked, {rejection}.");
+        send_bot_message(ctx, match_id, &message).await?;
+    }
     Ok(MultiplayerMatch::try_from(mp_match)?)
 }
 
+pub async fn fetch_constraints<C: Context>(
+    ctx: &C,
+    match_id: i64,
+) -> ServiceResult<MapConstraints> {
+    let constraints = multiplayer::fetch_constraints(ctx, match_id).await?;
+    Ok(constraints.unwrap_or_default())
+}
+
+pub async fn update_constraints<C: Context>(
+    ctx: &C,
+    match_id: i64,
+    constraints: &MapConstraints,
+) -> ServiceResult<()> {
+    multiplayer::set_constraints(ctx, match_id, constraints).await?;
+    Ok(())
+}
+
+/// Returns the reason why the beatmap can't be picked in the match, if any.
+pub async fn check_map_constraints<C: Context>(
+    ctx: &C,
+    match_id: i64,
+    beatmap_id: i32,
+) -> ServiceResult<Option<String>> {
+    let constraints = fetch_constraints(ctx, match_id).await?;
+    if constraints.is_empty() {
+        return Ok(None);
+    }
+
+    let beatmap = match beatmaps_service::fetch_by_id(beatmap_id).await {
+        Ok(beatmap) => beatmap,
+        Err(AppError::BeatmapsNotFound) => {
+            return Ok(Some("the beatmap could not be found".to_string()));
+        }
+        Err(e) => return Err(e),
+    };
+
+    if let Some(mode) = constraints.mode
+        && beatmap.mode != mode
+    {
+        return Ok(Some(format!("only {} maps are allowed", mode_name(mode))));
+    }
+
+    let ranked_status = RankedStatus::from(beatmap.ranked);
+    if constraints.ranked_only
+        && !matches!(ranked_status, RankedStatus::Ranked | RankedStatus::Approved)
+    {
+        return Ok(Some("only ranked maps are allowed".to_string()));
+    }
+
+    if let Some(max_length) = constraints.max_length
+        && beatmap.hit_length > max_length
+    {
+        return Ok(Some(format!(
+            "maps must be shorter than {}",
+            format_length(max_length)
+        )));
+    }
+
+    if constraints.min_stars.is_none() && constraints.max_stars.is_none() {
+        return Ok(None);
+    }
+    let request = PerformanceRequest {
+        beatmap_id: beatmap.beatmap_id,
+        beatmap_md5: beatmap.beatmap_md5,
+        mode: beatmap.mode as _,
+        mods: 0,
+        max_combo: beatmap.max_combo,
+        accuracy: 100.0,
+        miss_count: 0,
+    };
+    let stars = match performance_service::calculate_pp(&[request]).await?.first() {
+        Some(result) => result.stars,
+        None => return Err(AppError::Unexpected),
+    };
+    if let Some(min_stars) = constraints.min_stars
+        && stars < min_stars
+    {
+        return Ok(Some(format!("maps must be at least {min_stars:.2}★")));
+    }
+    if let Some(max_stars) = constraints.max_stars
+        && stars > max_stars
+    {
+        return Ok(Some(format!("maps must be at most {max_stars:.2}★")));
+    }
+    Ok(None)
+}
+
+pub fn format_constraints(constraints: &MapConstraints) -> String {
+    if constraints.is_empty() {
+        return "none".to_string();
+    }
+
+    let mut parts = vec![];
+    match (constraints.min_stars, constraints.max_stars) {
+        (Some(min_stars), Some(max_stars)) => parts.push(format!("{min_stars:.2}-{max_stars:.2}★")),
+        (Some(min_stars), None) => parts.push(format!("at least {min_stars:.2}★")),
+        (None, Some(max_stars)) => parts.push(format!("at most {max_stars:.2}★")),
+        (None, None) => {}
+    }
+    if let Some(max_length) = constraints.max_length {
+        parts.push(format!("shorter than {}", format_length(max_length)));
+    }
+    if let Some(mode) = constraints.mode {
+        parts.push(format!("{} only", mode_name(mode)));
+    }
+    if constraints.ranked_only {
+        parts.push("ranked only".to_string());
+    }
+    parts.join(", ")
+}
+
+fn mode_name(mode: u8) -> &'static str {
+    match mode {
+        1 => "osu!taiko",
+        2 => "osu!catch",
+        3 => "osu!mania",
+        _ => "osu!",
+    }
+}
+
+fn format_length(seconds: i32) -> String {
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
 pub async fn fetch_user_slot<C: Context>(
     ctx: &C,
     match_id: i64,
@@ -772,6 +947,7 @@ pub async fn start_game<C: Context>(
         }
     }
 
+    multiplayer::clear_score_frames(ctx, match_id).await?;
     let game_id = match_games::create(
         ctx,
         match_id,
@@ -813,9 +989,58 @@ pub async fn start_game<C: Context>(
         None,
     )
     .await?;
+    if let Err(e) = match_feeds::publish(
+        ctx,
+        match_id,
+        &MatchFeedEvent::Start {
+            game_id,
+            beatmap_id: mp_match.beatmap_id,
+            beatmap_md5: mp_match.beatmap_md5,
+            mods: mp_match.mods.bits(),
+            mode: mp_match.mode as _,
+        },
+    )
+    .await
+    {
+        error!(match_id, "Failed to publish match feed event: {e:?}");
+    }
     Ok(())
 }
 
+/// Changes the beatmap of the match, `mode` only applies to osu!standard beatmaps.
+pub async fn change_beatmap<C: Context>(
+    ctx: &C,
+    match_id: i64,
+    beatmap_id: i32,
+    mode: Option<Mode>,
+) -> ServiceResult<MultiplayerMatch> {
+    let mut mp_match = fetch_one(ctx, match_id).await?;
+
+    // Fetch the beatmap to get its details
+    let beatmap = beatmaps::fetch_by_id(ctx, beatmap_id).await?;
+    mp_match.beatmap_id = beatmap.beatmap_id;
+    mp_match.beatmap_name = beatmap.song_name;
+    mp_match.beatmap_md5 = beatmap.beatmap_md5;
+
+    let new_mode = match mode {
+        Some(mode) if beatmap.mode == Mode::Standard => mode,
+        _ => beatmap.mode,
+    };
+
+    // osu! mode changed, reset mods.
+    if mp_match.mode.as_bancho() != new_mode {
+        mp_match.mods = Mods::None;
+        let mut slots = fetch_all_slots(ctx, match_id).await?;
+        for slot in &mut slots {
+            slot.mods = Mods::None;
+        }
+        update_all_slots(ctx, match_id, slots).await?;
+    }
+
+    mp_match.mode = Gamemode::from_mode_and_mods(new_mode, mp_match.mods);
+    update(ctx, mp_match).await
+}
+
 pub async fn end_game<C: Context>(ctx: &C, match_id: i64) -> ServiceResult<()> {
     streams::broadcast_message(
         ctx,
@@ -831,6 +1056,12 @@ pub async fn end_game<C: Context>(ctx: &C, match_id: i64) -> ServiceResult<()> {
         .await?
         .ok_or(AppError::MultiplayerNotFound)?;
     let mut slots = multiplayer::fetch_all_slots(ctx, match_id).await?;
+    // players who were not ready for this game are skipped by the host rotation
+    let participant_ids: Vec<i64> = slots
+        .iter()
+        .filter(|slot| slot.status == SlotStatus::Playing.bits() || slot.completed)
+        .filter_map(|slot| slot.user.map(|slot_user| slot_user.user_id))
+        .collect();
     mp_match.in_progress = false;
     slots.iter_mut().for_each(|slot| {
         if slot.user.is_some() {
@@ -844,9 +1075,101 @@ pub async fn end_game<C: Context>(ctx: &C, match_id: i64) -> ServiceResult<()> {
     let mp_match = MultiplayerMatch::try_from(mp_match)?;
     let slots = MultiplayerMatchSlot::from(slots);
     broadcast_update(ctx, &mp_match, slots).await?;
+    if let Some(game_id) = mp_match.last_game_id {
+        let winner = match_game_scores::finish_game(
+            ctx,
+            game_id,
+            mp_match.mode,
+            mp_match.win_condition,
+            mp_match.team_type,
+        )
+        .await?;
+        tournaments::record_game_result(ctx, match_id, game_id, mp_match.beatmap_id, winner)
+            .await?;
+    }
+    if let Err(e) = match_feeds::publish(
+        ctx,
+        match_id,
+        &MatchFeedEvent::Complete {
+            game_id: mp_match.last_game_id,
+        },
+    )
+    .await
+    {
+        error!(match_id, "Failed to publish match feed event: {e:?}");
+    }
+    if multiplayer::is_auto_host(ctx, match_id).await? {
+        rotate_host(ctx, match_id, &participant_ids).await?;
+    }
     Ok(())
 }
 
+pub async fn is_auto_host<C: Context>(ctx: &C, match_id: i64) -> ServiceResult<bool> {
+    Ok(multiplayer::is_auto_host(ctx, match_id).await?)
+}
+
+/// Toggles auto host rotation. The queue starts with the current host,
+/// followed by the other players in slot order.
+pub async fn set_auto_host<C: Context>(ctx: &C, match_id: i64, enabled: bool) -> ServiceResult<()> {
+    if !enabled {
+        multiplayer::disable_auto_host(ctx, match_id).await?;
+        return Ok(());
+    }
+
+    let mp_match = fetch_one(ctx, match_id).await?;
+    let slots = fetch_all_slots(ctx, match_id).await?;
+    let host_queue: Vec<i64> = std::iter::once(mp_match.host_user_id)
+        .chain(
+            slots
+                .iter()
+                .filter_map(|slot| slot.user.map(|slot_user| slot_user.user_id))
+                .filter(|user_id| *user_id != mp_match.host_user_id),
+        )
+        .collect();
+    multiplayer::enable_auto_host(ctx, match_id, &host_queue).await?;
+    Ok(())
+}
+
+pub async fn fetch_host_queue<C: Context>(ctx: &C, match_id: i64) -> ServiceResult<Vec<i64>> {
+    Ok(multiplayer::fetch_host_queue(ctx, match_id).await?)
+}
+
+/// Passes host to the next player in the queue,
+/// skipping players who are AFK or did not play the last game.
+async fn rotate_host<C: Context>(
+    ctx: &C,
+    match_id: i64,
+    participant_ids: &[i64],
+) -> ServiceResult<()> {
+    let queue_length = multiplayer::fetch_host_queue(ctx, match_id).await?.len();
+    let mut next_host_user_id = None;
+    for _ in 0..queue_length {
+        let Some(candidate_id) = multiplayer::rotate_host_queue(ctx, match_id).await? else {
+            break;
+        };
+        let is_afk = match presences::fetch_one(ctx, candidate_id).await {
+            Ok(presence) => presence.action.action == Action::Afk,
+            Err(_) => true,
+        };
+        if !is_afk && participant_ids.contains(&candidate_id) {
+            next_host_user_id = Some(candidate_id);
+            break;
+        }
+    }
+
+    let Some(next_host_user_id) = next_host_user_id else {
+        return Ok(());
+    };
+    let mp_match = fetch_one(ctx, match_id).await?;
+    if mp_match.host_user_id == next_host_user_id {
+        return Ok(());
+    }
+    transfer_host_to_user(ctx, match_id, next_host_user_id, None).await?;
+    let presence = presences::fetch_one(ctx, next_host_user_id).await?;
+    let message = format!("{} is now the host.", presence.username);
+    send_bot_message(ctx, match_id, &message).await
+}
+
 pub async fn player_loaded<C: Context>(ctx: &C, session: &Session) -> ServiceResult<bool> {
     let match_id = fetch_session_match_id(ctx, session.session_id)
         .await?
@@ -907,6 +1230,8 @@ pub async fn player_failed<C: Context>(ctx: &C, session: &Session) -> ServiceRes
         None,
     )
     .await?;
+    let (slot_id, slot) = fetch_session_slot(ctx, match_id, session.session_id).await?;
+    save_game_score(ctx, match_id, slot_id, &slot, false).await?;
     Ok(all_failed)
 }
 
@@ -919,6 +1244,8 @@ pub async fn player_completed<C: Context>(ctx: &C, session: &Session) -> Service
             &mut slot.completed
         })
         .await?;
+    let (slot_id, slot) = fetch_session_slot(ctx, match_id, session.session_id).await?;
+    save_game_score(ctx, match_id, slot_id, &slot, !slot.failed).await?;
     if all_completed {
         end_game(ctx, match_id).await?;
     }
@@ -926,6 +1253,60 @@ pub async fn player_completed<C: Context>(ctx: &C, session: &Session) -> Service
     Ok(all_completed)
 }
 
+/// Keeps the latest score frame of a player, so their final result
+/// can be stored once they finish playing.
+pub async fn record_score_frame<C: Context>(
+    ctx: &C,
+    match_id: i64,
+    frame: MatchScoreFrame,
+) -> ServiceResult<()> {
+    multiplayer::set_score_frame(ctx, match_id, frame.slot_id as _, frame).await?;
+    Ok(())
+}
+
+async fn save_game_score<C: Context>(
+    ctx: &C,
+    match_id: i64,
+    slot_id: usize,
+    slot: &MultiplayerMatchSlot,
+    passed: bool,
+) -> ServiceResult<()> {
+    let mp_match = multiplayer::fetch_one(ctx, match_id)
+        .await?
+        .ok_or(AppError::MultiplayerNotFound)?;
+    let Some(game_id) = mp_match.last_game_id else {
+        return Ok(());
+    };
+    // players who never sent a score frame have nothing to store
+    let Some(frame) = multiplayer::fetch_score_frame(ctx, match_id, slot_id).await? else {
+        return Ok(());
+    };
+
+    // with freemod enabled, the match only holds the speed changing mods
+    let mods = match mp_match.freemod_enabled {
+        true => mp_match.mods | slot.mods.bits(),
+        false => mp_match.mods,
+    };
+    let score = MatchGameScore {
+        game_id,
+        user_id: frame.user_id,
+        slot_id: slot_id as _,
+        team: slot.team as _,
+        mods,
+        score: frame.total_score as _,
+        max_combo: frame.max_combo as _,
+        count_300: frame.count_300 as _,
+        count_100: frame.count_100 as _,
+        count_50: frame.count_50 as _,
+        count_geki: frame.count_geki as _,
+        count_katu: frame.count_katu as _,
+        count_miss: frame.count_miss as _,
+        perfect: frame.perfect,
+        passed,
+    };
+    match_game_scores::save(ctx, score).await
+}
+
 pub async fn change_mods<C: Context>(
     ctx: &C,
     match_id: i64,
@@ -1077,52 +1458,83 @@ pub async fn get_referees<C: Context>(ctx: &C, match_id: i64) -> ServiceResult<V
     Ok(referees)
 }
 
-pub fn start_timer<C: Context>(ctx: &C, match_id: i64, timer_type: TimerType, seconds: u64) {
-    let state = AppState::from_ctx(ctx);
-    tokio::spawn(async move {
-        if let Err(e) = run_timer(state, match_id, timer_type, seconds).await {
-            error!("Error running timer: {e:?}");
-        }
-    });
+pub async fn start_timer<C: Context>(
+    ctx: &C,
+    match_id: i64,
+    timer_type: TimerType,
+    seconds: u64,
+) -> ServiceResult<()> {
+    let deadline = Utc::now() + TimeDelta::seconds(seconds as _);
+    multiplayer::set_timer(ctx, match_id, timer_type, deadline, seconds as _).await?;
+    Ok(())
 }
 
-async fn run_timer<C: Context>(
-    ctx: C,
+/// Returns the remaining seconds of a running timer, rounded up.
+pub async fn get_timer_remaining_seconds<C: Context>(
+    ctx: &C,
     match_id: i64,
     timer_type: TimerType,
-    seconds: u64,
+) -> ServiceResult<Option<i64>> {
+    let deadline = multiplayer::get_timer(ctx, match_id, timer_type).await?;
+    Ok(deadline.map(|deadline| remaining_seconds_until(deadline, Utc::now())))
+}
+
+fn remaining_seconds_until(deadline: DateTime<Utc>, now: DateTime<Utc>) -> i64 {
+    let remaining_millis = (deadline - now).num_milliseconds();
+    (remaining_millis + 999).div_euclid(1000)
+}
+
+const fn should_announce_timer(remaining_seconds: i64) -> bool {
+    remaining_seconds <= 5
+        || remaining_seconds == 10
+        || remaining_seconds == 30
+        || (remaining_seconds % 60) == 0
+}
+
+/// Advances every running timer. Safe to run concurrently on multiple replicas:
+/// every announcement and every expiry is claimed in redis before it is acted on.
+pub async fn process_timers<C: Context>(ctx: &C) -> ServiceResult<()> {
+    let now = Utc::now();
+    let timers = multiplayer::fetch_all_timers(ctx).await?;
+    for timer in timers {
+        let remaining_seconds = remaining_seconds_until(timer.deadline, now);
+        if let Err(e) = process_timer(ctx, timer, remaining_seconds).await {
+            error!(
+                match_id = timer.match_id,
+                timer_type = timer.timer_type.as_str(),
+                "Error processing timer: {e:?}"
+            );
+        }
+    }
+    Ok(())
+}
+
+async fn process_timer<C: Context>(
+    ctx: &C,
+    timer: MultiplayerTimer,
+    remaining_seconds: i64,
 ) -> ServiceResult<()> {
-    multiplayer::set_timer(&ctx, match_id, timer_type, seconds).await?;
-    let mut interval = tokio::time::interval(Duration::from_secs(1));
-    interval.tick().await;
-    loop {
-        interval.tick().await;
-        // Timer was aborted externally
-        if multiplayer::get_timer(&ctx, match_id, timer_type)
-            .await?
-            .is_none()
-        {
-            break;
+    let MultiplayerTimer {
+        match_id,
+        timer_type,
+        ..
+    } = timer;
+    if remaining_seconds <= 0 {
+        if !multiplayer::claim_timer_expiry(ctx, timer).await? {
+            return Ok(());
         }
 
-        let remaining_seconds = multiplayer::decrease_timer(&ctx, match_id, timer_type).await?;
-        if remaining_seconds <= 0 {
-            multiplayer::abort_timer(&ctx, match_id, timer_type).await?;
-            send_timer_ended_message(&ctx, match_id, timer_type).await?;
-            match timer_type {
-                TimerType::Regular => {}
-                TimerType::MatchStart => {
-                    start_game(&ctx, match_id, None).await?;
-                }
+        send_timer_ended_message(ctx, match_id, timer_type).await?;
+        match timer_type {
+            TimerType::Regular => {}
+            TimerType::MatchStart => {
+                start_game(ctx, match_id, None).await?;
             }
-            break;
-        } else if remaining_seconds <= 5
-            || remaining_seconds == 10
-            || remaining_seconds == 30
-            || (remaining_seconds % 60) == 0
-        {
-            send_timer_remaining_message(&ctx, match_id, remaining_seconds, timer_type).await?;
         }
+    } else if should_announce_timer(remaining_seconds)
+        && multiplayer::claim_timer_announcement(ctx, timer, remaining_seconds).await?
+    {
+        send_timer_remaining_message(ctx, match_id, remaining_seconds, timer_type).await?;
     }
 
     Ok(())
@@ -1133,27 +1545,11 @@ async fn send_timer_ended_message<C: Context>(
     match_id: i64,
     timer_type: TimerType,
 ) -> ServiceResult<()> {
-    let mp_match = fetch_one(ctx, match_id).await?;
     let message_text = match timer_type {
         TimerType::Regular => "Timer has ended.",
         TimerType::MatchStart => "Match is starting!",
     };
-
-    let bot_message = IrcMessage {
-        sender_id: bot::BOT_ID as _,
-        sender: bot::BOT_NAME,
-        text: message_text,
-        recipient: "#multiplayer",
-    };
-    streams::broadcast_message(
-        ctx,
-        StreamName::Multiplayer(mp_match.match_id),
-        ChatMessage(&bot_message),
-        None,
-        None,
-    )
-    .await?;
-    Ok(())
+    send_bot_message(ctx, match_id, message_text).await
 }
 
 async fn send_timer_remaining_message<C: Context>(
@@ -1162,8 +1558,6 @@ async fn send_timer_remaining_message<C: Context>(
     remaining_seconds: i64,
     timer_type: TimerType,
 ) -> ServiceResult<()> {
-    let mp_match = fetch_one(ctx, match_id).await?;
-
     let prefix = match timer_type {
         TimerType::Regular => "Timer is ending in",
         TimerType::MatchStart => "Match is starting in",
@@ -1185,10 +1579,16 @@ async fn send_timer_remaining_message<C: Context>(
     };
 
     let timer_remaining_text = format!("{prefix}{minutes_text}{seconds_text}");
+    send_bot_message(ctx, match_id, &timer_remaining_text).await
+}
+
+/// Sends a message from the bot to the match's chat.
+pub async fn send_bot_message<C: Context>(ctx: &C, match_id: i64, text: &str) -> ServiceResult<()> {
+    let mp_match = fetch_one(ctx, match_id).await?;
     let bot_message = IrcMessage {
         sender_id: bot::BOT_ID as _,
         sender: bot::BOT_NAME,
-        text: &timer_remaining_text,
+        text,
         recipient: "#multiplayer",
     };
     streams::broadcast_message(
@@ -1199,7 +1599,6 @@ async fn send_timer_remaining_message<C: Context>(
         None,
     )
     .await?;
-
     Ok(())
 }
 
@@ -1317,6 +1716,17 @@ pub async fn abort<C: Context>(ctx: &C, match_id: i64) -> ServiceResult<()> {
     let mp_match = MultiplayerMatch::try_from(mp_match)?;
     let slots = MultiplayerMatchSlot::from(slots);
     broadcast_update(ctx, &mp_match, slots).await?;
+    if let Err(e) = match_feeds::publish(
+        ctx,
+        match_id,
+        &MatchFeedEvent::Abort {
+            game_id: mp_match.last_game_id,
+        },
+    )
+    .await
+    {
+        error!(match_id, "Failed to publish match feed event: {e:?}");
+    }
 
     // Save the match game end to match history
     match_games::game_ended(ctx, match_id).await?;
@@ -1398,3 +1808,35 @@ async fn change_playing_state<C: Context, F: Fn(&mut SlotEntity) -> &mut bool>(
     multiplayer::update_slot(ctx, match_id, player_slot_id, player_slot).await?;
     Ok((all, player_slot_id))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at_millis(millis: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp_millis(millis).unwrap()
+    }
+
+    #[test]
+    fn remaining_seconds_round_up() {
+        let now = at_millis(1_000_000);
+        assert_eq!(remaining_seconds_until(at_millis(1_030_000), now), 30);
+        assert_eq!(remaining_seconds_until(at_millis(1_029_001), now), 30);
+        assert_eq!(remaining_seconds_until(at_millis(1_029_000), now), 29);
+        assert_eq!(remaining_seconds_until(at_millis(1_000_001), now), 1);
+    }
+
+    #[test]
+    fn remaining_seconds_at_and_past_deadline() {
+        let now = at_millis(1_000_000);
+        assert_eq!(remaining_seconds_until(now, now), 0);
+        assert_eq!(remaining_seconds_until(at_millis(999_999), now), 0);
+        assert_eq!(remaining_seconds_until(at_millis(998_500), now), -1);
+    }
+
+    #[test]
+    fn announced_steps() {
+        let announced: Vec<i64> = (1..=120).filter(|&s| should_announce_timer(s)).collect();
+        assert_eq!(announced, vec![1, 2, 3, 4, 5, 10, 30, 60, 120]);
+    }
+}
@@ -0,0 +1,157 @@
+use crate::common::context::Context;
+use crate::common::error::{ServiceResult, unexpected};
+use crate::entities::bot;
+use crate::models::bancho::LoginError;
+use crate::models::privileges::Privileges;
+use crate::models::punishments::{Punishment, PunishmentKind};
+use crate::repositories::punishments;
+use crate::repositories::streams::StreamName;
+use crate::usecases::{sessions, streams, users};
+use bancho_protocol::messages::server::{ChatMessage, LoginResult};
+use bancho_protocol::structures::IrcMessage;
+use chrono::{TimeDelta, Utc};
+use std::time::Duration;
+
+/// Bans the user and records the punishment, online sessions are sent the ban packet.
+pub async fn ban<C: Context>(
+    ctx: &C,
+    user_id: i64,
+    reason: &str,
+    created_by: i64,
+    duration: Option<Duration>,
+) -> ServiceResult<()> {
+    users::ban_user(ctx, user_id).await?;
+    create(
+        ctx,
+        user_id,
+        PunishmentKind::Ban,
+        reason,
+        created_by,
+        duration,
+    )
+    .await?;
+
+    for session in sessions::fetch_by_user_id(ctx, user_id).await? {
+        let ban_packet = LoginResult {
+            user_id: LoginError::Banned as i32,
+        };
+        streams::broadcast_message(
+            ctx,
+            StreamName::User(session.session_id),
+            ban_packet,
+            None,
+            None,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Restricts the user and records the punishment, online sessions are notified by the bot.
+pub async fn restrict<C: Context>(
+    ctx: &C,
+    user_id: i64,
+    reason: &str,
+    created_by: i64,
+    duration: Option<Duration>,
+) -> ServiceResult<()> {
+    users::restrict_user(ctx, user_id).await?;
+    create(
+        ctx,
+        user_id,
+        PunishmentKind::Restrict,
+        reason,
+        created_by,
+        duration,
+    )
+    .await?;
+
+    for session in sessions::fetch_by_user_id(ctx, user_id).await? {
+        let restriction_message = IrcMessage {
+            recipient: &session.username,
+            sender: bot::BOT_NAME,
+            sender_id: bot::BOT_ID as _,
+            text: "Your account is now in restricted mode. Visit the website for more information.",
+        };
+        streams::broadcast_message(
+            ctx,
+            StreamName::User(session.session_id),
+            ChatMessage(&restriction_message),
+            None,
+            None,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Records a restriction or ban in the user's privileges history,
+/// replacing any active punishment of the same kind.
+/// Punishments without a duration are permanent.
+pub async fn create<C: Context>(
+    ctx: &C,
+    user_id: i64,
+    kind: PunishmentKind,
+    reason: &str,
+    created_by: i64,
+    duration: Option<Duration>,
+) -> ServiceResult<()> {
+    lift_active(ctx, user_id, kind).await?;
+    let expires_at =
+        duration.map(|duration| Utc::now() + TimeDelta::seconds(duration.as_secs() as _));
+    match punishments::create(ctx, user_id, kind as _, reason, created_by, expires_at).await {
+        Ok(_) => Ok(()),
+        Err(e) => unexpected(e),
+    }
+}
+
+pub async fn lift_active<C: Context>(
+    ctx: &C,
+    user_id: i64,
+    kind: PunishmentKind,
+) -> ServiceResult<()> {
+    match punishments::lift_active(ctx, user_id, kind as _).await {
+        Ok(_) => Ok(()),
+        Err(e) => unexpected(e),
+    }
+}
+
+/// Lifts an expired punishment, restoring only the privileges taken away by its kind.
+/// Privileges stay removed while another active punishment still takes them away,
+/// bans take away public visibility as well.
+pub async fn expire<C: Context>(ctx: &C, punishment: &Punishment) -> ServiceResult<()> {
+    if let Err(e) = punishments::lift(ctx, punishment.id).await {
+        return unexpected(e);
+    }
+
+    let user_id = punishment.user_id;
+    if has_active(ctx, user_id, PunishmentKind::Ban).await? {
+        return Ok(());
+    }
+    let restricted = has_active(ctx, user_id, PunishmentKind::Restrict).await?;
+    let privileges = match (punishment.kind, restricted) {
+        (PunishmentKind::Ban, false) => Privileges::CanLogin | Privileges::PubliclyVisible,
+        (PunishmentKind::Ban, true) => Privileges::CanLogin,
+        (PunishmentKind::Restrict, false) => Privileges::PubliclyVisible,
+        (PunishmentKind::Restrict, true) => return Ok(()),
+    };
+    users::grant_privileges(ctx, user_id, privileges).await
+}
+
+pub async fn has_active<C: Context>(
+    ctx: &C,
+    user_id: i64,
+    kind: PunishmentKind,
+) -> ServiceResult<bool> {
+    match punishments::has_active(ctx, user_id, kind as _).await {
+        Ok(active) => Ok(active),
+        Err(e) => unexpected(e),
+    }
+}
+
+pub async fn fetch_expired<C: Context>(ctx: &C) -> ServiceResult<Vec<Punishment>> {
+    match punishments::fetch_expired(ctx).await {
+        Ok(punishments) => Ok(punishments.into_iter().map(Punishment::from).collect()),
+        Err(e) => unexpected(e),
+    }
+}
@@ -0,0 +1,89 @@
+use crate::common::context::Context;
+use crate::common::error::{AppError, ServiceResult, unexpected};
+use crate::models::rate_limits::{RateLimitAction, RateLimitScope};
+use crate::models::sessions::Session;
+use crate::repositories::rate_limits;
+use crate::settings::AppSettings;
+use crate::usecases::{bancho_settings, users};
+use chrono::{TimeDelta, Utc};
+use std::time::Duration;
+
+/// Records a hit for the action, returning false if the rate limit has been exceeded.
+pub async fn hit<C: Context>(
+    ctx: &C,
+    action: RateLimitAction,
+    scope: RateLimitScope<'_>,
+) -> ServiceResult<bool> {
+    let limit = bancho_settings::fetch_current(ctx)
+        .await?
+        .rate_limit(action);
+    match rate_limits::hit(
+        ctx,
+        action.as_str(),
+        &scope.to_string(),
+        limit.count,
+        limit.window,
+    )
+    .await
+    {
+        Ok(allowed) => Ok(allowed),
+        Err(e) => unexpected(e),
+    }
+}
+
+/// Like [`hit`], but fails with [`AppError::RateLimitsExceeded`] once the limit is exceeded.
+pub async fn check<C: Context>(
+    ctx: &C,
+    action: RateLimitAction,
+    scope: RateLimitScope<'_>,
+) -> ServiceResult<()> {
+    match hit(ctx, action, scope).await? {
+        true => Ok(()),
+        false => Err(AppError::RateLimitsExceeded),
+    }
+}
+
+/// Silences the user, doubling the duration for every offence within the last day.
+pub async fn silence_offender<C: Context>(
+    ctx: &C,
+    session: &mut Session,
+    reason: &str,
+) -> ServiceResult<Duration> {
+    let offences = match rate_limits::increment_offences(ctx, session.user_id).await {
+        Ok(offences) => offences,
+        Err(e) => return unexpected(e),
+    };
+    let settings = &AppSettings::get().rate_limits;
+    let duration = silence_duration(offences, settings.silence_base, settings.silence_max);
+    let silence_seconds = duration.as_secs() as i64;
+    session.silence_end = Some(Utc::now() + TimeDelta::seconds(silence_seconds));
+    users::silence_user(ctx, session.user_id, reason, silence_seconds).await?;
+    Ok(duration)
+}
+
+fn silence_duration(offences: u32, base: Duration, max: Duration) -> Duration {
+    let multiplier = 2u32.saturating_pow(offences.saturating_sub(1));
+    base.saturating_mul(multiplier).min(max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: Duration = Duration::from_secs(300);
+    const MAX: Duration = Duration::from_secs(86400);
+
+    #[test]
+    fn silence_doubles_with_every_offence() {
+        assert_eq!(silence_duration(0, BASE, MAX), BASE);
+        assert_eq!(silence_duration(1, BASE, MAX), BASE);
+        assert_eq!(silence_duration(2, BASE, MAX), Duration::from_secs(600));
+        assert_eq!(silence_duration(4, BASE, MAX), Duration::from_secs(2400));
+    }
+
+    #[test]
+    fn silence_is_capped() {
+        assert_eq!(silence_duration(10, BASE, MAX), MAX);
+        assert_eq!(silence_duration(u32::MAX, BASE, MAX), MAX);
+    }
+}
@@ -1,8 +1,9 @@
 use crate::common::context::Context;
 use crate::common::error::{ServiceResult, unexpected};
+use crate::models::rate_limits::{RateLimitAction, RateLimitScope};
 use crate::models::relationships::Relationship;
 use crate::repositories::relationships;
-use crate::usecases::users;
+use crate::usecases::{rate_limits, users};
 use tracing::warn;
 
 pub async fn fetch_one<C: Context>(
@@ -24,6 +25,7 @@ pub async fn fetch_friends<C: Context>(ctx: &C, user_id: i64) -> ServiceResult<V
 }
 
 pub async fn add_friend<C: Context>(ctx: &C, user_id: i64, to_add: i64) -> ServiceResult<()> {
+    rate_limits::check(ctx, RateLimitAction::Friends, RateLimitScope::User(user_id)).await?;
     let user = users::fetch_one(ctx, to_add).await?;
     if !user.privileges.is_publicly_visible() {
         warn!(
@@ -39,6 +41,7 @@ pub async fn add_friend<C: Context>(ctx: &C, user_id: i64, to_add: i64) -> Servi
 }
 
 pub async fn remove_friend<C: Context>(ctx: &C, user_id: i64, to_remove: i64) -> ServiceResult<()> {
+    rate_limits::check(ctx, RateLimitAction::Friends, RateLimitScope::User(user_id)).await?;
     match relationships::remove_friend(ctx, user_id, to_remove).await {
         Ok(_) => Ok(()),
         Err(e) => unexpected(e),
@@ -1,4 +1,5 @@
 use crate::api::RequestContext;
+use crate::common::chat::safe_username;
 use crate::common::context::Context;
 use crate::common::error::{AppError, ServiceResult, unexpected};
 use crate::entities::gamemodes::Gamemode;
@@ -9,33 +10,39 @@ use crate::models::privileges::Privileges;
 use crate::models::sessions::Session;
 use crate::models::users::User;
 use crate::repositories::streams::StreamName;
-use crate::repositories::{ip_logs, sessions, users};
+use crate::repositories::{ip_logs, irc_tokens, sessions, users};
 use crate::usecases::{
-    channels, hardware_logs, location, multiplayer, presences, spectators, stats, streams,
+    bancho_settings, channels, client_builds, hardware_logs, location, login_attempts, multiplayer,
+    presences, spectators, stats, streams,
 };
 use bancho_protocol::messages::server::UserLogout;
 use chrono::TimeDelta;
+use std::net::IpAddr;
 use uuid::Uuid;
 
-pub const USER_SESSIONS_LIMIT: u64 = 20;
-pub const TOURNAMENT_STAFF_SESSIONS_LIMIT: u64 = 40;
-
 pub async fn create(ctx: &RequestContext, args: LoginArgs) -> ServiceResult<(Session, Presence)> {
-    if args.client_info.osu_version.is_outdated() {
-        return Err(AppError::ClientTooOld);
-    }
+    let ip_address = ctx.request_ip.ip_addr;
+    // checked before the password, so that locked out attempts don't cost a bcrypt hash
+    login_attempts::check(ctx, ip_address, &args.identifier).await?;
+    let settings = bancho_settings::fetch_current(ctx).await?;
+    client_builds::check_supported(ctx, &args.client_info.osu_version, &settings).await?;
 
     let user = match users::fetch_one_by_username(ctx, &args.identifier).await {
         Ok(user) => user,
-        Err(sqlx::Error::RowNotFound) => return Err(AppError::SessionsInvalidCredentials),
+        Err(sqlx::Error::RowNotFound) => {
+            login_attempts::record_failure(ctx, ip_address, &args.identifier, None).await?;
+            return Err(AppError::SessionsInvalidCredentials);
+        }
         Err(e) => return unexpected(e),
     };
 
     if !bcrypt::verify(&args.secret, &user.password_md5)
         .map_err(|_| AppError::SessionsInvalidCredentials)?
     {
+        login_attempts::record_failure(ctx, ip_address, &args.identifier, Some(user.id)).await?;
         return Err(AppError::SessionsInvalidCredentials);
     }
+    login_attempts::clear_failures(ctx, &args.identifier).await?;
 
     let mut user = User::try_from(user)?;
     if !user.privileges.contains(Privileges::CanLogin)
@@ -44,7 +51,9 @@ pub async fn create(ctx: &RequestContext, args: LoginArgs) -> ServiceResult<(Ses
         return Err(AppError::SessionsLoginForbidden);
     }
 
-    let ip_address = ctx.request_ip.ip_addr;
+    // checked once the user is known, so that logins with denied builds can be reported
+    client_builds::check_denied(ctx, &user, &args.client_info.osu_version).await?;
+
     let user_verification_pending = user.privileges.is_pending_verification();
 
     ip_logs::create(ctx, user.user_id, ip_address).await?;
@@ -65,23 +74,94 @@ pub async fn create(ctx: &RequestContext, args: LoginArgs) -> ServiceResult<(Ses
     )
     .await?;
 
-    let user_session_count = sessions::fetch_user_session_count(ctx, user.user_id).await?;
-    if (!user.privileges.is_tournament_staff() && user_session_count >= USER_SESSIONS_LIMIT)
-        || user_session_count >= TOURNAMENT_STAFF_SESSIONS_LIMIT
-    {
-        return Err(AppError::SessionsLimitReached);
-    }
+    check_session_limit(ctx, &user).await?;
 
     if user_verification_pending {
         users::verify_user(ctx, user.user_id).await?;
         user.privileges.remove(Privileges::PendingVerification);
     }
 
+    start_session(
+        ctx,
+        user,
+        ip_address,
+        args.client_info.display_city,
+        args.client_info.pm_private,
+        args.client_info.utc_offset,
+        Some(args.client_info.osu_version.to_string()),
+    )
+    .await
+}
+
+/// Creates a session for an IRC client, authenticated by the user's IRC password.
+pub async fn create_irc<C: Context>(
+    ctx: &C,
+    username: &str,
+    password: &str,
+    ip_address: IpAddr,
+) -> ServiceResult<(Session, Presence)> {
+    if bancho_settings::in_maintenance_mode(ctx).await? {
+        return Err(AppError::MaintenanceModeEnabled);
+    }
+    login_attempts::check(ctx, ip_address, username).await?;
+
+    let user = match users::fetch_one_by_username_safe(ctx, &safe_username(username)).await {
+        Ok(user) => user,
+        Err(sqlx::Error::RowNotFound) => {
+            login_attempts::record_failure(ctx, ip_address, username, None).await?;
+            return Err(AppError::SessionsInvalidCredentials);
+        }
+        Err(e) => return unexpected(e),
+    };
+
+    let token_hash = match irc_tokens::fetch_token_hash(ctx, user.id).await {
+        Ok(token_hash) => token_hash,
+        Err(sqlx::Error::RowNotFound) => return Err(AppError::SessionsInvalidCredentials),
+        Err(e) => return unexpected(e),
+    };
+
+    if !bcrypt::verify(password, &token_hash).map_err(|_| AppError::SessionsInvalidCredentials)? {
+        login_attempts::record_failure(ctx, ip_address, username, Some(user.id)).await?;
+        return Err(AppError::SessionsInvalidCredentials);
+    }
+    login_attempts::clear_failures(ctx, username).await?;
+
+    // accounts pending verification have to log in through the game client first
+    let user = User::try_from(user)?;
+    if !user.privileges.contains(Privileges::CanLogin) {
+        return Err(AppError::SessionsLoginForbidden);
+    }
+
+    ip_logs::create(ctx, user.user_id, ip_address).await?;
+    check_session_limit(ctx, &user).await?;
+    start_session(ctx, user, ip_address, false, false, 0, None).await
+}
+
+async fn check_session_limit<C: Context>(ctx: &C, user: &User) -> ServiceResult<()> {
+    let settings = bancho_settings::fetch_current(ctx).await?;
+    let user_session_count = sessions::fetch_user_session_count(ctx, user.user_id).await?;
+    if (!user.privileges.is_tournament_staff()
+        && user_session_count >= settings.user_sessions_limit)
+        || user_session_count >= settings.tournament_staff_sessions_limit
+    {
+        return Err(AppError::SessionsLimitReached);
+    }
+    Ok(())
+}
+
+async fn start_session<C: Context>(
+    ctx: &C,
+    user: User,
+    ip_address: IpAddr,
+    display_city: bool,
+    private_dms: bool,
+    utc_offset: i8,
+    client_version: Option<String>,
+) -> ServiceResult<(Session, Presence)> {
     let stats = stats::fetch_one(ctx, user.user_id, Gamemode::Standard).await?;
     let rank = stats::fetch_global_rank(ctx, user.user_id, Gamemode::Standard).await?;
 
-    let location_info =
-        location::get_location(ip_address, user.country, args.client_info.display_city).await;
+    let location_info = location::get_location(ctx, ip_address, user.country, display_city).await;
     let session = sessions::create(
         ctx,
         CreateSessionArgs {
@@ -90,7 +170,8 @@ pub async fn create(ctx: &RequestContext, args: LoginArgs) -> ServiceResult<(Ses
             username: user.username.clone(),
             privileges: user.privileges.bits(),
             silence_end: user.silence_end,
-            private_dms: args.client_info.pm_private,
+            private_dms,
+            client_version,
         },
     )
     .await?;
@@ -109,7 +190,7 @@ pub async fn create(ctx: &RequestContext, args: LoginArgs) -> ServiceResult<(Ses
         location_info.country,
         location_info.latitude,
         location_info.longitude,
-        args.client_info.utc_offset,
+        utc_offset,
     )
     .await?;
     Ok((Session::from(session), presence))
@@ -1,6 +1,7 @@
 use crate::common::context::Context;
 use crate::common::error::{ServiceResult, unexpected};
-use crate::entities::streams::MessageInfo;
+use crate::common::metrics;
+use crate::entities::streams::{MessageInfo, StreamReadMessage};
 use crate::models::privileges::Privileges;
 use crate::models::sessions::Session;
 use crate::repositories::streams;
@@ -8,6 +9,8 @@ use crate::repositories::streams::StreamName;
 use bancho_protocol::messages::MessageArgs;
 use bancho_protocol::serde::BinarySerialize;
 use chrono::{DateTime, TimeDelta, Utc};
+use redis::aio::MultiplexedConnection;
+use std::time::Duration;
 use uuid::Uuid;
 
 pub async fn broadcast_message<C: Context, M: MessageArgs>(
@@ -53,6 +56,21 @@ pub async fn broadcast_data<C: Context>(
 
 pub async fn read_pending_data<C: Context>(ctx: &C, session: &Session) -> ServiceResult<Vec<u8>> {
     let messages = streams::read_pending_messages(ctx, session.session_id).await?;
+    Ok(filter_readable_data(session, messages))
+}
+
+/// Like [`read_pending_data`], but waits up to `timeout` for new data to arrive.
+pub async fn wait_for_pending_data(
+    redis: &mut MultiplexedConnection,
+    session: &Session,
+    timeout: Duration,
+) -> ServiceResult<Vec<u8>> {
+    let messages = streams::wait_for_pending_messages(redis, session.session_id, timeout).await?;
+    Ok(filter_readable_data(session, messages))
+}
+
+fn filter_readable_data(session: &Session, messages: Vec<StreamReadMessage>) -> Vec<u8> {
+    let message_count = messages.len();
     let mut pending_data = vec![];
     for msg in messages {
         let is_excluded = msg
@@ -67,7 +85,8 @@ pub async fn read_pending_data<C: Context>(ctx: &C, session: &Session) -> Servic
             pending_data.extend(&msg.data);
         }
     }
-    Ok(pending_data)
+    metrics::record_pending_data(message_count, pending_data.len());
+    pending_data
 }
 
 pub async fn join<C: Context>(
@@ -77,9 +96,35 @@ pub async fn join<C: Context>(
 ) -> ServiceResult<()> {
     let latest_message_id = streams::get_latest_message_id(ctx, stream_name).await?;
     streams::set_offset(ctx, session_id, stream_name, latest_message_id).await?;
+    if !matches!(stream_name, StreamName::User(_))
+        && streams::is_push_active(ctx, session_id).await?
+    {
+        // an empty message wakes up the blocking read of the session,
+        // so that the new stream is read from right away
+        broadcast_data(ctx, StreamName::User(session_id), &[], None, None).await?;
+    }
     Ok(())
 }
 
+/// Marks the session as blocking on its streams, so that joins wake up the read.
+pub async fn set_push_active<C: Context>(
+    ctx: &C,
+    session_id: Uuid,
+    ttl: Duration,
+) -> ServiceResult<()> {
+    match streams::set_push_active(ctx, session_id, ttl.as_secs()).await {
+        Ok(()) => Ok(()),
+        Err(e) => unexpected(e),
+    }
+}
+
+pub async fn remove_push_active<C: Context>(ctx: &C, session_id: Uuid) -> ServiceResult<()> {
+    match streams::remove_push_active(ctx, session_id).await {
+        Ok(()) => Ok(()),
+        Err(e) => unexpected(e),
+    }
+}
+
 pub async fn leave<C: Context>(
     ctx: &C,
     session_id: Uuid,
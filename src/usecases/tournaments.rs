@@ -0,0 +1,599 @@
+use crate::common::context::Context;
+use crate::common::error::{AppError, ServiceResult, unexpected};
+use crate::entities::match_events::MatchEventType;
+use crate::entities::tournaments::{PoolMap, Tournament, TournamentBan, TournamentPick};
+use crate::repositories::multiplayer::TimerType;
+use crate::repositories::tournaments;
+use crate::usecases::match_game_scores::GameWinner;
+use crate::usecases::{beatmaps, match_events, multiplayer};
+use bancho_protocol::structures::{MatchTeam, Mods};
+use std::str::FromStr;
+use tracing::error;
+
+/// How long teams have to get ready after a map has been picked.
+const READY_TIMER_SECONDS: u64 = 120;
+
+/// How often an update starts over when another replica changed the tournament meanwhile.
+const UPDATE_ATTEMPTS: usize = 5;
+
+pub const DEFAULT_BANS_PER_TEAM: u8 = 1;
+
+pub async fn fetch_one<C: Context>(ctx: &C, match_id: i64) -> ServiceResult<Tournament> {
+    match tournaments::fetch_one(ctx, match_id).await {
+        Ok(Some(tournament)) => Ok(tournament),
+        Ok(None) => Err(AppError::TournamentsNotEnabled),
+        Err(e) => unexpected(e),
+    }
+}
+
+/// Enables tournament mode for the match, resetting points, picks and bans.
+/// An already loaded mappool is kept.
+pub async fn enable<C: Context>(
+    ctx: &C,
+    match_id: i64,
+    best_of: u8,
+    bans_per_team: u8,
+) -> ServiceResult<Tournament> {
+    let (tournament, _) = modify(ctx, match_id, true, |tournament| {
+        tournament.best_of = best_of;
+        tournament.bans_per_team = bans_per_team;
+        tournament.first_team = None;
+        tournament.picks.clear();
+        tournament.bans.clear();
+        tournament.red_points = 0;
+        tournament.blue_points = 0;
+        Ok(())
+    })
+    .await?;
+    Ok(tournament)
+}
+
+pub async fn add_pool_map<C: Context>(
+    ctx: &C,
+    match_id: i64,
+    code: &str,
+    beatmap_id: i32,
+) -> ServiceResult<PoolMap> {
+    fetch_one(ctx, match_id).await?;
+    let code = code.to_uppercase();
+    let (mods, freemod) = parse_map_code(&code)?;
+    let beatmap = beatmaps::fetch_by_id(ctx, beatmap_id).await?;
+
+    let pool_map = PoolMap {
+        code,
+        beatmap_id: beatmap.beatmap_id,
+        mods: mods.bits(),
+        freemod,
+    };
+    modify(ctx, match_id, false, |tournament| {
+        tournament.pool.retain(|map| map.code != pool_map.code);
+        tournament.pool.push(pool_map.clone());
+        Ok(())
+    })
+    .await?;
+    Ok(pool_map)
+}
+
+pub async fn clear_pool<C: Context>(ctx: &C, match_id: i64) -> ServiceResult<()> {
+    modify(ctx, match_id, false, |tournament| {
+        tournament.pool.clear();
+        Ok(())
+    })
+    .await?;
+    Ok(())
+}
+
+/// Bans a map from the pool. Teams ban in turns until both used up their bans,
+/// referees may ban out of turn on behalf of a team.
+pub async fn ban<C: Context>(
+    ctx: &C,
+    match_id: i64,
+    user_id: i64,
+    code: &str,
+    team: MatchTeam,
+    referee: bool,
+) -> ServiceResult<PoolMap> {
+    let team = team as u8;
+    let (_, pool_map) = modify(ctx, match_id, false, |tournament| {
+        apply_ban(tournament, code, team, referee)
+    })
+    .await?;
+    if let Err(e) = match_events::create(
+        ctx,
+        match_id,
+        MatchEventType::MatchMapBanned,
+        Some(user_id),
+        None,
+    )
+    .await
+    {
+        error!(match_id, "Failed to record map ban event: {e:?}");
+    }
+    Ok(pool_map)
+}
+
+/// Picks a map from the pool, applies its beatmap and mods to the match
+/// and starts the ready timer. Teams pick in turns once all bans are made,
+/// referees may pick out of turn on behalf of a team.
+pub async fn pick<C: Context>(
+    ctx: &C,
+    match_id: i64,
+    user_id: i64,
+    code: &str,
+    team: MatchTeam,
+    referee: bool,
+) -> ServiceResult<PoolMap> {
+    let team = team as u8;
+    let (_, pool_map) = modify(ctx, match_id, false, |tournament| {
+        apply_pick(tournament, code, team, referee)
+    })
+    .await?;
+    if let Err(e) = match_events::create(
+        ctx,
+        match_id,
+        MatchEventType::MatchMapPicked,
+        Some(user_id),
+        None,
+    )
+    .await
+    {
+        error!(match_id, "Failed to record map pick event: {e:?}");
+    }
+
+    multiplayer::change_beatmap(ctx, match_id, pool_map.beatmap_id, None).await?;
+    let mut mp_match = multiplayer::fetch_one(ctx, match_id).await?;
+    if mp_match.freemod_enabled != pool_map.freemod {
+        mp_match.freemod_enabled = pool_map.freemod;
+        multiplayer::update(ctx, mp_match).await?;
+    }
+    multiplayer::change_mods(ctx, match_id, Mods::from_bits_retain(pool_map.mods), None).await?;
+    multiplayer::start_timer(ctx, match_id, TimerType::MatchStart, READY_TIMER_SECONDS).await?;
+    Ok(pool_map)
+}
+
+/// Awards the point of the current pick to the winning team of the game,
+/// if the game was played on the picked beatmap.
+pub async fn record_game_result<C: Context>(
+    ctx: &C,
+    match_id: i64,
+    game_id: i64,
+    beatmap_id: i32,
+    winner: Option<GameWinner>,
+) -> ServiceResult<()> {
+    match tournaments::fetch_one(ctx, match_id).await? {
+        Some(tournament) if current_pick(&tournament, beatmap_id).is_some() => {}
+        _ => return Ok(()),
+    }
+
+    let winning_team = match winner {
+        Some(GameWinner::Team(team)) => Some(team),
+        _ => None,
+    };
+    let result = modify(ctx, match_id, false, |tournament| {
+        let Some(pick_index) = current_pick(tournament, beatmap_id) else {
+            return Ok(false);
+        };
+        let pick = &mut tournament.picks[pick_index];
+        pick.game_id = Some(game_id);
+        pick.winning_team = winning_team;
+        match winning_team {
+            Some(team) if team == MatchTeam::Red as u8 => tournament.red_points += 1,
+            Some(_) => tournament.blue_points += 1,
+            None => {}
+        }
+        Ok(true)
+    })
+    .await;
+    let tournament = match result {
+        Ok((tournament, true)) => tournament,
+        // the pick has been replaced or tournament mode disabled in the meantime
+        Ok((_, false)) | Err(AppError::TournamentsNotEnabled) => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    let Some(winning_team) = winning_team else {
+        let message = "No team won this map, the point is not awarded.";
+        return multiplayer::send_bot_message(ctx, match_id, message).await;
+    };
+
+    let mut message = format!(
+        "{} wins this map! Red {} - {} Blue",
+        team_name(winning_team),
+        tournament.red_points,
+        tournament.blue_points,
+    );
+    if is_decided(&tournament) {
+        message.push_str(&format!("\n{} wins the match!", team_name(winning_team)));
+    } else if is_tiebreaker_due(&tournament) {
+        message.push_str("\nIt's time for the tiebreaker!");
+    }
+    multiplayer::send_bot_message(ctx, match_id, &message).await
+}
+
+pub async fn delete<C: Context>(ctx: &C, match_id: i64) -> ServiceResult<()> {
+    match tournaments::delete(ctx, match_id).await {
+        Ok(_) => Ok(()),
+        Err(e) => unexpected(e),
+    }
+}
+
+pub fn format_status(tournament: &Tournament) -> String {
+    let codes = |codes: Vec<&str>| match codes.is_empty() {
+        true => "none".to_string(),
+        false => codes.join(", "),
+    };
+    let bans = codes(
+        tournament
+            .bans
+            .iter()
+            .map(|ban| ban.code.as_str())
+            .collect(),
+    );
+    let picks = codes(
+        tournament
+            .picks
+            .iter()
+            .map(|pick| pick.code.as_str())
+            .collect(),
+    );
+    let remaining = codes(
+        tournament
+            .pool
+            .iter()
+            .filter(|map| is_available(tournament, &map.code))
+            .map(|map| map.code.as_str())
+            .collect(),
+    );
+    format!(
+        "Best of {} | {} ban(s) per team | Red {} - {} Blue\nBans: {bans}\nPicks: {picks}\nRemaining: {remaining}",
+        tournament.best_of, tournament.bans_per_team, tournament.red_points, tournament.blue_points,
+    )
+}
+
+// utility
+
+/// Applies `apply` to the stored tournament and stores the result, starting over
+/// if another replica changed the tournament in the meantime. With `create`,
+/// missing tournaments start out empty instead of failing.
+async fn modify<C: Context, T>(
+    ctx: &C,
+    match_id: i64,
+    create: bool,
+    mut apply: impl FnMut(&mut Tournament) -> ServiceResult<T>,
+) -> ServiceResult<(Tournament, T)> {
+    for _ in 0..UPDATE_ATTEMPTS {
+        let (mut tournament, previous) = match tournaments::fetch_for_update(ctx, match_id).await? {
+            Some((tournament, stored)) => (tournament, Some(stored)),
+            None if create => (
+                Tournament {
+                    match_id,
+                    ..Default::default()
+                },
+                None,
+            ),
+            None => return Err(AppError::TournamentsNotEnabled),
+        };
+        let result = apply(&mut tournament)?;
+        if tournaments::replace(ctx, previous.as_deref(), &tournament).await? {
+            return Ok((tournament, result));
+        }
+    }
+    unexpected(anyhow::anyhow!(
+        "tournament {match_id} kept changing during an update"
+    ))
+}
+
+fn apply_ban(
+    tournament: &mut Tournament,
+    code: &str,
+    team: u8,
+    referee: bool,
+) -> ServiceResult<PoolMap> {
+    if is_decided(tournament) {
+        return Err(AppError::TournamentsMatchDecided);
+    }
+    if tournament.bans.len() >= total_bans(tournament) {
+        return Err(AppError::TournamentsBanLimitReached);
+    }
+
+    let pool_map = find_available_map(tournament, code)?;
+    if is_tiebreaker(&pool_map.code) {
+        return Err(AppError::TournamentsMapUnavailable);
+    }
+    let turn = tournament.bans.len();
+    check_turn(tournament, turn, team, referee)?;
+
+    tournament.bans.push(TournamentBan {
+        code: pool_map.code.clone(),
+        team,
+    });
+    Ok(pool_map)
+}
+
+fn apply_pick(
+    tournament: &mut Tournament,
+    code: &str,
+    team: u8,
+    referee: bool,
+) -> ServiceResult<PoolMap> {
+    if is_decided(tournament) {
+        return Err(AppError::TournamentsMatchDecided);
+    }
+    if tournament.bans.len() < total_bans(tournament) {
+        return Err(AppError::TournamentsBansPending);
+    }
+
+    // a pick that was never decided (e.g. aborted) may be replaced,
+    // but only by the team that made it or a referee
+    if let Some(pick) = tournament
+        .picks
+        .last()
+        .filter(|pick| pick.winning_team.is_none())
+    {
+        if pick.team != team && !referee {
+            return Err(AppError::TournamentsNotYourTurn);
+        }
+        tournament.picks.pop();
+    }
+
+    let pool_map = find_available_map(tournament, code)?;
+    match (is_tiebreaker_due(tournament), is_tiebreaker(&pool_map.code)) {
+        (true, false) => return Err(AppError::TournamentsTiebreakerRequired),
+        (false, true) => return Err(AppError::TournamentsMapUnavailable),
+        // the tiebreaker is nobody's pick
+        (true, true) => {}
+        (false, false) => {
+            let turn = tournament.picks.len();
+            check_turn(tournament, turn, team, referee)?;
+        }
+    }
+
+    tournament.picks.push(TournamentPick {
+        code: pool_map.code.clone(),
+        team,
+        game_id: None,
+        winning_team: None,
+    });
+    Ok(pool_map)
+}
+
+/// Returns the index of the undecided last pick, if it was picked for this beatmap.
+fn current_pick(tournament: &Tournament, beatmap_id: i32) -> Option<usize> {
+    let index = tournament.picks.len().checked_sub(1)?;
+    let pick = &tournament.picks[index];
+    let pool_map = tournament.pool.iter().find(|map| map.code == pick.code)?;
+    match pick.winning_team.is_none() && pool_map.beatmap_id == beatmap_id {
+        true => Some(index),
+        false => None,
+    }
+}
+
+/// Map codes start with their mods, followed by their index in the pool.
+/// NM is played without mods, FM and TB allow players to pick their own mods.
+fn parse_map_code(code: &str) -> ServiceResult<(Mods, bool)> {
+    let mods = code.trim_end_matches(|c: char| c.is_ascii_digit());
+    match mods {
+        "" => Err(AppError::TournamentsInvalidMapCode),
+        "NM" => Ok((Mods::None, false)),
+        "FM" | "TB" => Ok((Mods::None, true)),
+        mods => match Mods::from_str(mods) {
+            Ok(mods) => Ok((mods, false)),
+            Err(_) => Err(AppError::TournamentsInvalidMapCode),
+        },
+    }
+}
+
+fn find_available_map(tournament: &Tournament, code: &str) -> ServiceResult<PoolMap> {
+    let code = code.to_uppercase();
+    let pool_map = tournament
+        .pool
+        .iter()
+        .find(|map| map.code == code)
+        .ok_or(AppError::TournamentsMapNotInPool)?;
+    match is_available(tournament, &pool_map.code) {
+        true => Ok(pool_map.clone()),
+        false => Err(AppError::TournamentsMapUnavailable),
+    }
+}
+
+fn is_available(tournament: &Tournament, code: &str) -> bool {
+    !tournament.bans.iter().any(|ban| ban.code == code)
+        && !tournament.picks.iter().any(|pick| pick.code == code)
+}
+
+fn is_tiebreaker(code: &str) -> bool {
+    code.starts_with("TB")
+}
+
+const fn wins_required(tournament: &Tournament) -> u8 {
+    tournament.best_of / 2 + 1
+}
+
+const fn is_decided(tournament: &Tournament) -> bool {
+    let wins_required = wins_required(tournament);
+    tournament.red_points >= wins_required || tournament.blue_points >= wins_required
+}
+
+const fn is_tiebreaker_due(tournament: &Tournament) -> bool {
+    let match_point = wins_required(tournament) - 1;
+    tournament.red_points == match_point && tournament.blue_points == match_point
+}
+
+const fn total_bans(tournament: &Tournament) -> usize {
+    tournament.bans_per_team as usize * 2
+}
+
+/// Teams take turns starting with the team that made the first ban or pick.
+fn check_turn(
+    tournament: &mut Tournament,
+    turn: usize,
+    team: u8,
+    referee: bool,
+) -> ServiceResult<()> {
+    let first_team = *tournament.first_team.get_or_insert(team);
+    let turn_team = match turn % 2 == 0 {
+        true => first_team,
+        false => opposing_team(first_team),
+    };
+    match referee || turn_team == team {
+        true => Ok(()),
+        false => Err(AppError::TournamentsNotYourTurn),
+    }
+}
+
+const fn opposing_team(team: u8) -> u8 {
+    match team == MatchTeam::Red as u8 {
+        true => MatchTeam::Blue as u8,
+        false => MatchTeam::Red as u8,
+    }
+}
+
+fn team_name(team: u8) -> &'static str {
+    match team == MatchTeam::Red as u8 {
+        true => "Red",
+        false => "Blue",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: u8 = MatchTeam::Red as u8;
+    const BLUE: u8 = MatchTeam::Blue as u8;
+
+    fn pool_map(code: &str, beatmap_id: i32) -> PoolMap {
+        PoolMap {
+            code: code.to_string(),
+            beatmap_id,
+            mods: 0,
+            freemod: false,
+        }
+    }
+
+    fn tournament() -> Tournament {
+        Tournament {
+            match_id: 1,
+            best_of: 3,
+            bans_per_team: 1,
+            pool: vec![
+                pool_map("NM1", 1),
+                pool_map("NM2", 2),
+                pool_map("HD1", 3),
+                pool_map("DT1", 4),
+                pool_map("TB", 5),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn first_team_starts_and_teams_alternate() {
+        let mut tournament = tournament();
+        assert!(check_turn(&mut tournament, 0, BLUE, false).is_ok());
+        assert_eq!(tournament.first_team, Some(BLUE));
+        assert!(check_turn(&mut tournament, 1, RED, false).is_ok());
+        assert!(matches!(
+            check_turn(&mut tournament, 1, BLUE, false),
+            Err(AppError::TournamentsNotYourTurn)
+        ));
+        assert!(check_turn(&mut tournament, 2, BLUE, false).is_ok());
+    }
+
+    #[test]
+    fn referees_may_act_out_of_turn() {
+        let mut tournament = tournament();
+        tournament.first_team = Some(RED);
+        assert!(check_turn(&mut tournament, 0, BLUE, true).is_ok());
+        assert_eq!(tournament.first_team, Some(RED));
+    }
+
+    #[test]
+    fn bans_alternate_and_are_limited() {
+        let mut tournament = tournament();
+        assert!(apply_ban(&mut tournament, "nm1", RED, false).is_ok());
+        assert!(matches!(
+            apply_ban(&mut tournament, "NM2", RED, false),
+            Err(AppError::TournamentsNotYourTurn)
+        ));
+        assert!(matches!(
+            apply_ban(&mut tournament, "NM1", BLUE, false),
+            Err(AppError::TournamentsMapUnavailable)
+        ));
+        assert!(matches!(
+            apply_ban(&mut tournament, "TB", BLUE, false),
+            Err(AppError::TournamentsMapUnavailable)
+        ));
+        assert!(apply_ban(&mut tournament, "NM2", BLUE, false).is_ok());
+        assert!(matches!(
+            apply_ban(&mut tournament, "HD1", RED, false),
+            Err(AppError::TournamentsBanLimitReached)
+        ));
+    }
+
+    #[test]
+    fn picks_follow_bans() {
+        let mut tournament = tournament();
+        assert!(matches!(
+            apply_pick(&mut tournament, "HD1", RED, false),
+            Err(AppError::TournamentsBansPending)
+        ));
+        apply_ban(&mut tournament, "NM1", RED, false).unwrap();
+        apply_ban(&mut tournament, "NM2", BLUE, false).unwrap();
+        let pool_map = apply_pick(&mut tournament, "HD1", RED, false).unwrap();
+        assert_eq!(pool_map.beatmap_id, 3);
+        assert_eq!(tournament.picks.len(), 1);
+    }
+
+    #[test]
+    fn undecided_pick_is_replaced_by_its_team() {
+        let mut tournament = tournament();
+        tournament.bans_per_team = 0;
+        apply_pick(&mut tournament, "HD1", RED, false).unwrap();
+        assert!(matches!(
+            apply_pick(&mut tournament, "DT1", BLUE, false),
+            Err(AppError::TournamentsNotYourTurn)
+        ));
+        apply_pick(&mut tournament, "DT1", RED, false).unwrap();
+        assert_eq!(tournament.picks.len(), 1);
+        assert_eq!(tournament.picks[0].code, "DT1");
+    }
+
+    #[test]
+    fn tiebreaker_is_due_at_match_point() {
+        let mut tournament = tournament();
+        tournament.bans_per_team = 0;
+        assert!(matches!(
+            apply_pick(&mut tournament, "TB", RED, false),
+            Err(AppError::TournamentsMapUnavailable)
+        ));
+        tournament.red_points = 1;
+        tournament.blue_points = 1;
+        assert!(matches!(
+            apply_pick(&mut tournament, "HD1", RED, false),
+            Err(AppError::TournamentsTiebreakerRequired)
+        ));
+        assert!(apply_pick(&mut tournament, "TB", BLUE, false).is_ok());
+    }
+
+    #[test]
+    fn decided_match_rejects_picks() {
+        let mut tournament = tournament();
+        tournament.bans_per_team = 0;
+        tournament.red_points = 2;
+        assert!(matches!(
+            apply_pick(&mut tournament, "HD1", BLUE, false),
+            Err(AppError::TournamentsMatchDecided)
+        ));
+    }
+
+    #[test]
+    fn current_pick_matches_the_played_beatmap() {
+        let mut tournament = tournament();
+        tournament.bans_per_team = 0;
+        apply_pick(&mut tournament, "HD1", RED, false).unwrap();
+        assert_eq!(current_pick(&tournament, 3), Some(0));
+        assert_eq!(current_pick(&tournament, 4), None);
+        tournament.picks[0].winning_team = Some(RED);
+        assert_eq!(current_pick(&tournament, 3), None);
+    }
+}
@@ -1,10 +1,11 @@
 use crate::common::context::Context;
 use crate::common::error::{AppError, ServiceResult, unexpected};
 use crate::models::privileges::Privileges;
+use crate::models::punishments::PunishmentKind;
 use crate::models::users::{User, VerifiedStatus};
 use crate::repositories::streams::StreamName;
 use crate::repositories::users;
-use crate::usecases::{messages, sessions, streams};
+use crate::usecases::{messages, punishments, sessions, streams};
 use bancho_protocol::messages::server::{SilenceEnd, UserSilenced};
 use chrono::Utc;
 
@@ -99,6 +100,9 @@ pub async fn ban_user<C: Context>(ctx: &C, user_id: i64) -> ServiceResult<()> {
 
 pub async fn unban_user<C: Context>(ctx: &C, user_id: i64) -> ServiceResult<()> {
     users::unban(ctx, user_id).await?;
+    // unbanning restores public visibility as well, lifting any restriction
+    punishments::lift_active(ctx, user_id, PunishmentKind::Ban).await?;
+    punishments::lift_active(ctx, user_id, PunishmentKind::Restrict).await?;
     users::publish_unban_event(ctx, user_id).await?;
     Ok(())
 }
@@ -111,6 +115,18 @@ pub async fn restrict_user<C: Context>(ctx: &C, user_id: i64) -> ServiceResult<(
 
 pub async fn unrestrict_user<C: Context>(ctx: &C, user_id: i64) -> ServiceResult<()> {
     users::unrestrict(ctx, user_id).await?;
+    punishments::lift_active(ctx, user_id, PunishmentKind::Restrict).await?;
+    users::publish_unban_event(ctx, user_id).await?;
+    Ok(())
+}
+
+/// Restores privileges taken away by a punishment that has been lifted.
+pub async fn grant_privileges<C: Context>(
+    ctx: &C,
+    user_id: i64,
+    privileges: Privileges,
+) -> ServiceResult<()> {
+    users::grant_privileges(ctx, user_id, privileges).await?;
     users::publish_unban_event(ctx, user_id).await?;
     Ok(())
 }
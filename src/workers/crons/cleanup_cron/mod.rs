@@ -1,9 +1,12 @@
 pub mod tasks;
 
+use crate::common::metrics;
 use crate::settings::AppSettings;
 use crate::{cron_tasks, lifecycle};
 use tasks::cleanup_sessions::cleanup_sessions;
 use tasks::cleanup_streams::cleanup_streams;
+use tasks::expire_punishments::expire_punishments;
+use tasks::record_metrics::record_metrics;
 
 pub async fn serve(settings: &AppSettings) -> anyhow::Result<()> {
     let ctx = lifecycle::initialize_state(settings).await?;
@@ -11,6 +14,11 @@ pub async fn serve(settings: &AppSettings) -> anyhow::Result<()> {
         &ctx,
         cleanup_sessions,
         cleanup_streams,
+        expire_punishments,
+        record_metrics,
     }
+
+    // the cron exits right away, so metrics can only be pushed
+    metrics::push().await;
     Ok(())
 }
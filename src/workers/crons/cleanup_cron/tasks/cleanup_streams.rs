@@ -1,18 +1,17 @@
 use crate::common::context::Context;
 use crate::common::error::ServiceResult;
+use crate::models::bancho_settings::BanchoSettings;
 use crate::repositories::streams::StreamName;
-use crate::usecases::streams;
+use crate::usecases::{bancho_settings, streams};
 use chrono::{TimeDelta, Utc};
 use std::ops::Add;
 use tracing::{error, info};
 
-const MESSAGE_TTL: usize = 5 * 60;
-const CLEAR_STREAM_INTERVAL: i64 = 10 * 60;
-
 pub async fn cleanup_streams<C: Context>(ctx: &C) -> ServiceResult<()> {
+    let settings = bancho_settings::fetch_current(ctx).await?;
     let streams = streams::fetch_all(ctx).await?;
     for key in streams {
-        match cleanup_stream(ctx, key).await {
+        match cleanup_stream(ctx, &settings, key).await {
             Ok((key, count)) => match count {
                 usize::MAX => info!("Cleared Stream {key}"),
                 count => info!("Trimmed {count} messages from {key}"),
@@ -24,17 +23,21 @@ pub async fn cleanup_streams<C: Context>(ctx: &C) -> ServiceResult<()> {
     Ok(())
 }
 
-async fn cleanup_stream<C: Context>(ctx: &C, key: String) -> ServiceResult<(String, usize)> {
+async fn cleanup_stream<C: Context>(
+    ctx: &C,
+    settings: &BanchoSettings,
+    key: String,
+) -> ServiceResult<(String, usize)> {
     let stream = StreamName::from_key(&key)?;
     let timestamp = streams::get_latest_message_timestamp(ctx, stream).await?;
     let now = Utc::now();
 
     // If there was no message broadcasted in the interval, fully delete the stream
-    if timestamp.add(TimeDelta::seconds(CLEAR_STREAM_INTERVAL)) < now {
+    if timestamp.add(TimeDelta::seconds(settings.stream_clear_interval as _)) < now {
         streams::clear_stream(ctx, stream).await?;
         Ok((key, usize::MAX))
     } else {
-        let count = streams::trim_stream(ctx, stream, MESSAGE_TTL).await?;
+        let count = streams::trim_stream(ctx, stream, settings.stream_message_ttl as _).await?;
         Ok((key, count))
     }
 }
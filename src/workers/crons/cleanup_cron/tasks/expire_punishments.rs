@@ -0,0 +1,42 @@
+use crate::adapters::discord;
+use crate::common::context::Context;
+use crate::common::error::ServiceResult;
+use crate::common::website;
+use crate::models::punishments::{Punishment, PunishmentKind};
+use crate::usecases::{punishments, users};
+use tracing::{error, info};
+
+pub async fn expire_punishments<C: Context>(ctx: &C) -> ServiceResult<()> {
+    for punishment in punishments::fetch_expired(ctx).await? {
+        match expire_punishment(ctx, &punishment).await {
+            Ok(()) => info!(
+                punishment_id = punishment.id,
+                user_id = punishment.user_id,
+                "Punishment expired"
+            ),
+            Err(e) => error!(
+                punishment_id = punishment.id,
+                user_id = punishment.user_id,
+                "Failed to lift expired punishment: {e:?}"
+            ),
+        }
+    }
+    Ok(())
+}
+
+async fn expire_punishment<C: Context>(ctx: &C, punishment: &Punishment) -> ServiceResult<()> {
+    let user = users::fetch_one(ctx, punishment.user_id).await?;
+    punishments::expire(ctx, punishment).await?;
+    let (title, description) = match punishment.kind {
+        PunishmentKind::Restrict => ("Restriction Expired", "restriction"),
+        PunishmentKind::Ban => ("Ban Expired", "ban"),
+    };
+
+    let target_profile = website::get_profile_link(user.user_id);
+    let log_message = format!(
+        "The {description} of [{}]({}) has expired, it was given for: {}",
+        user.username, target_profile, punishment.reason
+    );
+    let _ = discord::send_logs_blue_embed(title, &log_message, None).await;
+    Ok(())
+}
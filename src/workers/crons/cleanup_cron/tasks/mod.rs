@@ -1,2 +1,4 @@
 pub mod cleanup_sessions;
 pub mod cleanup_streams;
+pub mod expire_punishments;
+pub mod record_metrics;
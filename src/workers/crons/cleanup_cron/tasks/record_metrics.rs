@@ -0,0 +1,12 @@
+use crate::common::context::Context;
+use crate::common::error::ServiceResult;
+use crate::common::metrics;
+use crate::usecases;
+
+/// Records gauges that are too expensive to collect on every scrape.
+pub async fn record_metrics<C: Context>(ctx: &C) -> ServiceResult<()> {
+    usecases::metrics::record_online_state(ctx).await?;
+    usecases::metrics::record_stream_lengths(ctx).await?;
+    metrics::record_pool_utilization(ctx);
+    Ok(())
+}
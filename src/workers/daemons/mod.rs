@@ -1 +1,2 @@
+pub mod multiplayer_timers;
 pub mod pubsub_consumer;
@@ -0,0 +1,36 @@
+use crate::lifecycle;
+use crate::settings::AppSettings;
+use crate::usecases::{maintenance, multiplayer};
+use std::time::Duration;
+use tokio::time::MissedTickBehavior;
+use tracing::{error, info};
+
+/// Polled more often than once a second so that no countdown announcement gets skipped.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// The maintenance countdown tolerates late polls, so it doesn't need to hit the database as often.
+const MAINTENANCE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// TODO: change return type to anyhow::Result<!> when its stabilized
+pub async fn serve(settings: &AppSettings) -> anyhow::Result<()> {
+    let ctx = lifecycle::initialize_state(settings).await?;
+    info!("Processing multiplayer timers and scheduled maintenance");
+
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    let mut maintenance_interval = tokio::time::interval(MAINTENANCE_POLL_INTERVAL);
+    maintenance_interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                if let Err(e) = multiplayer::process_timers(&ctx).await {
+                    error!("Error processing multiplayer timers: {e:?}");
+                }
+            }
+            _ = maintenance_interval.tick() => {
+                if let Err(e) = maintenance::process_window(&ctx).await {
+                    error!("Error processing scheduled maintenance: {e:?}");
+                }
+            }
+        }
+    }
+}
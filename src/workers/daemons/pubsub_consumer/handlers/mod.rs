@@ -2,6 +2,8 @@ pub mod ban;
 pub mod change_username;
 pub mod disconnect;
 pub mod notification;
+pub mod reload_chat_filters;
+pub mod reload_settings;
 pub mod silence;
 pub mod unban;
 pub mod update_cached_stats;
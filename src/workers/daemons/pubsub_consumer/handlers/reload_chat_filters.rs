@@ -0,0 +1,13 @@
+use crate::common::error::ServiceResult;
+use crate::common::state::AppState;
+use crate::usecases::chat_filters;
+use redis::Msg;
+use tracing::info;
+
+pub async fn handle(ctx: AppState, msg: Msg) -> ServiceResult<()> {
+    let payload: String = msg.get_payload()?;
+    if let Some(version) = chat_filters::handle_reload_request(&ctx, &payload).await? {
+        info!(version, "Successfully requested chat filters reload");
+    }
+    Ok(())
+}
@@ -1,13 +1,15 @@
 pub mod handlers;
 
+use crate::common::metrics;
 use crate::lifecycle;
 use crate::settings::AppSettings;
 use crate::workers::daemons::pubsub_consumer::handlers::{
-    ban, change_username, disconnect, notification, silence, unban, update_cached_stats, wipe,
+    ban, change_username, disconnect, notification, reload_chat_filters, reload_settings, silence,
+    unban, update_cached_stats, wipe,
 };
 use tracing::{error, info, warn};
 
-pub const PUBSUB_CHANNELS: [&str; 8] = [
+pub const PUBSUB_CHANNELS: [&str; 10] = [
     "peppy:ban",
     "peppy:unban",
     "peppy:silence",
@@ -16,6 +18,8 @@ pub const PUBSUB_CHANNELS: [&str; 8] = [
     "peppy:change_username",
     "peppy:update_cached_stats",
     "peppy:wipe",
+    "peppy:reload_chat_filters",
+    "peppy:reload_settings",
 ];
 
 // TODO: change return type to anyhow::Result<!> when its stabilized
@@ -29,6 +33,7 @@ pub async fn serve(settings: &AppSettings) -> anyhow::Result<()> {
     }
 
     let state = lifecycle::initialize_state(&settings).await?;
+    metrics::spawn_exporter(settings).await?;
     loop {
         let msg = pubsub.get_message()?;
         let task_state = state.clone();
@@ -45,14 +50,17 @@ pub async fn serve(settings: &AppSettings) -> anyhow::Result<()> {
                 "peppy:change_username" => change_username::handle(ctx, msg).await,
                 "peppy:update_cached_stats" => update_cached_stats::handle(ctx, msg).await,
                 "peppy:wipe" => wipe::handle(ctx, msg).await,
+                "peppy:reload_chat_filters" => reload_chat_filters::handle(ctx, msg).await,
+                "peppy:reload_settings" => reload_settings::handle(ctx, msg).await,
                 _ => {
                     warn!("Unknown pubsub channel message: {}", channel_name);
                     Ok(())
                 }
             };
-            if let Err(e) = handler_result {
+            if let Err(e) = &handler_result {
                 error!(channel_name, "Error handling pubsub event: {e:?}");
             }
+            metrics::record_pubsub_message(channel_name, handler_result.is_ok());
         });
     }
 }